@@ -0,0 +1,199 @@
+//! # Agenda
+//!
+//! Lays out one day's tasks for time blocking: timed tasks in order of their
+//! due time, with an end time derived from their duration, followed by the
+//! tasks due that day without a time. Timed tasks whose blocks intersect are
+//! flagged as overlapping.
+
+use crate::error::{Result, TodoError};
+use crate::models::{Duration, TaskOutput};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime};
+use serde::Serialize;
+
+/// A task placed on the agenda
+#[derive(Debug, Clone, Serialize)]
+pub struct AgendaEntry {
+    pub id: String,
+    pub content: String,
+    pub project_name: Option<String>,
+    /// Local start time (`HH:MM`), absent for tasks without a time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<String>,
+    /// Local end time, for timed tasks with a duration
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<String>,
+    pub duration: Option<Duration>,
+    /// IDs of the timed tasks whose blocks intersect this one
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub overlaps_with: Vec<String>,
+}
+
+/// One day's tasks
+#[derive(Debug, Clone, Serialize)]
+pub struct Agenda {
+    pub date: String,
+    pub timed: Vec<AgendaEntry>,
+    pub all_day: Vec<AgendaEntry>,
+}
+
+/// Parses `--date`: `today`, `tomorrow`, `yesterday` or `YYYY-MM-DD`
+pub fn parse_date(value: &str, today: NaiveDate) -> Result<NaiveDate> {
+    match value.trim().to_lowercase().as_str() {
+        "today" => Ok(today),
+        "tomorrow" => Ok(today + chrono::Duration::days(1)),
+        "yesterday" => Ok(today - chrono::Duration::days(1)),
+        other => NaiveDate::parse_from_str(other, "%Y-%m-%d").map_err(|_| {
+            TodoError::InvalidInput(format!(
+                "Invalid date '{}'. Use today, tomorrow, yesterday or YYYY-MM-DD.",
+                value
+            ))
+        }),
+    }
+}
+
+/// Local time of a due date with a time; UTC values (ending in `Z`) are converted
+fn local_datetime(value: &str) -> Option<NaiveDateTime> {
+    if let Ok(utc) = DateTime::parse_from_rfc3339(value) {
+        return Some(utc.with_timezone(&Local).naive_local());
+    }
+    value.parse().ok()
+}
+
+fn entry(
+    task: &TaskOutput,
+    start: Option<NaiveDateTime>,
+    end: Option<NaiveDateTime>,
+) -> AgendaEntry {
+    AgendaEntry {
+        id: task.id.clone(),
+        content: task.content.clone(),
+        project_name: task.project_name.clone(),
+        start: start.map(|t| t.format("%H:%M").to_string()),
+        end: end.map(|t| t.format("%H:%M").to_string()),
+        duration: task.duration.clone(),
+        overlaps_with: Vec::new(),
+    }
+}
+
+/// Builds the agenda for `date` from the active tasks
+pub fn build(tasks: &[TaskOutput], date: NaiveDate) -> Agenda {
+    let day = date.format("%Y-%m-%d").to_string();
+    let mut timed = Vec::new();
+    let mut all_day = Vec::new();
+
+    for task in tasks.iter().filter(|t| !t.is_completed) {
+        // Timed tasks carry their time in `datetime`, or in `date` itself
+        let due_time = task
+            .due_datetime
+            .as_deref()
+            .or(task.due_date.as_deref().filter(|d| d.contains('T')))
+            .and_then(local_datetime);
+        match due_time {
+            Some(start) if start.date() == date => {
+                let minutes = task.duration.as_ref().map_or(0, Duration::as_minutes);
+                let end = start + chrono::Duration::minutes(minutes);
+                let shown_end = task.duration.is_some().then_some(end);
+                timed.push((start, end, entry(task, Some(start), shown_end)));
+            }
+            Some(_) => {}
+            None if task.due_date.as_deref() == Some(day.as_str()) => {
+                all_day.push(entry(task, None, None));
+            }
+            None => {}
+        }
+    }
+
+    timed.sort_by_key(|(start, end, _)| (*start, *end));
+
+    // Sorted by start, so `a` overlaps a later `b` when `b` starts before `a` ends
+    for i in 0..timed.len() {
+        for j in i + 1..timed.len() {
+            let (a_start, a_end) = (timed[i].0, timed[i].1);
+            let b_start = timed[j].0;
+            if b_start < a_end || b_start == a_start {
+                let (a_id, b_id) = (timed[i].2.id.clone(), timed[j].2.id.clone());
+                timed[i].2.overlaps_with.push(b_id);
+                timed[j].2.overlaps_with.push(a_id);
+            }
+        }
+    }
+
+    Agenda {
+        date: day,
+        timed: timed.into_iter().map(|(_, _, e)| e).collect(),
+        all_day,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Task;
+
+    fn task(id: &str, due: serde_json::Value, duration: Option<Duration>) -> TaskOutput {
+        let task: Task = serde_json::from_value(serde_json::json!({
+            "id": id,
+            "content": format!("Task {}", id),
+            "project_id": null,
+            "due": due,
+            "is_completed": false,
+            "created_at": "",
+            "order": 0,
+            "priority": 1,
+            "duration": duration
+        }))
+        .unwrap();
+        TaskOutput::from_task(task, None)
+    }
+
+    fn day() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 1, 20).unwrap()
+    }
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(parse_date("today", day()).unwrap(), day());
+        assert_eq!(
+            parse_date("Tomorrow", day()).unwrap(),
+            NaiveDate::from_ymd_opt(2026, 1, 21).unwrap()
+        );
+        assert_eq!(parse_date("2026-01-20", day()).unwrap(), day());
+        assert!(parse_date("next week", day()).is_err());
+    }
+
+    #[test]
+    fn test_build_orders_timed_tasks_and_flags_overlaps() {
+        let tasks = vec![
+            task(
+                "review",
+                serde_json::json!({"date": "2026-01-20", "datetime": "2026-01-20T09:30:00"}),
+                Some(Duration::minutes(30)),
+            ),
+            task(
+                "standup",
+                serde_json::json!({"date": "2026-01-20T09:00:00"}),
+                Some(Duration::minutes(45)),
+            ),
+            task(
+                "lunch",
+                serde_json::json!({"date": "2026-01-20", "datetime": "2026-01-20T12:00:00"}),
+                None,
+            ),
+            task("rent", serde_json::json!({"date": "2026-01-20"}), None),
+            task("later", serde_json::json!({"date": "2026-01-21"}), None),
+        ];
+
+        let agenda = build(&tasks, day());
+        assert_eq!(agenda.date, "2026-01-20");
+        let ids: Vec<&str> = agenda.timed.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, ["standup", "review", "lunch"]);
+        assert_eq!(agenda.timed[0].start.as_deref(), Some("09:00"));
+        assert_eq!(agenda.timed[0].end.as_deref(), Some("09:45"));
+        assert_eq!(agenda.timed[0].overlaps_with, ["review"]);
+        assert_eq!(agenda.timed[1].overlaps_with, ["standup"]);
+        assert!(agenda.timed[2].end.is_none());
+        assert!(agenda.timed[2].overlaps_with.is_empty());
+        assert_eq!(agenda.all_day.len(), 1);
+        assert_eq!(agenda.all_day[0].id, "rent");
+    }
+}
@@ -0,0 +1,456 @@
+//! # Backup
+//!
+//! `export --all` writes the projects, sections, tasks, labels, filters and
+//! comments of the account as JSON: the synced resources (the cache layout)
+//! plus a `version` header. `import` recreates them with batched commands,
+//! chaining new resources by temp id, optionally below a new root project.
+//!
+//! Restored resources get new ids. Completed tasks are not part of the synced
+//! data and therefore not backed up; labels and filters that already exist by
+//! name are kept as they are. Archived projects are read separately and backed
+//! up without their sections, tasks and comments, which the Sync API does not
+//! return; the backup lists such gaps under `warnings`.
+
+use crate::error::{Result, TodoError};
+use crate::sync::{
+    CacheData, Command, CommandKind, FilterAddArgs, IdArgs, ItemAddArgs, LabelAddArgs, NoteAddArgs,
+    ProjectAddArgs, SectionAddArgs,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Version of the backup layout written by this build
+pub const VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+pub struct Backup {
+    pub version: u32,
+    /// RFC 3339 timestamp
+    pub exported_at: String,
+    #[serde(flatten)]
+    pub data: CacheData,
+    /// Resources the backup leaves out
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+impl Backup {
+    /// Wraps synced resources, leaving out deleted ones and account details
+    pub fn new(mut data: CacheData, exported_at: String) -> Self {
+        data.projects.retain(|p| !p.is_deleted);
+        data.sections.retain(|s| !s.is_deleted);
+        data.items.retain(|t| !t.is_deleted);
+        data.notes.retain(|n| !n.is_deleted);
+        data.collaborators.clear();
+        data.collaborator_states.clear();
+        data.user = None;
+        Self {
+            version: VERSION,
+            exported_at,
+            data,
+            warnings: Vec::new(),
+        }
+    }
+
+    pub fn parse(json: &str) -> Result<Self> {
+        let backup: Self = serde_json::from_str(json)
+            .map_err(|e| TodoError::InvalidInput(format!("Invalid backup: {}", e)))?;
+        if backup.version > VERSION {
+            return Err(TodoError::InvalidInput(format!(
+                "Backup version {} is newer than supported version {}",
+                backup.version, VERSION
+            )));
+        }
+        Ok(backup)
+    }
+}
+
+/// Number of resources an import creates
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct Summary {
+    pub projects: usize,
+    pub sections: usize,
+    pub tasks: usize,
+    pub labels: usize,
+    pub filters: usize,
+    pub comments: usize,
+    /// Labels and filters that already exist by name
+    pub existing: Vec<String>,
+}
+
+/// Commands recreating a backup
+pub struct Restore {
+    pub commands: Vec<Command>,
+    pub summary: Summary,
+    /// Temp id of the new root project
+    pub root: Option<String>,
+}
+
+/// The account a backup is restored into
+pub struct Target<'a> {
+    /// Name of a new project holding all restored projects
+    pub into: Option<&'a str>,
+    /// Inbox of the account, for sections of the backed up Inbox
+    pub inbox_id: Option<&'a str>,
+    pub labels: &'a [&'a str],
+    pub filters: &'a [&'a str],
+}
+
+/// Builds the commands recreating `backup` in `target`
+pub fn restore(backup: &Backup, target: &Target) -> Restore {
+    let data = &backup.data;
+    let mut commands = Vec::new();
+    let mut summary = Summary::default();
+    // Backed up id -> temp id
+    let mut ids: HashMap<&str, String> = HashMap::new();
+    // Sent last, as nothing can be added to archived projects and sections
+    let mut project_archives = Vec::new();
+    let mut section_archives = Vec::new();
+
+    let root = target.into.map(|name| {
+        summary.projects += 1;
+        add(
+            &mut commands,
+            CommandKind::ProjectAdd(ProjectAddArgs::new(name.to_string())),
+        )
+    });
+    // Inbox content goes to the root project, or the Inbox of the account
+    let inbox = data.projects.iter().find(|p| p.inbox_project);
+    let inbox_target = root.clone().or(target.inbox_id.map(str::to_string));
+
+    let mut projects: Vec<_> = data.projects.iter().filter(|p| !p.inbox_project).collect();
+    projects.sort_by_key(|p| p.sort_order);
+    for project in parents_first(projects, |p| &p.id, |p| p.parent_id.as_deref()) {
+        let parent_id = match project.parent_id.as_deref() {
+            Some(parent) => ids.get(parent).cloned(),
+            None => root.clone(),
+        };
+        let args = ProjectAddArgs::new(project.name.clone())
+            .parent_id(parent_id)
+            .color(Some(project.color.clone()).filter(|c| !c.is_empty()))
+            .favorite(project.favorite.then_some(true))
+            .view_style(Some(project.view_style.clone()).filter(|v| !v.is_empty()));
+        let temp_id = add(&mut commands, CommandKind::ProjectAdd(args));
+        if project.is_archived {
+            project_archives.push(Command::new(CommandKind::ProjectArchive(IdArgs::new(
+                &temp_id,
+            ))));
+        }
+        ids.insert(&project.id, temp_id);
+        summary.projects += 1;
+    }
+    let project_id = |id: Option<&str>| match id {
+        Some(id) if inbox.is_some_and(|i| i.id == id) => inbox_target.clone(),
+        Some(id) => ids.get(id).cloned(),
+        None => inbox_target.clone(),
+    };
+
+    let mut sections: Vec<_> = data.sections.iter().collect();
+    sections.sort_by_key(|s| s.order);
+    let mut section_ids = HashMap::new();
+    for section in sections {
+        let Some(project_id) = project_id(Some(&section.project_id)) else {
+            continue;
+        };
+        let args = SectionAddArgs::new(section.name.clone(), project_id);
+        let temp_id = add(&mut commands, CommandKind::SectionAdd(args));
+        if section.is_archived {
+            section_archives.push(Command::new(CommandKind::SectionArchive(IdArgs::new(
+                &temp_id,
+            ))));
+        }
+        section_ids.insert(section.id.as_str(), temp_id);
+        summary.sections += 1;
+    }
+
+    for label in &data.labels {
+        if target
+            .labels
+            .iter()
+            .any(|l| l.eq_ignore_ascii_case(&label.name))
+        {
+            summary.existing.push(format!("label {}", label.name));
+            continue;
+        }
+        let args = LabelAddArgs::new(label.name.clone())
+            .color(Some(label.color.clone()).filter(|c| !c.is_empty()));
+        add(&mut commands, CommandKind::LabelAdd(args));
+        summary.labels += 1;
+    }
+
+    let mut items: Vec<_> = data.items.iter().collect();
+    items.sort_by_key(|t| t.order);
+    let mut item_ids: HashMap<&str, String> = HashMap::new();
+    for item in parents_first(items, |t| &t.id, |t| t.parent_id.as_deref()) {
+        let args = ItemAddArgs::new(item.content.clone())
+            .description(item.description.clone().filter(|d| !d.is_empty()))
+            .project_id(project_id(item.project_id.as_deref()))
+            .section_id(
+                item.section_id
+                    .as_deref()
+                    .and_then(|s| section_ids.get(s).cloned()),
+            )
+            .parent_id(
+                item.parent_id
+                    .as_deref()
+                    .and_then(|p| item_ids.get(p).cloned()),
+            )
+            .due_string(item.due.as_ref().and_then(|d| d.to_due_string()))
+            .priority((2..=4).contains(&item.priority).then_some(item.priority))
+            .labels((!item.labels.is_empty()).then(|| item.labels.clone()))
+            .duration(item.duration.clone().map(Into::into));
+        let temp_id = add(&mut commands, CommandKind::ItemAdd(args));
+        if item.is_completed {
+            commands.push(Command::new(CommandKind::ItemComplete(IdArgs::new(
+                &temp_id,
+            ))));
+        }
+        item_ids.insert(&item.id, temp_id);
+        summary.tasks += 1;
+    }
+
+    for note in &data.notes {
+        let Some(item_id) = item_ids.get(note.item_id.as_str()) else {
+            continue;
+        };
+        let args = NoteAddArgs {
+            item_id: item_id.clone(),
+            content: note.content.clone(),
+        };
+        add(&mut commands, CommandKind::NoteAdd(args));
+        summary.comments += 1;
+    }
+
+    for filter in &data.filters {
+        if target
+            .filters
+            .iter()
+            .any(|f| f.eq_ignore_ascii_case(&filter.name))
+        {
+            summary.existing.push(format!("filter {}", filter.name));
+            continue;
+        }
+        let args = FilterAddArgs::new(filter.name.clone(), filter.query.clone());
+        add(&mut commands, CommandKind::FilterAdd(args));
+        summary.filters += 1;
+    }
+
+    // Subprojects before their parents
+    commands.extend(section_archives);
+    commands.extend(project_archives.into_iter().rev());
+
+    Restore {
+        commands,
+        summary,
+        root,
+    }
+}
+
+/// Appends a creating command and returns its temp id
+pub(crate) fn add(commands: &mut Vec<Command>, kind: CommandKind) -> String {
+    let command = Command::new(kind);
+    let temp_id = command.temp_id.clone().unwrap_or_default();
+    commands.push(command);
+    temp_id
+}
+
+/// Orders `nodes` as a tree: each node followed by its children, keeping
+/// the given order among siblings. Nodes whose parent is missing count as
+/// top level.
+fn parents_first<'a, T>(
+    nodes: Vec<&'a T>,
+    id: impl Fn(&T) -> &str,
+    parent: impl Fn(&T) -> Option<&str>,
+) -> Vec<&'a T> {
+    let known: HashSet<&str> = nodes.iter().map(|n| id(n)).collect();
+    let mut children: HashMap<&str, Vec<&'a T>> = HashMap::new();
+    let mut stack = Vec::new();
+    for node in nodes.iter().rev() {
+        match parent(node).filter(|p| known.contains(p)) {
+            Some(p) => children.entry(p).or_default().insert(0, *node),
+            None => stack.push(*node),
+        }
+    }
+
+    let mut ordered = Vec::with_capacity(nodes.len());
+    while let Some(node) = stack.pop() {
+        ordered.push(node);
+        if let Some(kids) = children.remove(id(node)) {
+            stack.extend(kids.into_iter().rev());
+        }
+    }
+    // Nodes in a parent cycle are never reached from the top level
+    let placed: HashSet<&str> = ordered.iter().map(|n| id(n)).collect();
+    ordered.extend(nodes.into_iter().filter(|n| !placed.contains(id(n))));
+    ordered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn backup() -> Backup {
+        let data: CacheData = serde_json::from_value(json!({
+            "projects": [
+                {"id": "inbox", "name": "Inbox", "inbox_project": true},
+                {"id": "child", "name": "Q1", "parent_id": "work", "sort_order": 1},
+                {"id": "work", "name": "Work", "color": "red", "sort_order": 2},
+                {"id": "gone", "name": "Gone", "is_deleted": true}
+            ],
+            "sections": [
+                {"id": "s1", "project_id": "work", "name": "Doing", "order": 1, "created_at": ""},
+                {"id": "s2", "project_id": "inbox", "name": "Later", "order": 2, "created_at": ""}
+            ],
+            "items": [
+                {"id": "sub", "content": "Draft", "project_id": "work", "parent_id": "t1", "child_order": 1},
+                {"id": "t1", "content": "Report", "project_id": "work", "section_id": "s1",
+                 "priority": 4, "labels": ["deep"], "child_order": 2,
+                 "due": {"date": "2026-01-20", "is_recurring": true, "string": "every monday"}},
+                {"id": "t2", "content": "Milk", "child_order": 3}
+            ],
+            "labels": [
+                {"id": "l1", "name": "deep", "color": "blue"},
+                {"id": "l2", "name": "Home", "color": "green"}
+            ],
+            "filters": [{"id": "f1", "name": "Today", "query": "today"}],
+            "notes": [
+                {"id": "n1", "item_id": "t1", "content": "See doc"},
+                {"id": "n2", "item_id": "missing", "content": "Orphan"}
+            ],
+            "user": {"id": "u1", "email": "me@example.com", "full_name": "Me"}
+        }))
+        .unwrap();
+        Backup::new(data, "2026-10-19T00:00:00Z".to_string())
+    }
+
+    fn args(command: &Command) -> serde_json::Value {
+        serde_json::to_value(&command.kind).unwrap()["args"].clone()
+    }
+
+    #[test]
+    fn test_backup_roundtrip() {
+        let backup = backup();
+        assert_eq!(backup.data.projects.len(), 3);
+        assert!(backup.data.user.is_none());
+
+        let json = serde_json::to_string(&backup).unwrap();
+        assert!(json.starts_with("{\"version\":1,\"exported_at\""));
+        let parsed = Backup::parse(&json).unwrap();
+        assert_eq!(parsed.data.items.len(), 3);
+
+        let newer = json.replacen("\"version\":1", "\"version\":99", 1);
+        let err = Backup::parse(&newer).err().unwrap();
+        assert!(err.to_string().contains("newer"));
+        assert!(Backup::parse("[]").is_err());
+    }
+
+    #[test]
+    fn test_restore_chains_temp_ids() {
+        let restore = restore(
+            &backup(),
+            &Target {
+                into: None,
+                inbox_id: Some("my-inbox"),
+                labels: &["Home"],
+                filters: &[],
+            },
+        );
+        let types: Vec<_> = restore.commands.iter().map(|c| c.command_type()).collect();
+        assert_eq!(
+            types,
+            [
+                "project_add",
+                "project_add",
+                "section_add",
+                "section_add",
+                "label_add",
+                "item_add",
+                "item_add",
+                "item_add",
+                "note_add",
+                "filter_add"
+            ]
+        );
+        let temp_id = |i: usize| restore.commands[i].temp_id.clone().unwrap();
+
+        // Work is created before its child project Q1
+        assert_eq!(args(&restore.commands[0])["name"], "Work");
+        assert_eq!(args(&restore.commands[0])["color"], "red");
+        assert_eq!(args(&restore.commands[1])["parent_id"], temp_id(0));
+        assert_eq!(args(&restore.commands[3])["project_id"], "my-inbox");
+
+        // The parent task comes first, the subtask points at it
+        let report = args(&restore.commands[5]);
+        assert_eq!(report["content"], "Report");
+        assert_eq!(report["section_id"], temp_id(2));
+        assert_eq!(report["due_string"], "every monday");
+        assert_eq!(report["priority"], 4);
+        assert_eq!(args(&restore.commands[6])["parent_id"], temp_id(5));
+        assert_eq!(args(&restore.commands[7])["project_id"], "my-inbox");
+        assert_eq!(args(&restore.commands[8])["item_id"], temp_id(5));
+
+        assert_eq!(
+            restore.summary,
+            Summary {
+                projects: 2,
+                sections: 2,
+                tasks: 3,
+                labels: 1,
+                filters: 1,
+                comments: 1,
+                existing: vec!["label Home".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn test_restore_archives_after_adding_contents() {
+        let mut backup = backup();
+        backup.data.projects[2].is_archived = true;
+        backup.data.sections[0].is_archived = true;
+        let restore = restore(
+            &backup,
+            &Target {
+                into: None,
+                inbox_id: Some("my-inbox"),
+                labels: &[],
+                filters: &[],
+            },
+        );
+        let types: Vec<_> = restore.commands.iter().map(|c| c.command_type()).collect();
+        let last_add = types.iter().rposition(|t| t.ends_with("_add")).unwrap();
+        assert_eq!(
+            &types[last_add + 1..],
+            ["section_archive", "project_archive"]
+        );
+        let work = restore.commands[0].temp_id.clone().unwrap();
+        assert_eq!(args(restore.commands.last().unwrap())["id"], work);
+    }
+
+    #[test]
+    fn test_restore_into_root_project() {
+        let restore = restore(
+            &backup(),
+            &Target {
+                into: Some("Restored"),
+                inbox_id: Some("my-inbox"),
+                labels: &[],
+                filters: &["today"],
+            },
+        );
+        let root = restore.root.clone().unwrap();
+        assert_eq!(restore.commands[0].temp_id.as_ref(), Some(&root));
+        assert_eq!(args(&restore.commands[0])["name"], "Restored");
+        assert_eq!(args(&restore.commands[1])["parent_id"], root.as_str());
+
+        let milk = restore
+            .commands
+            .iter()
+            .find(|c| args(c)["content"] == "Milk")
+            .unwrap();
+        assert_eq!(args(milk)["project_id"], root.as_str());
+        assert_eq!(restore.summary.projects, 3);
+        assert_eq!(restore.summary.existing, vec!["filter Today"]);
+    }
+}
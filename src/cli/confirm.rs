@@ -0,0 +1,121 @@
+use std::io::{BufRead, IsTerminal, Write};
+
+use crate::error::{Result, TodoError};
+
+/// Decides whether destructive commands need confirmation, and asks for it
+pub struct Confirmation {
+    assume_yes: bool,
+    required: bool,
+    interactive: bool,
+}
+
+impl Confirmation {
+    /// `assume_yes` comes from `--yes`, `required` from the `require_confirmation` config option
+    pub fn new(assume_yes: bool, required: bool) -> Self {
+        Self {
+            assume_yes,
+            required,
+            interactive: std::io::stdin().is_terminal() && std::io::stdout().is_terminal(),
+        }
+    }
+
+    /// Skips every prompt, as with `--yes`
+    pub fn assume_yes() -> Self {
+        Self {
+            assume_yes: true,
+            required: false,
+            interactive: false,
+        }
+    }
+
+    /// A confirmation as seen by scripts and agents (for testing)
+    #[cfg(test)]
+    pub fn non_interactive(assume_yes: bool, required: bool) -> Self {
+        Self {
+            assume_yes,
+            required,
+            interactive: false,
+        }
+    }
+
+    /// Whether [`confirm`](Self::confirm) would prompt or refuse, so callers
+    /// can skip looking up the details shown in the prompt
+    pub fn needed(&self) -> bool {
+        !self.assume_yes && (self.interactive || self.required)
+    }
+
+    /// Asks `question` on a terminal. Without a terminal the action proceeds,
+    /// unless confirmation is required by configuration.
+    pub fn confirm(&self, question: &str) -> Result<()> {
+        if !self.needed() {
+            return Ok(());
+        }
+        if !self.interactive {
+            return Err(TodoError::InvalidInput(format!(
+                "{} Confirmation is required; re-run with --yes to proceed",
+                question
+            )));
+        }
+
+        eprint!("{} [y/N] ", question);
+        std::io::stderr().flush()?;
+        let mut answer = String::new();
+        std::io::stdin().lock().read_line(&mut answer)?;
+        match answer.trim().to_lowercase().as_str() {
+            "y" | "yes" => Ok(()),
+            _ => Err(TodoError::InvalidInput("Aborted".to_string())),
+        }
+    }
+
+    /// Like [`confirm`](Self::confirm), but without a terminal the action
+    /// only proceeds with `--yes`, even when confirmation is not required
+    pub fn confirm_always(&self, question: &str) -> Result<()> {
+        Self {
+            required: true,
+            ..*self
+        }
+        .confirm(question)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_non_interactive_proceeds_by_default() {
+        let confirm = Confirmation::non_interactive(false, false);
+        assert!(!confirm.needed());
+        assert!(confirm.confirm("Delete project 'Work'?").is_ok());
+    }
+
+    #[test]
+    fn test_required_confirmation_refuses_without_yes() {
+        let err = Confirmation::non_interactive(false, true)
+            .confirm("Delete project 'Work' and its 3 task(s)?")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Delete project 'Work' and its 3 task(s)?"));
+        assert!(err.contains("--yes"));
+    }
+
+    #[test]
+    fn test_confirm_always_refuses_without_yes() {
+        let err = Confirmation::non_interactive(false, false)
+            .confirm_always("Delete 3 task(s) matching 'p1'?")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Delete 3 task(s)"));
+        assert!(Confirmation::non_interactive(true, false)
+            .confirm_always("Delete?")
+            .is_ok());
+    }
+
+    #[test]
+    fn test_yes_overrides_required_confirmation() {
+        let confirm = Confirmation::non_interactive(true, true);
+        assert!(!confirm.needed());
+        assert!(confirm.confirm("Delete?").is_ok());
+        assert!(!Confirmation::assume_yes().needed());
+    }
+}
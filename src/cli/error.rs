@@ -41,6 +41,10 @@ pub fn get_error_message(error: &TodoError) -> String {
         TodoError::Io(e) => {
             format!("System IO Error: {}", e)
         }
+        TodoError::Template(msg) => {
+            format!("Template Error: {}", msg)
+        }
+        TodoError::DryRun => "Dry run: no changes were sent to Todoist.".to_string(),
     }
 }
 
@@ -118,6 +122,13 @@ mod tests {
         assert!(msg.contains("Invalid Input: test input"));
     }
 
+    #[test]
+    fn test_get_error_message_template() {
+        let err = TodoError::Template("unexpected end of input".to_string());
+        let msg = get_error_message(&err);
+        assert!(msg.contains("Template Error: unexpected end of input"));
+    }
+
     #[test]
     fn test_get_error_message_request() {
         // We can't easily create a connect error without real networking,
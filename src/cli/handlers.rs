@@ -1,110 +1,336 @@
+use crate::cli::confirm::Confirmation;
 use crate::error::Result;
 use crate::formatter::{Formattable, OutputFormat};
-use crate::sync::{Command, TodoistSyncClient};
+use crate::models::Priority;
+use crate::resolver::Resolver;
+use crate::sync::{Command, Journal, TodoistSyncClient};
 use std::collections::HashMap;
 
-pub async fn get_tasks(
+/// Resolves `--project`/`--section` references to IDs.
+///
+/// Names are only looked up when given, so raw IDs never trigger an extra sync.
+/// When only a section is given, its project is filled in as well.
+async fn resolve_location(
     client: &TodoistSyncClient,
-    filter: Option<&str>,
-    format: &OutputFormat,
-    fields: Option<&str>,
-    limit: Option<usize>,
-) -> Result<()> {
-    // Get all tasks and projects using a single sync call
-    let (projects, tasks) = client.get_projects_and_tasks().await?;
+    project_id: Option<String>,
+    project: Option<&str>,
+    section_id: Option<String>,
+    section: Option<&str>,
+) -> Result<(Option<String>, Option<String>)> {
+    if project.is_none() && section.is_none() {
+        return Ok((project_id, section_id));
+    }
+    let resolver = Resolver::load(client).await?;
+    let mut project_id = match project {
+        Some(p) => Some(resolver.project(p)?.id.clone()),
+        None => project_id,
+    };
+    let section_id = match section {
+        Some(s) => {
+            let found = resolver.section(s, project_id.as_deref())?;
+            project_id.get_or_insert_with(|| found.project_id.clone());
+            Some(found.id.clone())
+        }
+        None => section_id,
+    };
+    Ok((project_id, section_id))
+}
+
+fn missing_reference(kind: &str) -> crate::error::TodoError {
+    crate::error::TodoError::InvalidInput(format!("Provide --{} or --{}-id", kind, kind))
+}
+
+async fn resolve_project_id(
+    client: &TodoistSyncClient,
+    project_id: Option<String>,
+    project: Option<&str>,
+) -> Result<String> {
+    resolve_location(client, project_id, project, None, None)
+        .await?
+        .0
+        .ok_or_else(|| missing_reference("project"))
+}
+
+async fn resolve_section_id(
+    client: &TodoistSyncClient,
+    section_id: Option<String>,
+    section: Option<&str>,
+) -> Result<String> {
+    resolve_location(client, None, None, section_id, section)
+        .await?
+        .1
+        .ok_or_else(|| missing_reference("section"))
+}
+
+async fn resolve_label_id(
+    client: &TodoistSyncClient,
+    label_id: Option<String>,
+    label: Option<&str>,
+) -> Result<String> {
+    match label {
+        Some(l) => Ok(Resolver::load(client).await?.label(l)?.id.clone()),
+        None => label_id.ok_or_else(|| missing_reference("label")),
+    }
+}
+
+/// Resolves `--assignee` (ID, email, name or `me`) to a user ID.
+///
+/// With a `project_id`, only active members of that project are considered.
+async fn resolve_assignee(
+    client: &TodoistSyncClient,
+    assignee: &str,
+    project_id: Option<&str>,
+) -> Result<String> {
+    let response = client.sync_with_cache(&["collaborators", "user"]).await?;
+    let mut people = response.collaborators;
+    if let Some(user) = response.user {
+        if assignee.eq_ignore_ascii_case("me") {
+            return Ok(user.id);
+        }
+        if !people.iter().any(|c| c.id == user.id) {
+            people.push(crate::sync::SyncCollaborator {
+                id: user.id,
+                email: user.email,
+                full_name: user.full_name,
+                timezone: None,
+            });
+        }
+    }
+    if let Some(pid) = project_id {
+        people.retain(|c| {
+            response.collaborator_states.iter().any(|s| {
+                s.project_id == pid && s.user_id == c.id && s.state == "active" && !s.is_deleted
+            })
+        });
+        if people.is_empty() {
+            return Err(crate::error::TodoError::InvalidInput(format!(
+                "Project {} is not shared; tasks can only be assigned in shared projects",
+                pid
+            )));
+        }
+    }
+    Ok(crate::resolver::collaborator(&people, assignee)?.id.clone())
+}
+
+/// ID of the Inbox project, for `--inbox`
+async fn inbox_project_id(client: &TodoistSyncClient) -> Result<String> {
+    Ok(Resolver::load(client).await?.inbox()?.id.clone())
+}
+
+/// All tasks with their project and assignee names filled in, from a single sync call.
+///
+/// Also returns the current user's ID, for `assigned to: me`.
+async fn load_task_outputs(
+    client: &TodoistSyncClient,
+) -> Result<(Vec<crate::models::TaskOutput>, Option<String>)> {
+    let response = client
+        .sync_with_cache(&["projects", "items", "sections", "collaborators", "user"])
+        .await?;
 
-    // Build project name lookup
-    let project_map: HashMap<&str, &str> = projects
+    // Build project and section name lookups
+    let project_map: HashMap<&str, &str> = response
+        .projects
         .iter()
         .map(|p| (p.id.as_str(), p.name.as_str()))
         .collect();
+    let section_map: HashMap<&str, &str> = response
+        .sections
+        .iter()
+        .map(|s| (s.id.as_str(), s.name.as_str()))
+        .collect();
+    // Tasks without a project live in the Inbox
+    let inbox = response.projects.iter().find(|p| p.inbox_project);
 
-    // Convert to TaskOutput with project names
-    let task_outputs: Vec<crate::models::TaskOutput> = tasks
-        .into_iter()
+    // Assignees are collaborators or the current user
+    let mut people: HashMap<&str, &str> = response
+        .collaborators
+        .iter()
+        .map(|c| (c.id.as_str(), person_name(&c.full_name, &c.email)))
+        .collect();
+    if let Some(user) = &response.user {
+        people.insert(&user.id, person_name(&user.full_name, &user.email));
+    }
+
+    let tasks = response
+        .items
+        .iter()
+        .cloned()
         .map(|t| {
-            let project_name = t
-                .project_id
-                .as_ref()
-                .and_then(|pid| project_map.get(pid.as_str()))
+            let project_name = match &t.project_id {
+                Some(pid) => project_map.get(pid.as_str()).map(|s| s.to_string()),
+                None => inbox.map(|p| p.name.clone()),
+            };
+            let assignee = t
+                .responsible_uid
+                .as_deref()
+                .map(|uid| people.get(uid).copied().unwrap_or(uid).to_string());
+
+            let section_name = t
+                .section_id
+                .as_deref()
+                .and_then(|sid| section_map.get(sid))
                 .map(|s| s.to_string());
 
-            crate::models::TaskOutput {
-                id: t.id,
-                content: t.content,
-                description: t.description,
-                project_id: t.project_id,
-                project_name,
-                due_date: t.due.and_then(|d| d.date),
-                is_completed: t.is_completed,
-                created_at: t.created_at,
-                order: t.order,
-                priority: t.priority,
-                labels: t.labels,
-            }
+            let mut output = crate::models::TaskOutput::from_task(t.into(), project_name);
+            output.section_name = section_name;
+            output.assignee = assignee;
+            output
         })
         .collect();
+    Ok((tasks, response.user.map(|u| u.id)))
+}
 
-    // Apply filter if provided
-    let mut filtered: Vec<crate::models::TaskOutput> = if let Some(f) = filter {
-        let f_lower = f.to_lowercase();
-
-        // Check for specific patterns like "p:1" or "priority:1"
-        let priority_filter = if f_lower.starts_with("p:") {
-            f_lower
-                .strip_prefix("p:")
-                .and_then(|s| s.parse::<u8>().ok())
-        } else if f_lower.starts_with("priority:") {
-            f_lower
-                .strip_prefix("priority:")
-                .and_then(|s| s.parse::<u8>().ok())
+fn person_name<'a>(full_name: &'a str, email: &'a str) -> &'a str {
+    if full_name.is_empty() {
+        email
+    } else {
+        full_name
+    }
+}
+
+/// A parsed `--filter` expression.
+///
+/// `@label` and `#project` match exactly (case-insensitive); `assigned to: me`,
+/// `assigned to: others`, `assigned to: <name>`, `assigned` and `!assigned`
+/// match on the assignee (`me` being the current user); `p1`, `p:high` and
+/// `priority:p1` match on priority, `is:completed` and `is:active` on status,
+/// and `search: <text>` on a substring of the content or project name.
+enum TaskFilter {
+    AssignedTo(String),
+    Assigned(bool),
+    Label(String),
+    Project(String),
+    Priority(Priority),
+    Completed(bool),
+    Search(String),
+    /// Anything else, matched like `search:`
+    Text(String),
+}
+
+impl TaskFilter {
+    fn parse(filter: &str) -> Self {
+        let f_lower = filter.to_lowercase();
+        if let Some(who) = f_lower.strip_prefix("assigned to:") {
+            return Self::AssignedTo(who.trim().to_string());
+        }
+        if f_lower == "assigned" || f_lower == "!assigned" {
+            return Self::Assigned(f_lower == "assigned");
+        }
+        if let Some(label) = f_lower.strip_prefix('@') {
+            return Self::Label(label.to_string());
+        }
+        if let Some(project) = f_lower.strip_prefix('#') {
+            return Self::Project(project.to_string());
+        }
+        if let Some(text) = f_lower.strip_prefix("search:") {
+            return Self::Search(text.trim().to_string());
+        }
+
+        // "p1", "p:urgent" or "priority:p1"
+        let priority = if let Some(value) = f_lower
+            .strip_prefix("p:")
+            .or_else(|| f_lower.strip_prefix("priority:"))
+        {
+            value.parse::<Priority>().ok()
+        } else if f_lower.len() == 2 && f_lower.starts_with('p') {
+            f_lower.parse::<Priority>().ok()
         } else {
             None
         };
+        if let Some(priority) = priority {
+            return Self::Priority(priority);
+        }
 
-        let status_filter = match f_lower.as_str() {
-            "is:completed" | "completed" => Some(true),
-            "is:active" | "active" | "incomplete" => Some(false),
-            _ => None,
-        };
-
-        task_outputs
-            .into_iter()
-            .filter(|t| {
-                if let Some(p) = priority_filter {
-                    if t.priority == p {
-                        return true;
-                    }
-                }
+        match f_lower.as_str() {
+            "is:completed" | "completed" => Self::Completed(true),
+            "is:active" | "active" | "incomplete" => Self::Completed(false),
+            _ => Self::Text(f_lower),
+        }
+    }
 
-                if let Some(completed) = status_filter {
-                    if t.is_completed == completed {
-                        return true;
-                    }
+    fn matches(&self, t: &crate::models::TaskOutput, current_user: Option<&str>) -> bool {
+        match self {
+            Self::AssignedTo(who) => {
+                let Some(uid) = t.responsible_uid.as_deref() else {
+                    return false;
+                };
+                match who.as_str() {
+                    "me" => Some(uid) == current_user,
+                    "others" => Some(uid) != current_user,
+                    name => t
+                        .assignee
+                        .as_ref()
+                        .is_some_and(|a| a.to_lowercase().contains(name)),
                 }
-
-                // Content or project name match
-                t.content.to_lowercase().contains(&f_lower)
+            }
+            Self::Assigned(assigned) => t.responsible_uid.is_some() == *assigned,
+            Self::Label(label) => t.labels.iter().any(|l| l.to_lowercase() == *label),
+            Self::Project(project) => t
+                .project_name
+                .as_ref()
+                .is_some_and(|p| p.to_lowercase() == *project),
+            Self::Priority(priority) => t.priority == *priority,
+            Self::Completed(completed) => t.is_completed == *completed,
+            Self::Search(text) | Self::Text(text) => {
+                t.content.to_lowercase().contains(text.as_str())
                     || t.project_name
                         .as_ref()
-                        .map(|p| p.to_lowercase().contains(&f_lower))
-                        .unwrap_or(false)
-            })
-            .collect()
-    } else {
-        task_outputs
+                        .is_some_and(|p| p.to_lowercase().contains(text.as_str()))
+            }
+        }
+    }
+}
+
+/// Tasks matching a `--filter` expression (see [`TaskFilter`]).
+///
+/// Priority and status filters also match tasks whose content or project
+/// name contains the filter text, and anything unrecognised is a substring
+/// search.
+fn filter_tasks(
+    tasks: Vec<crate::models::TaskOutput>,
+    filter: &str,
+    current_user: Option<&str>,
+) -> Vec<crate::models::TaskOutput> {
+    let parsed = TaskFilter::parse(filter);
+    let text = matches!(parsed, TaskFilter::Priority(_) | TaskFilter::Completed(_))
+        .then(|| TaskFilter::Text(filter.to_lowercase()));
+    tasks
+        .into_iter()
+        .filter(|t| {
+            parsed.matches(t, current_user)
+                || text.as_ref().is_some_and(|f| f.matches(t, current_user))
+        })
+        .collect()
+}
+
+pub async fn get_tasks(
+    client: &TodoistSyncClient,
+    filter: Option<&str>,
+    format: &OutputFormat,
+    fields: Option<&str>,
+    sort: &[crate::listing::SortKey],
+    group_by: Option<crate::listing::GroupBy>,
+    limit: Option<usize>,
+) -> Result<()> {
+    let (task_outputs, current_user) = load_task_outputs(client).await?;
+
+    // Apply filter if provided
+    let mut filtered = match filter {
+        Some(f) => filter_tasks(task_outputs, f, current_user.as_deref()),
+        None => task_outputs,
     };
 
-    // Apply limit if provided
+    // Sort before limiting, so the limit keeps the first tasks in that order
+    crate::listing::sort(&mut filtered, sort);
     if let Some(l) = limit {
         filtered.truncate(l);
     }
 
-    if format == &OutputFormat::Json && fields.is_some() {
-        println!("{}", filtered.format_filtered(fields));
-    } else {
-        println!("{}", filtered.format(format));
-    }
+    let output = match group_by {
+        Some(by) => crate::listing::group(filtered, by).format_output(format, fields)?,
+        None => filtered.format_output(format, fields)?,
+    };
+    println!("{}", output);
     Ok(())
 }
 
@@ -112,22 +338,31 @@ pub async fn get_projects(
     client: &TodoistSyncClient,
     format: &OutputFormat,
     fields: Option<&str>,
+    archived: bool,
 ) -> Result<()> {
-    let projects = client.get_projects().await?;
-    if format == &OutputFormat::Json && fields.is_some() {
-        println!("{}", projects.format_filtered(fields));
+    let projects: Vec<crate::models::Project> = if archived {
+        client
+            .get_archived_projects()
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect()
     } else {
-        println!("{}", projects.format(format));
-    }
+        client.get_projects().await?
+    };
+    println!("{}", projects.format_output(format, fields)?);
     Ok(())
 }
 
 pub async fn get_sections(
     client: &TodoistSyncClient,
     project_id: Option<&str>,
+    project: Option<&str>,
     format: &OutputFormat,
     fields: Option<&str>,
 ) -> Result<()> {
+    let (project_id, _) =
+        resolve_location(client, project_id.map(String::from), project, None, None).await?;
     let mut sections = client.get_sections().await?;
 
     // Filter by project_id if provided
@@ -135,11 +370,50 @@ pub async fn get_sections(
         sections.retain(|s| s.project_id == pid);
     }
 
-    if format == &OutputFormat::Json && fields.is_some() {
-        println!("{}", sections.format_filtered(fields));
-    } else {
-        println!("{}", sections.format(format));
-    }
+    println!("{}", sections.format_output(format, fields)?);
+    Ok(())
+}
+
+pub async fn get_collaborators(
+    client: &TodoistSyncClient,
+    project_id: Option<&str>,
+    project: Option<&str>,
+    format: &OutputFormat,
+    fields: Option<&str>,
+) -> Result<()> {
+    let (project_id, _) =
+        resolve_location(client, project_id.map(String::from), project, None, None).await?;
+    let (collaborators, states) = client.get_collaborators().await?;
+
+    // With a project, list its members and their state
+    let collaborators: Vec<crate::models::Collaborator> = match project_id {
+        Some(pid) => states
+            .iter()
+            .filter(|s| s.project_id == pid && !s.is_deleted && s.state != "deleted")
+            .filter_map(|s| {
+                let c = collaborators.iter().find(|c| c.id == s.user_id)?;
+                let mut collaborator: crate::models::Collaborator = c.clone().into();
+                collaborator.state = Some(s.state.clone());
+                Some(collaborator)
+            })
+            .collect(),
+        None => collaborators.into_iter().map(Into::into).collect(),
+    };
+
+    println!("{}", collaborators.format_output(format, fields)?);
+    Ok(())
+}
+
+pub async fn get_agenda(
+    client: &TodoistSyncClient,
+    date: &str,
+    format: &OutputFormat,
+    fields: Option<&str>,
+) -> Result<()> {
+    let date = crate::agenda::parse_date(date, chrono::Local::now().date_naive())?;
+    let (tasks, _) = load_task_outputs(client).await?;
+    let agenda = crate::agenda::build(&tasks, date);
+    println!("{}", agenda.format_output(format, fields)?);
     Ok(())
 }
 
@@ -149,11 +423,7 @@ pub async fn get_filters(
     fields: Option<&str>,
 ) -> Result<()> {
     let filters = client.get_filters().await?;
-    if format == &OutputFormat::Json && fields.is_some() {
-        println!("{}", filters.format_filtered(fields));
-    } else {
-        println!("{}", filters.format(format));
-    }
+    println!("{}", filters.format_output(format, fields)?);
     Ok(())
 }
 
@@ -163,11 +433,7 @@ pub async fn get_labels(
     fields: Option<&str>,
 ) -> Result<()> {
     let labels = client.get_labels().await?;
-    if format == &OutputFormat::Json && fields.is_some() {
-        println!("{}", labels.format_filtered(fields));
-    } else {
-        println!("{}", labels.format(format));
-    }
+    println!("{}", labels.format_output(format, fields)?);
     Ok(())
 }
 
@@ -177,44 +443,17 @@ pub async fn get_task(
     format: &OutputFormat,
     fields: Option<&str>,
 ) -> Result<()> {
-    // Get tasks and projects using a single sync call
-    let (projects, tasks) = client.get_projects_and_tasks().await?;
-
-    let project_map: HashMap<&str, &str> = projects
-        .iter()
-        .map(|p| (p.id.as_str(), p.name.as_str()))
-        .collect();
-
-    let task = tasks.into_iter().find(|t| t.id == task_id).ok_or_else(|| {
-        crate::error::TodoError::InvalidInput(format!("Task {} not found", task_id))
-    })?;
-
-    let project_name = task
-        .project_id
-        .as_ref()
-        .and_then(|pid| project_map.get(pid.as_str()))
-        .map(|s| s.to_string());
-
-    let task_output = crate::models::TaskOutput {
-        id: task.id,
-        content: task.content,
-        description: task.description,
-        project_id: task.project_id,
-        project_name,
-        due_date: task.due.and_then(|d| d.date),
-        is_completed: task.is_completed,
-        created_at: task.created_at,
-        order: task.order,
-        priority: task.priority,
-        labels: task.labels,
-    };
+    let task_output = load_task_outputs(client)
+        .await?
+        .0
+        .into_iter()
+        .find(|t| t.id == task_id)
+        .ok_or_else(|| {
+            crate::error::TodoError::InvalidInput(format!("Task {} not found", task_id))
+        })?;
 
     let result = vec![task_output];
-    if format == &OutputFormat::Json && fields.is_some() {
-        println!("{}", result.format_filtered(fields));
-    } else {
-        println!("{}", result.format(format));
-    }
+    println!("{}", result.format_output(format, fields)?);
     Ok(())
 }
 
@@ -225,9 +464,15 @@ pub async fn add_task(
     content: Option<String>,
     description: Option<String>,
     project_id: Option<String>,
+    project: Option<String>,
+    section: Option<String>,
     due_date: Option<String>,
-    priority: Option<u8>,
+    priority: Option<Priority>,
     labels: Option<String>,
+    assignee: Option<String>,
+    duration: Option<crate::models::Duration>,
+    inbox: bool,
+    default_project: Option<&str>,
 ) -> Result<()> {
     let task_content = title
         .as_ref()
@@ -237,32 +482,43 @@ pub async fn add_task(
         })?
         .clone();
 
-    // Validate priority
-    let validated_priority = if let Some(p) = priority {
-        if !validate_priority(p) {
-            return Err(crate::error::TodoError::InvalidInput(format!(
-                "Invalid priority {}. Priority must be between 1 and 4.",
-                p
-            )));
-        }
-        Some(p)
+    let (project_id, project) = if inbox {
+        (Some(inbox_project_id(client).await?), None)
+    } else if project_id.is_none() && project.is_none() && section.is_none() {
+        (None, default_project.map(str::to_string))
     } else {
-        None
+        (project_id, project)
     };
 
     let labels_vec: Option<Vec<&str>> = labels
         .as_ref()
         .map(|l| l.split(',').map(|s| s.trim()).collect());
 
+    let (project_id, section_id) = resolve_location(
+        client,
+        project_id,
+        project.as_deref(),
+        None,
+        section.as_deref(),
+    )
+    .await?;
+
+    let responsible_uid = match assignee {
+        Some(a) => Some(resolve_assignee(client, &a, project_id.as_deref()).await?),
+        None => None,
+    };
+
     let task_id = client
         .add_task(
             &task_content,
             description.as_deref(),
             project_id.as_deref(),
-            None,
+            section_id.as_deref(),
             due_date.as_deref(),
-            validated_priority,
+            priority.map(Priority::api_value),
             labels_vec,
+            responsible_uid.as_deref(),
+            duration,
         )
         .await?;
 
@@ -276,11 +532,69 @@ pub async fn add_task(
     Ok(())
 }
 
+pub async fn add_quick(
+    client: &TodoistSyncClient,
+    text: &str,
+    default_project: Option<&str>,
+) -> Result<()> {
+    let parsed = crate::quick_add::parse(text)?;
+
+    let project = match (&parsed.project, &parsed.section) {
+        (None, None) => default_project,
+        _ => parsed.project.as_deref(),
+    };
+    let (project_id, section_id) =
+        resolve_location(client, None, project, None, parsed.section.as_deref()).await?;
+    let content = parsed.content.clone();
+    let preview = quick_add_preview(&parsed);
+    let builder =
+        crate::sync::CommandBuilder::new().item_add(parsed.into_args(project_id, section_id));
+    let response = match client.execute(builder).await {
+        // Show how the text was read, so the parse can be checked before sending
+        Err(crate::error::TodoError::DryRun) => {
+            let mut report = dry_run_output(client).await?;
+            report["parsed"] = preview;
+            println!("{}", serde_json::to_string_pretty(&report).unwrap());
+            return Ok(());
+        }
+        response => response?,
+    };
+    let task_id = response
+        .temp_id_mapping
+        .values()
+        .next()
+        .cloned()
+        .ok_or_else(|| crate::error::TodoError::Api("No ID returned".to_string()))?;
+
+    let response = serde_json::json!({
+        "status": "success",
+        "type": "task",
+        "id": task_id,
+        "content": content
+    });
+    println!("{}", serde_json::to_string_pretty(&response).unwrap());
+    Ok(())
+}
+
+/// The parts of a quick-add text, with the priority as shown in the UI
+fn quick_add_preview(parsed: &crate::quick_add::QuickAdd) -> serde_json::Value {
+    serde_json::json!({
+        "content": parsed.content,
+        "due_string": parsed.due_string,
+        "priority": parsed.priority.map(|p| p.to_string()),
+        "labels": parsed.labels,
+        "project": parsed.project,
+        "section": parsed.section
+    })
+}
+
 pub async fn add_section(
     client: &TodoistSyncClient,
     name: String,
-    project_id: String,
+    project_id: Option<String>,
+    project: Option<String>,
 ) -> Result<()> {
+    let project_id = resolve_project_id(client, project_id, project.as_deref()).await?;
     let section_id = client.add_section(&name, &project_id).await?;
     let response = serde_json::json!({
         "status": "success",
@@ -296,11 +610,21 @@ pub async fn add_section(
 pub async fn add_project(
     client: &TodoistSyncClient,
     name: String,
+    parent: Option<String>,
     color: Option<String>,
     favorite: bool,
 ) -> Result<()> {
+    let parent_id = match parent {
+        Some(p) => Some(resolve_project_id(client, None, Some(&p)).await?),
+        None => None,
+    };
     let project_id = client
-        .add_project(&name, color.as_deref(), Some(favorite))
+        .add_project(
+            &name,
+            parent_id.as_deref(),
+            color.as_deref(),
+            Some(favorite),
+        )
         .await?;
     let response = serde_json::json!({
         "status": "success",
@@ -346,6 +670,23 @@ pub async fn add_filter(
     Ok(())
 }
 
+/// Applies `--add-label`/`--remove-label` to `current`, matching names case-insensitively.
+///
+/// Returns `None` when the label set would not change.
+fn merge_labels(current: &[String], add: &[String], remove: &[String]) -> Option<Vec<String>> {
+    let mut labels: Vec<String> = current
+        .iter()
+        .filter(|l| !remove.iter().any(|r| r.eq_ignore_ascii_case(l)))
+        .cloned()
+        .collect();
+    for label in add {
+        if !labels.iter().any(|l| l.eq_ignore_ascii_case(label)) {
+            labels.push(label.clone());
+        }
+    }
+    (labels != current).then_some(labels)
+}
+
 #[allow(clippy::too_many_arguments)]
 pub async fn edit_task(
     client: &TodoistSyncClient,
@@ -353,42 +694,57 @@ pub async fn edit_task(
     title: Option<String>,
     content: Option<String>,
     project_id: Option<String>,
+    project: Option<String>,
     due_date: Option<String>,
-    priority: Option<u8>,
+    priority: Option<Priority>,
     labels: Option<String>,
+    add_labels: Vec<String>,
+    remove_labels: Vec<String>,
+    assignee: Option<String>,
+    unassign: bool,
+    duration: Option<crate::models::Duration>,
 ) -> Result<()> {
     let task_content = title.as_ref().or(content.as_ref()).map(|s| s.as_str());
-    let labels_vec: Option<Vec<&str>> = labels
+    let mut labels_vec: Option<Vec<String>> = labels
         .as_ref()
-        .map(|l| l.split(',').map(|s| s.trim()).collect());
+        .map(|l| l.split(',').map(|s| s.trim().to_string()).collect());
 
-    // Validate priority
-    if let Some(p) = priority {
-        if !validate_priority(p) {
-            return Err(crate::error::TodoError::InvalidInput(format!(
-                "Invalid priority {}. Priority must be between 1 and 4.",
-                p
-            )));
+    let (project_id, _) =
+        resolve_location(client, project_id, project.as_deref(), None, None).await?;
+
+    // Compute the new label set and the assignee's project from the cached task
+    let mut responsible_uid = unassign.then_some(None);
+    if !add_labels.is_empty() || !remove_labels.is_empty() || assignee.is_some() {
+        let response = client.sync_with_cache(&["items"]).await?;
+        let task = response
+            .items
+            .iter()
+            .find(|t| t.id == task_id && !t.is_deleted)
+            .ok_or_else(|| {
+                crate::error::TodoError::InvalidInput(format!("Task '{}' not found", task_id))
+            })?;
+        if !add_labels.is_empty() || !remove_labels.is_empty() {
+            labels_vec = merge_labels(&task.labels, &add_labels, &remove_labels);
+        }
+        if let Some(a) = &assignee {
+            let task_project = project_id.as_deref().or(task.project_id.as_deref());
+            responsible_uid = Some(Some(resolve_assignee(client, a, task_project).await?));
         }
     }
 
-    // If project_id is provided, move the task to the new project first
+    // Move and update in one batch, so the edit is a single write
+    let mut builder = crate::sync::CommandBuilder::new();
     if let Some(ref new_project_id) = project_id {
-        let builder = crate::sync::CommandBuilder::new().item_move(&task_id, new_project_id, None);
-        client.execute(builder).await?;
+        builder = builder.item_move(&task_id, new_project_id, None);
     }
-
-    // Update task fields after move
-    client
-        .update_task(
-            &task_id,
-            task_content,
-            None,
-            priority,
-            due_date.as_deref(),
-            labels_vec,
-        )
-        .await?;
+    let update = crate::sync::ItemUpdateArgs::new(task_id.clone())
+        .content(task_content.map(str::to_string))
+        .priority(priority.map(Priority::api_value))
+        .due_string(due_date)
+        .labels(labels_vec)
+        .responsible_uid(responsible_uid)
+        .duration(duration.map(|d| Some(d.into())));
+    client.execute(builder.item_update(update)).await?;
 
     let response = serde_json::json!({
         "status": "success",
@@ -402,16 +758,27 @@ pub async fn edit_task(
 
 pub async fn edit_project(
     client: &TodoistSyncClient,
-    project_id: String,
+    project_id: Option<String>,
+    project: Option<String>,
     name: Option<String>,
+    color: Option<String>,
+    favorite: Option<bool>,
+    view_style: Option<String>,
 ) -> Result<()> {
-    if name.is_none() {
+    if name.is_none() && color.is_none() && favorite.is_none() && view_style.is_none() {
         return Err(crate::error::TodoError::InvalidInput(
-            "No fields to update. Provide at least --name.".to_string(),
+            "No fields to update. Provide at least --name, --color, --favorite or --view-style."
+                .to_string(),
         ));
     }
+    let project_id = resolve_project_id(client, project_id, project.as_deref()).await?;
+    let args = crate::sync::ProjectUpdateArgs::new(project_id.clone())
+        .name(name)
+        .color(color)
+        .favorite(favorite)
+        .view_style(view_style);
     let builder =
-        crate::sync::CommandBuilder::new().project_update(&project_id, name.as_deref(), None, None);
+        crate::sync::CommandBuilder::new().push(crate::sync::CommandKind::ProjectUpdate(args));
     client.execute(builder).await?;
     let response = serde_json::json!({
         "status": "success",
@@ -425,7 +792,8 @@ pub async fn edit_project(
 
 pub async fn edit_section(
     client: &TodoistSyncClient,
-    section_id: String,
+    section_id: Option<String>,
+    section: Option<String>,
     name: Option<String>,
 ) -> Result<()> {
     let new_name = name.ok_or_else(|| {
@@ -433,6 +801,7 @@ pub async fn edit_section(
             "No fields to update. Provide at least --name.".to_string(),
         )
     })?;
+    let section_id = resolve_section_id(client, section_id, section.as_deref()).await?;
     client.update_section(&section_id, &new_name).await?;
     let response = serde_json::json!({
         "status": "success",
@@ -444,9 +813,12 @@ pub async fn edit_section(
     Ok(())
 }
 
+/// Edits a label; a rename is also applied to every task carrying the old name,
+/// in the same batch, so tasks never point at a label that no longer exists.
 pub async fn edit_label(
     client: &TodoistSyncClient,
-    label_id: String,
+    label_id: Option<String>,
+    label: Option<String>,
     name: Option<String>,
     color: Option<String>,
 ) -> Result<()> {
@@ -455,14 +827,63 @@ pub async fn edit_label(
             "No fields to update. Provide at least --name or --color.".to_string(),
         ));
     }
-    client
-        .update_label(&label_id, name.as_deref(), color.as_deref())
-        .await?;
+    let label_id = resolve_label_id(client, label_id, label.as_deref()).await?;
+    let mut builder = crate::sync::CommandBuilder::new().label_update(
+        &label_id,
+        name.as_deref(),
+        color.as_deref(),
+    );
+
+    let mut renamed = Vec::new();
+    if let Some(new_name) = &name {
+        let resolver = Resolver::load(client).await?;
+        let old_name = match resolver.labels().iter().find(|l| l.id == label_id) {
+            Some(label) => label.name.clone(),
+            // Not in the cache: read all labels before renaming it on its tasks
+            None => client
+                .snapshot(&["labels"])
+                .await?
+                .labels
+                .into_iter()
+                .find(|l| l.id == label_id && !l.is_deleted)
+                .map(|l| l.name)
+                .ok_or_else(|| {
+                    crate::error::TodoError::InvalidInput(format!("Label '{}' not found", label_id))
+                })?,
+        };
+        if &old_name != new_name {
+            let response = client.sync_with_cache(&["items"]).await?;
+            for task in response
+                .items
+                .iter()
+                .filter(|t| !t.is_deleted && t.labels.contains(&old_name))
+            {
+                let labels = task
+                    .labels
+                    .iter()
+                    .map(|l| {
+                        if *l == old_name {
+                            new_name.clone()
+                        } else {
+                            l.clone()
+                        }
+                    })
+                    .collect();
+                builder = builder.item_update(
+                    crate::sync::ItemUpdateArgs::new(task.id.clone()).labels(Some(labels)),
+                );
+                renamed.push(task.id.clone());
+            }
+        }
+    }
+    client.execute_chunked(builder).await?;
+
     let response = serde_json::json!({
         "status": "success",
         "action": "edit",
         "type": "label",
-        "id": label_id
+        "id": label_id,
+        "tasks_updated": renamed
     });
     println!("{}", serde_json::to_string_pretty(&response).unwrap());
     Ok(())
@@ -498,44 +919,227 @@ pub async fn edit_filter(
     Ok(())
 }
 
-pub async fn complete_task(client: &TodoistSyncClient, task_id: String) -> Result<()> {
-    client.complete_task(&task_id).await?;
+/// Archives (or unarchives) a project.
+///
+/// Archived projects are not part of the sync data, so names given to
+/// `unarchive` are looked up among the archived projects instead.
+pub async fn archive_project(
+    client: &TodoistSyncClient,
+    project_id: Option<String>,
+    project: Option<String>,
+    archive: bool,
+) -> Result<()> {
+    let project_id = match (archive, project) {
+        (false, Some(query)) => {
+            let archived = client.get_archived_projects().await?;
+            archived
+                .iter()
+                .find(|p| p.id == query)
+                .or_else(|| {
+                    archived
+                        .iter()
+                        .find(|p| p.name.to_lowercase() == query.to_lowercase())
+                })
+                .map(|p| p.id.clone())
+                .ok_or_else(|| {
+                    crate::error::TodoError::InvalidInput(format!(
+                        "No archived project matches '{}'",
+                        query
+                    ))
+                })?
+        }
+        (_, project) => resolve_project_id(client, project_id, project.as_deref()).await?,
+    };
+    let builder = if archive {
+        crate::sync::CommandBuilder::new().project_archive(&project_id)
+    } else {
+        crate::sync::CommandBuilder::new().project_unarchive(&project_id)
+    };
+    client.execute(builder).await?;
     let response = serde_json::json!({
         "status": "success",
-        "action": "complete",
-        "id": task_id
+        "action": if archive { "archive" } else { "unarchive" },
+        "type": "project",
+        "id": project_id
     });
     println!("{}", serde_json::to_string_pretty(&response).unwrap());
     Ok(())
 }
 
-pub async fn reopen_task(client: &TodoistSyncClient, task_id: String) -> Result<()> {
-    let builder = crate::sync::CommandBuilder::new().item_uncomplete(&task_id);
+/// Archives (or unarchives) a section
+pub async fn archive_section(
+    client: &TodoistSyncClient,
+    section_id: Option<String>,
+    section: Option<String>,
+    archive: bool,
+) -> Result<()> {
+    let section_id = resolve_section_id(client, section_id, section.as_deref()).await?;
+    let builder = if archive {
+        crate::sync::CommandBuilder::new().section_archive(&section_id)
+    } else {
+        crate::sync::CommandBuilder::new().section_unarchive(&section_id)
+    };
     client.execute(builder).await?;
     let response = serde_json::json!({
         "status": "success",
-        "action": "reopen",
-        "id": task_id
+        "action": if archive { "archive" } else { "unarchive" },
+        "type": "section",
+        "id": section_id
     });
     println!("{}", serde_json::to_string_pretty(&response).unwrap());
     Ok(())
 }
 
-pub async fn delete_task(client: &TodoistSyncClient, task_id: String) -> Result<()> {
-    client.delete_task(&task_id).await?;
+/// Completes a task.
+///
+/// A recurring task is closed instead, which moves it to its next occurrence,
+/// unless `forever` is set; the next due date is reported from the refreshed item.
+pub async fn complete_task(
+    client: &TodoistSyncClient,
+    task_id: String,
+    forever: bool,
+) -> Result<()> {
+    let recurring = client
+        .sync_with_cache(&["items"])
+        .await?
+        .items
+        .iter()
+        .find(|t| t.id == task_id)
+        .and_then(|t| t.due.as_ref())
+        .is_some_and(|d| d.is_recurring);
+
+    if !recurring || forever {
+        client.complete_task(&task_id).await?;
+        let response = serde_json::json!({
+            "status": "success",
+            "action": "complete",
+            "id": task_id
+        });
+        println!("{}", serde_json::to_string_pretty(&response).unwrap());
+        return Ok(());
+    }
+
+    let builder = crate::sync::CommandBuilder::new().item_close(&task_id);
+    client.execute(builder).await?;
+    let next_due = client
+        .sync_with_cache(&["items"])
+        .await?
+        .items
+        .into_iter()
+        .find(|t| t.id == task_id)
+        .and_then(|t| t.due)
+        .and_then(|d| d.datetime.or(d.date));
+
     let response = serde_json::json!({
         "status": "success",
-        "action": "delete",
-        "type": "task",
-        "id": task_id
+        "action": "complete",
+        "id": task_id,
+        "recurring": true,
+        "next_due": next_due
     });
     println!("{}", serde_json::to_string_pretty(&response).unwrap());
     Ok(())
 }
 
-pub async fn delete_project(client: &TodoistSyncClient, project_id: String) -> Result<()> {
-    let builder = crate::sync::CommandBuilder::new().project_delete(&project_id);
-    client.execute(builder).await?;
+pub async fn reopen_task(client: &TodoistSyncClient, task_id: String) -> Result<()> {
+    let builder = crate::sync::CommandBuilder::new().item_uncomplete(&task_id);
+    client.execute(builder).await?;
+    let response = serde_json::json!({
+        "status": "success",
+        "action": "reopen",
+        "id": task_id
+    });
+    println!("{}", serde_json::to_string_pretty(&response).unwrap());
+    Ok(())
+}
+
+/// Active tasks in the cache matching `predicate`
+async fn count_tasks(
+    client: &TodoistSyncClient,
+    predicate: impl Fn(&crate::sync::SyncTask) -> bool,
+) -> Result<usize> {
+    let response = client.sync_with_cache(&["items"]).await?;
+    Ok(response
+        .items
+        .iter()
+        .filter(|t| !t.is_deleted && !t.is_completed)
+        .filter(|t| predicate(t))
+        .count())
+}
+
+pub async fn delete_task(
+    client: &TodoistSyncClient,
+    task_id: String,
+    confirm: &Confirmation,
+) -> Result<()> {
+    if confirm.needed() {
+        let response = client.sync_with_cache(&["items"]).await?;
+        let name = response
+            .items
+            .iter()
+            .find(|t| t.id == task_id)
+            .map(|t| t.content.clone())
+            .unwrap_or_else(|| task_id.clone());
+        confirm.confirm(&format!("Delete task '{}'?", name))?;
+    }
+    client.delete_task(&task_id).await?;
+    let response = serde_json::json!({
+        "status": "success",
+        "action": "delete",
+        "type": "task",
+        "id": task_id
+    });
+    println!("{}", serde_json::to_string_pretty(&response).unwrap());
+    Ok(())
+}
+
+pub async fn delete_project(
+    client: &TodoistSyncClient,
+    project_id: Option<String>,
+    project: Option<String>,
+    confirm: &Confirmation,
+) -> Result<()> {
+    let project_id = resolve_project_id(client, project_id, project.as_deref()).await?;
+    if confirm.needed() {
+        let resolver = Resolver::load(client).await?;
+        let name = resolver
+            .project(&project_id)
+            .map(|p| p.name.clone())
+            .unwrap_or_else(|_| project_id.clone());
+        // Subprojects are deleted along with the project
+        let mut projects = vec![project_id.clone()];
+        let mut index = 0;
+        while let Some(parent) = projects.get(index).cloned() {
+            index += 1;
+            let children: Vec<String> = resolver
+                .projects()
+                .iter()
+                .filter(|p| p.parent_id.as_deref() == Some(parent.as_str()))
+                .map(|p| p.id.clone())
+                .filter(|id| !projects.contains(id))
+                .collect();
+            projects.extend(children);
+        }
+        let tasks = count_tasks(client, |t| {
+            t.project_id
+                .as_ref()
+                .is_some_and(|id| projects.contains(id))
+        })
+        .await?;
+        let question = match projects.len() {
+            1 => format!("Delete project '{}' and its {} task(s)?", name, tasks),
+            n => format!(
+                "Delete project '{}' and its {} subproject(s), with {} task(s) in {} projects?",
+                name,
+                n - 1,
+                tasks,
+                n
+            ),
+        };
+        confirm.confirm(&question)?;
+    }
+    let builder = crate::sync::CommandBuilder::new().project_delete(&project_id);
+    client.execute(builder).await?;
     let response = serde_json::json!({
         "status": "success",
         "action": "delete",
@@ -546,7 +1150,25 @@ pub async fn delete_project(client: &TodoistSyncClient, project_id: String) -> R
     Ok(())
 }
 
-pub async fn delete_section(client: &TodoistSyncClient, section_id: String) -> Result<()> {
+pub async fn delete_section(
+    client: &TodoistSyncClient,
+    section_id: Option<String>,
+    section: Option<String>,
+    confirm: &Confirmation,
+) -> Result<()> {
+    let section_id = resolve_section_id(client, section_id, section.as_deref()).await?;
+    if confirm.needed() {
+        let name = Resolver::load(client)
+            .await?
+            .section(&section_id, None)
+            .map(|s| s.name.clone())
+            .unwrap_or_else(|_| section_id.clone());
+        let tasks = count_tasks(client, |t| t.section_id.as_deref() == Some(&section_id)).await?;
+        confirm.confirm(&format!(
+            "Delete section '{}' and its {} task(s)?",
+            name, tasks
+        ))?;
+    }
     client.delete_section(&section_id).await?;
     let response = serde_json::json!({
         "status": "success",
@@ -558,12 +1180,84 @@ pub async fn delete_section(client: &TodoistSyncClient, section_id: String) -> R
     Ok(())
 }
 
+pub async fn delete_label(
+    client: &TodoistSyncClient,
+    label_id: Option<String>,
+    label: Option<String>,
+    confirm: &Confirmation,
+) -> Result<()> {
+    let label_id = resolve_label_id(client, label_id, label.as_deref()).await?;
+    if confirm.needed() {
+        let name = Resolver::load(client)
+            .await?
+            .label(&label_id)
+            .map(|l| l.name.clone())
+            .unwrap_or_else(|_| label_id.clone());
+        let tasks = count_tasks(client, |t| t.labels.contains(&name)).await?;
+        confirm.confirm(&format!(
+            "Delete label '{}' and remove it from {} task(s)?",
+            name, tasks
+        ))?;
+    }
+    client.delete_label(&label_id).await?;
+    let response = serde_json::json!({
+        "status": "success",
+        "action": "delete",
+        "type": "label",
+        "id": label_id
+    });
+    println!("{}", serde_json::to_string_pretty(&response).unwrap());
+    Ok(())
+}
+
+pub async fn delete_filter(
+    client: &TodoistSyncClient,
+    filter_id: String,
+    confirm: &Confirmation,
+) -> Result<()> {
+    if confirm.needed() {
+        let filters = client.get_filters().await?;
+        let name = filters
+            .iter()
+            .find(|f| f.id == filter_id)
+            .map(|f| f.name.clone())
+            .unwrap_or_else(|| filter_id.clone());
+        confirm.confirm(&format!("Delete filter '{}'?", name))?;
+    }
+    client.delete_filter(&filter_id).await?;
+    let response = serde_json::json!({
+        "status": "success",
+        "action": "delete",
+        "type": "filter",
+        "id": filter_id
+    });
+    println!("{}", serde_json::to_string_pretty(&response).unwrap());
+    Ok(())
+}
+
 pub async fn move_task(
     client: &TodoistSyncClient,
     task_id: String,
-    project_id: String,
+    project_id: Option<String>,
+    project: Option<String>,
     section_id: Option<String>,
+    section: Option<String>,
+    inbox: bool,
 ) -> Result<()> {
+    let project_id = if inbox {
+        Some(inbox_project_id(client).await?)
+    } else {
+        project_id
+    };
+    let (project_id, section_id) = resolve_location(
+        client,
+        project_id,
+        project.as_deref(),
+        section_id,
+        section.as_deref(),
+    )
+    .await?;
+    let project_id = project_id.ok_or_else(|| missing_reference("project"))?;
     let builder =
         crate::sync::CommandBuilder::new().item_move(&task_id, &project_id, section_id.as_deref());
     client.execute(builder).await?;
@@ -578,6 +1272,280 @@ pub async fn move_task(
     Ok(())
 }
 
+/// Moves a project under `parent`, or to the top level when `parent` is `None`
+pub async fn move_project(
+    client: &TodoistSyncClient,
+    project_id: Option<String>,
+    project: Option<String>,
+    parent: Option<String>,
+) -> Result<()> {
+    let project_id = resolve_project_id(client, project_id, project.as_deref()).await?;
+    let parent_id = match parent {
+        Some(p) => Some(resolve_project_id(client, None, Some(&p)).await?),
+        None => None,
+    };
+    if parent_id.as_deref() == Some(project_id.as_str()) {
+        return Err(crate::error::TodoError::InvalidInput(
+            "A project cannot be its own parent".to_string(),
+        ));
+    }
+    if let Some(parent_id) = &parent_id {
+        // Walk up from the new parent; reaching the project means it is a descendant
+        let resolver = Resolver::load(client).await?;
+        let mut seen = std::collections::HashSet::new();
+        let mut current = Some(parent_id.clone());
+        while let Some(id) = current.filter(|id| seen.insert(id.clone())) {
+            if id == project_id {
+                return Err(crate::error::TodoError::InvalidInput(format!(
+                    "Cannot move project '{}' under its own subproject '{}'",
+                    project_id, parent_id
+                )));
+            }
+            current = resolver
+                .projects()
+                .iter()
+                .find(|p| p.id == id)
+                .and_then(|p| p.parent_id.clone());
+        }
+    }
+    let builder =
+        crate::sync::CommandBuilder::new().project_move(&project_id, parent_id.as_deref());
+    client.execute(builder).await?;
+    let response = serde_json::json!({
+        "status": "success",
+        "action": "move",
+        "type": "project",
+        "id": project_id,
+        "parent_id": parent_id
+    });
+    println!("{}", serde_json::to_string_pretty(&response).unwrap());
+    Ok(())
+}
+
+pub async fn move_section(
+    client: &TodoistSyncClient,
+    section_id: Option<String>,
+    section: Option<String>,
+    project_id: Option<String>,
+    project: Option<String>,
+) -> Result<()> {
+    let section_id = resolve_section_id(client, section_id, section.as_deref()).await?;
+    let project_id = resolve_project_id(client, project_id, project.as_deref()).await?;
+    let builder = crate::sync::CommandBuilder::new().section_move(&section_id, &project_id);
+    client.execute(builder).await?;
+    let response = serde_json::json!({
+        "status": "success",
+        "action": "move",
+        "type": "section",
+        "id": section_id,
+        "project_id": project_id
+    });
+    println!("{}", serde_json::to_string_pretty(&response).unwrap());
+    Ok(())
+}
+
+/// Active tasks matching `filter`, refusing to go on past `limit` matches.
+///
+/// Unlike `get tasks`, the filter must be a known expression: there is no
+/// substring fallback, and the label or project it names must exist.
+async fn bulk_targets(
+    client: &TodoistSyncClient,
+    filter: &str,
+    limit: usize,
+) -> Result<Vec<crate::models::TaskOutput>> {
+    let (tasks, current_user) = load_task_outputs(client).await?;
+    let parsed = TaskFilter::parse(filter);
+    match &parsed {
+        TaskFilter::Text(_) => {
+            return Err(crate::error::TodoError::InvalidInput(format!(
+                "Filter '{}' is not supported for bulk operations; use @label, #project, p1-p4, \
+                 assigned to: <name>, assigned, !assigned or search: <text>",
+                filter
+            )))
+        }
+        TaskFilter::Label(label) => {
+            let resolver = Resolver::load(client).await?;
+            let known = resolver
+                .labels()
+                .iter()
+                .map(|l| &l.name)
+                .chain(tasks.iter().flat_map(|t| &t.labels))
+                .any(|l| l.to_lowercase() == *label);
+            if !known {
+                return Err(crate::error::TodoError::InvalidInput(format!(
+                    "Filter '{}' names a label that does not exist",
+                    filter
+                )));
+            }
+        }
+        TaskFilter::Project(project) => {
+            let resolver = Resolver::load(client).await?;
+            if !resolver
+                .projects()
+                .iter()
+                .any(|p| p.name.to_lowercase() == *project)
+            {
+                return Err(crate::error::TodoError::InvalidInput(format!(
+                    "Filter '{}' names a project that does not exist",
+                    filter
+                )));
+            }
+        }
+        _ => {}
+    }
+    let tasks: Vec<_> = tasks
+        .into_iter()
+        .filter(|t| !t.is_completed && parsed.matches(t, current_user.as_deref()))
+        .collect();
+    if tasks.len() > limit {
+        return Err(crate::error::TodoError::InvalidInput(format!(
+            "Filter '{}' matches {} tasks, more than --limit {}; narrow the filter or raise --limit",
+            filter,
+            tasks.len(),
+            limit
+        )));
+    }
+    Ok(tasks)
+}
+
+/// Confirms and sends a bulk operation over `ids`, chunked into as few requests as possible
+async fn run_bulk(
+    client: &TodoistSyncClient,
+    action: &str,
+    filter: &str,
+    ids: Vec<String>,
+    builder: crate::sync::CommandBuilder,
+    confirm: &Confirmation,
+) -> Result<()> {
+    if !ids.is_empty() {
+        // Scripts must opt in with --yes whatever the configuration says
+        confirm.confirm_always(&format!(
+            "{}{} {} task(s) matching '{}'?",
+            action[..1].to_uppercase(),
+            &action[1..],
+            ids.len(),
+            filter
+        ))?;
+        client.execute_chunked(builder).await?;
+    }
+    let response = serde_json::json!({
+        "status": "success",
+        "action": action,
+        "type": "tasks",
+        "filter": filter,
+        "count": ids.len(),
+        "ids": ids
+    });
+    println!("{}", serde_json::to_string_pretty(&response).unwrap());
+    Ok(())
+}
+
+/// Completes every matching task; recurring ones advance unless `forever` is set
+pub async fn complete_tasks(
+    client: &TodoistSyncClient,
+    filter: String,
+    limit: usize,
+    forever: bool,
+    confirm: &Confirmation,
+) -> Result<()> {
+    let tasks = bulk_targets(client, &filter, limit).await?;
+    let builder = tasks
+        .iter()
+        .fold(crate::sync::CommandBuilder::new(), |b, t| {
+            if t.is_recurring && !forever {
+                b.item_close(&t.id)
+            } else {
+                b.item_complete(&t.id)
+            }
+        });
+    let ids = tasks.into_iter().map(|t| t.id).collect();
+    run_bulk(client, "complete", &filter, ids, builder, confirm).await
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn move_tasks(
+    client: &TodoistSyncClient,
+    filter: String,
+    project_id: Option<String>,
+    project: Option<String>,
+    section_id: Option<String>,
+    section: Option<String>,
+    limit: usize,
+    confirm: &Confirmation,
+) -> Result<()> {
+    let (project_id, section_id) = resolve_location(
+        client,
+        project_id,
+        project.as_deref(),
+        section_id,
+        section.as_deref(),
+    )
+    .await?;
+    let project_id = project_id.ok_or_else(|| missing_reference("project"))?;
+    let tasks = bulk_targets(client, &filter, limit).await?;
+    let builder = tasks
+        .iter()
+        .fold(crate::sync::CommandBuilder::new(), |b, t| {
+            b.item_move(&t.id, &project_id, section_id.as_deref())
+        });
+    let ids = tasks.into_iter().map(|t| t.id).collect();
+    run_bulk(client, "move", &filter, ids, builder, confirm).await
+}
+
+/// Adds and removes labels and sets the priority of every matching task.
+///
+/// Tasks the edit would leave unchanged are skipped.
+pub async fn edit_tasks(
+    client: &TodoistSyncClient,
+    filter: String,
+    add_labels: Vec<String>,
+    remove_labels: Vec<String>,
+    priority: Option<Priority>,
+    limit: usize,
+    confirm: &Confirmation,
+) -> Result<()> {
+    if add_labels.is_empty() && remove_labels.is_empty() && priority.is_none() {
+        return Err(crate::error::TodoError::InvalidInput(
+            "Provide --add-label, --remove-label or --priority".to_string(),
+        ));
+    }
+    let tasks = bulk_targets(client, &filter, limit).await?;
+
+    let mut builder = crate::sync::CommandBuilder::new();
+    let mut ids = Vec::new();
+    for task in tasks {
+        let labels = merge_labels(&task.labels, &add_labels, &remove_labels);
+        let priority = priority
+            .filter(|p| *p != task.priority)
+            .map(|p| p.api_value());
+        if labels.is_none() && priority.is_none() {
+            continue;
+        }
+        let args = crate::sync::ItemUpdateArgs::new(task.id.clone())
+            .labels(labels)
+            .priority(priority);
+        builder = builder.item_update(args);
+        ids.push(task.id);
+    }
+    run_bulk(client, "edit", &filter, ids, builder, confirm).await
+}
+
+pub async fn delete_tasks(
+    client: &TodoistSyncClient,
+    filter: String,
+    limit: usize,
+    confirm: &Confirmation,
+) -> Result<()> {
+    let tasks = bulk_targets(client, &filter, limit).await?;
+    let builder = tasks
+        .iter()
+        .fold(crate::sync::CommandBuilder::new(), |b, t| {
+            b.item_delete(&t.id)
+        });
+    let ids = tasks.into_iter().map(|t| t.id).collect();
+    run_bulk(client, "delete", &filter, ids, builder, confirm).await
+}
+
 pub async fn reorder_sections(client: &TodoistSyncClient, section_ids: String) -> Result<()> {
     let sections: Vec<&str> = section_ids.split(',').map(|s| s.trim()).collect();
     let sections_with_order: Vec<(&str, i64)> = sections
@@ -596,6 +1564,24 @@ pub async fn reorder_sections(client: &TodoistSyncClient, section_ids: String) -
     Ok(())
 }
 
+pub async fn reorder_projects(client: &TodoistSyncClient, project_ids: String) -> Result<()> {
+    let projects: Vec<&str> = project_ids.split(',').map(|s| s.trim()).collect();
+    let projects_with_order: Vec<(&str, i64)> = projects
+        .iter()
+        .enumerate()
+        .map(|(i, id)| (*id, i as i64))
+        .collect();
+    client.reorder_projects(&projects_with_order).await?;
+    let response = serde_json::json!({
+        "status": "success",
+        "action": "reorder",
+        "type": "projects",
+        "project_ids": projects
+    });
+    println!("{}", serde_json::to_string_pretty(&response).unwrap());
+    Ok(())
+}
+
 /// Synchronize data with Todoist
 /// - force: true = full sync, false = incremental (or full if no sync token)
 pub async fn sync(client: &TodoistSyncClient, force: bool) -> Result<()> {
@@ -657,9 +1643,45 @@ pub fn cache_clear(client: &TodoistSyncClient) -> Result<()> {
     Ok(())
 }
 
-pub async fn batch(client: &TodoistSyncClient, commands_json: String) -> Result<()> {
-    let commands: Vec<Command> = serde_json::from_str(&commands_json)
-        .map_err(|e| crate::error::TodoError::InvalidInput(format!("Invalid batch JSON: {}", e)))?;
+pub async fn batch(
+    client: &TodoistSyncClient,
+    commands_json: String,
+    allow_unknown: bool,
+    confirm: &Confirmation,
+) -> Result<()> {
+    // Validate every command locally so typos never reach the API
+    let commands = if allow_unknown {
+        crate::sync::parse_batch_allowing_unknown(&commands_json)?
+    } else {
+        crate::sync::parse_batch(&commands_json)?
+    };
+    for (index, command) in commands.iter().enumerate() {
+        if command.kind.is_unchecked() {
+            let reason = if crate::sync::CommandKind::TYPES.contains(&command.command_type()) {
+                "has fields todorust does not know"
+            } else {
+                "is a command type todorust does not know"
+            };
+            eprintln!(
+                "Warning: command [{}] ({}) {}; sending it unchecked",
+                index,
+                command.command_type(),
+                reason
+            );
+        }
+    }
+
+    let deletes = commands
+        .iter()
+        .filter(|c| c.command_type().ends_with("_delete"))
+        .count();
+    if deletes > 0 {
+        confirm.confirm(&format!(
+            "Run batch of {} command(s) including {} delete(s)?",
+            commands.len(),
+            deletes
+        ))?;
+    }
 
     let response = client.execute_commands_with_status(&commands).await?;
 
@@ -668,62 +1690,623 @@ pub async fn batch(client: &TodoistSyncClient, commands_json: String) -> Result<
     Ok(())
 }
 
-pub fn generate_completions(shell: clap_complete::Shell) {
-    use clap::CommandFactory;
-    let mut cmd = crate::cli::Cli::command();
-    clap_complete::generate(shell, &mut cmd, "todorust", &mut std::io::stdout());
+/// Revert the last `steps` operations recorded in the undo journal
+pub async fn undo(client: &TodoistSyncClient, journal: &Journal, steps: usize) -> Result<()> {
+    let entries = journal.undoable(steps)?;
+    if entries.iter().all(|e| e.inverse.is_empty()) {
+        return Err(crate::error::TodoError::InvalidInput(match entries.len() {
+            0 => "Nothing to undo".to_string(),
+            n => format!(
+                "Nothing to undo: the last {} operation(s) cannot be undone",
+                n
+            ),
+        }));
+    }
+
+    let mut undone = Vec::new();
+    let mut not_undoable = Vec::new();
+    let mut warnings = Vec::new();
+    // Old IDs of recreated resources mapped to their new IDs, so older
+    // entries act on the recreated resources
+    let mut recreated = HashMap::new();
+    for entry in entries {
+        // Operations without inverse commands stay in the journal
+        if entry.inverse.is_empty() {
+            not_undoable.push(serde_json::json!({
+                "id": entry.id,
+                "summary": entry.summary,
+                "warnings": entry.warnings
+            }));
+            continue;
+        }
+        warnings.extend(
+            entry
+                .warnings
+                .iter()
+                .map(|w| format!("#{}: {}", entry.id, w)),
+        );
+        // Fresh UUIDs, so retrying a failed undo is not mistaken for a replay
+        let commands: Vec<Command> = entry
+            .inverse
+            .into_iter()
+            .map(|c| {
+                let mut command = Command {
+                    uuid: Command::generate_uuid(),
+                    temp_id: c
+                        .temp_id
+                        .or_else(|| c.kind.creates_resource().then(Command::generate_temp_id)),
+                    kind: c.kind,
+                };
+                command.resolve_temp_ids(&recreated);
+                command
+            })
+            .collect();
+        let response = client.execute_untracked(&commands).await?;
+        recreated.extend(response.temp_id_mapping);
+        journal.mark_undone(entry.id)?;
+        undone.push(serde_json::json!({
+            "id": entry.id,
+            "summary": entry.summary
+        }));
+    }
+
+    let response = serde_json::json!({
+        "status": "success",
+        "action": "undo",
+        "undone": undone,
+        "not_undoable": not_undoable,
+        "warnings": warnings
+    });
+    println!("{}", serde_json::to_string_pretty(&response).unwrap());
+    Ok(())
 }
 
-pub fn validate_priority(priority: u8) -> bool {
-    (1..=4).contains(&priority)
+/// List recent operations from the undo journal, newest first
+pub fn history(journal: &Journal, limit: usize) -> Result<()> {
+    let entries: Vec<serde_json::Value> = journal
+        .load()?
+        .into_iter()
+        .rev()
+        .take(limit)
+        .map(|e| {
+            serde_json::json!({
+                "id": e.id,
+                "recorded_at": chrono::DateTime::from_timestamp(e.recorded_at, 0)
+                    .map(|t| t.to_rfc3339())
+                    .unwrap_or_default(),
+                "summary": e.summary,
+                "undoable": !e.undone && !e.inverse.is_empty(),
+                "undone": e.undone,
+                "warnings": e.warnings
+            })
+        })
+        .collect();
+    println!("{}", serde_json::to_string_pretty(&entries).unwrap());
+    Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use httpmock::prelude::*;
-    use serde_json::json;
+/// Two-way sync of the checklist lines of a Markdown file with Todoist.
+///
+/// Changes in the file are sent as one batch; changes in Todoist and the ids of
+/// new tasks are written back to the file.
+pub async fn md_sync(
+    client: &TodoistSyncClient,
+    state: &crate::markdown::SyncState,
+    path: &std::path::Path,
+    default_project: Option<&str>,
+) -> Result<()> {
+    use crate::markdown::{Field, Fields};
 
-    #[tokio::test]
-    async fn test_get_tasks_handler() {
-        let server = MockServer::start();
-        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
+    let text = std::fs::read_to_string(path)?;
+    let resolver = Resolver::load(client).await?;
+    let label_names: Vec<&str> = resolver.labels().iter().map(|l| l.name.as_str()).collect();
+    let mut items = crate::markdown::parse(&text, &label_names);
 
-        server.mock(|when, then| {
-            when.method(POST).path("/sync");
-            then.status(200).json_body(json!({
-                "sync_token": "token123",
-                "items": [
-                    {
-                        "id": "1",
-                        "content": "Task 1",
-                        "priority": 4,
-                        "checked": false,
-                        "added_at": "2024-01-01T00:00:00Z",
-                        "child_order": 1
-                    }
-                ],
-                "projects": []
-            }));
-        });
+    let (tasks, _) = load_task_outputs(client).await?;
+    let tasks: HashMap<&str, &crate::models::TaskOutput> =
+        tasks.iter().map(|t| (t.id.as_str(), t)).collect();
+    let base = state.load(path)?;
 
-        let result = get_tasks(&client, None, &OutputFormat::Json, None, None).await;
-        assert!(result.is_ok());
-    }
+    let mut commands = Vec::new();
+    // Index into `items` of each task to create, by temp id
+    let mut created = Vec::new();
+    let mut changed = Vec::new();
+    // Task id and index into `items` of each command on an existing task, by uuid
+    let mut owners = HashMap::new();
+    let mut recorded = HashMap::new();
+    let mut conflicts = Vec::new();
+    let mut missing = Vec::new();
+    let (mut updated, mut completed, mut reopened, mut pulled) = (0, 0, 0, 0);
 
-    #[tokio::test]
-    async fn test_get_projects_handler() {
-        let server = MockServer::start();
-        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
+    for (index, item) in items.iter_mut().enumerate() {
+        let Some(id) = item.id.clone() else {
+            // Ticked lines without an id are done already
+            if item.fields.checked || item.fields.content.is_empty() {
+                continue;
+            }
+            // Headings that are not project names fall back to the default project
+            let project_id = [item.heading.as_deref(), default_project]
+                .into_iter()
+                .flatten()
+                .find_map(|p| resolver.project(p).ok())
+                .map(|p| p.id.clone());
+            let fields = &item.fields;
+            let args = crate::sync::ItemAddArgs::new(fields.content.clone())
+                .project_id(project_id)
+                .due_string(item.recurrence.clone().or_else(|| fields.due.clone()))
+                .priority((fields.priority != Priority::LOW).then(|| fields.priority.api_value()))
+                .labels((!fields.labels.is_empty()).then(|| fields.labels.clone()));
+            let command = Command::new(crate::sync::CommandKind::ItemAdd(args));
+            created.push((command.temp_id.clone().unwrap_or_default(), index));
+            commands.push(command);
+            continue;
+        };
 
-        server.mock(|when, then| {
-            when.method(POST).path("/sync");
-            then.status(200).json_body(json!({
-                "sync_token": "token123",
-                "projects": [
-                    {
-                        "id": "p1",
-                        "name": "Project 1",
+        let Some(task) = tasks.get(id.as_str()) else {
+            // Unticked in the file since the last sync
+            let unticked = !item.fields.checked && base.get(&id).is_some_and(|b| b.checked);
+            if unticked {
+                for command in crate::sync::CommandBuilder::new()
+                    .item_uncomplete(&id)
+                    .build()
+                {
+                    owners.insert(command.uuid.clone(), (id.clone(), index));
+                    commands.push(command);
+                }
+                reopened += 1;
+            } else if !item.fields.checked {
+                // Completed or deleted in Todoist
+                item.fields.checked = true;
+                changed.push(index);
+                missing.push(id.clone());
+            }
+            recorded.insert(id, item.fields.clone());
+            continue;
+        };
+
+        let merge =
+            crate::markdown::merge(&id, &item.fields, &Fields::from_task(task), base.get(&id));
+        let mut builder = crate::sync::CommandBuilder::new();
+        let mut update = crate::sync::ItemUpdateArgs::new(id.clone());
+        let mut has_update = false;
+        let mut merged = merge.merged.clone();
+        let mut recorded_fields = merge.base.clone();
+        for field in &merge.push {
+            match field {
+                Field::Checked if item.fields.checked && task.is_recurring => {
+                    // Closing a recurring task moves it to its next date
+                    builder = builder.item_close(&id);
+                    merged.checked = false;
+                    recorded_fields.checked = false;
+                    item.fields.checked = false;
+                    changed.push(index);
+                    completed += 1;
+                }
+                Field::Checked if item.fields.checked => {
+                    builder = builder.item_complete(&id);
+                    completed += 1;
+                }
+                Field::Checked => {
+                    builder = builder.item_uncomplete(&id);
+                    reopened += 1;
+                }
+                // Recurring tasks keep their schedule
+                Field::Due if task.is_recurring => {
+                    merged.due = recorded_fields.due.clone();
+                }
+                field => {
+                    let fields = &item.fields;
+                    update = match field {
+                        Field::Content => update.content(Some(fields.content.clone())),
+                        Field::Priority => update.priority(Some(fields.priority.api_value())),
+                        Field::Labels => update.labels(Some(fields.labels.clone())),
+                        _ => update.due_string(Some(
+                            fields.due.clone().unwrap_or_else(|| "no date".to_string()),
+                        )),
+                    };
+                    has_update = true;
+                }
+            }
+        }
+        if has_update {
+            builder = builder.item_update(update);
+            updated += 1;
+        }
+        for command in builder.build() {
+            owners.insert(command.uuid.clone(), (id.clone(), index));
+            commands.push(command);
+        }
+
+        let recurrence = task.is_recurring.then(|| task.due_string.clone()).flatten();
+        if merged != item.fields || recurrence != item.recurrence {
+            if merged.checked && !item.fields.checked {
+                item.done = task.completed_at.as_deref().map(crate::markdown::date_part);
+            }
+            item.fields = merged;
+            item.recurrence = recurrence;
+            changed.push(index);
+            pulled += 1;
+        }
+        conflicts.extend(merge.conflicts);
+        recorded.insert(id, recorded_fields);
+    }
+
+    let mut failed = Vec::new();
+    let mut error = None;
+    if !commands.is_empty() {
+        let outcome = client.execute_chunked_partial(commands.into(), false).await;
+        if let Some(crate::error::TodoError::DryRun) = outcome.error {
+            return Err(crate::error::TodoError::DryRun);
+        }
+        // Tasks created before a failure keep their ids, so they are not created again
+        for (temp_id, index) in &created {
+            if let Some(id) = outcome.temp_id_mapping.get(temp_id) {
+                let item = &mut items[*index];
+                item.id = Some(id.clone());
+                recorded.insert(id.clone(), item.fields.clone());
+                changed.push(*index);
+            }
+        }
+        // Lines whose commands failed or were not sent keep their text and last
+        // synced state, so the next sync tries again
+        let rejected: std::collections::HashSet<&String> = outcome
+            .failures
+            .iter()
+            .map(|(uuid, _)| uuid)
+            .chain(&outcome.unsent)
+            .collect();
+        for (uuid, (id, index)) in &owners {
+            if !rejected.contains(uuid) {
+                continue;
+            }
+            changed.retain(|i| i != index);
+            match base.get(id) {
+                Some(fields) => recorded.insert(id.clone(), fields.clone()),
+                None => recorded.remove(id),
+            };
+        }
+        for (uuid, status) in &outcome.failures {
+            failed.push(serde_json::json!({
+                "task_id": owners.get(uuid).map(|(id, _)| id),
+                "error": status
+            }));
+        }
+        error = outcome.error.or_else(|| {
+            (!failed.is_empty()).then(|| {
+                crate::error::TodoError::Api(format!(
+                    "{} Markdown sync commands failed",
+                    failed.len()
+                ))
+            })
+        });
+    }
+
+    if !changed.is_empty() {
+        let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+        for &index in &changed {
+            lines[items[index].line] = items[index].render();
+        }
+        let mut output = lines.join("\n");
+        if text.ends_with('\n') {
+            output.push('\n');
+        }
+        std::fs::write(path, output)?;
+    }
+    state.save(path, recorded)?;
+
+    let response = serde_json::json!({
+        "status": if error.is_some() { "partial" } else { "success" },
+        "file": path.display().to_string(),
+        "created": created.len(),
+        "updated": updated,
+        "completed": completed,
+        "reopened": reopened,
+        "pulled": pulled,
+        "missing": missing,
+        "conflicts": conflicts,
+        "failed": failed
+    });
+    println!("{}", serde_json::to_string_pretty(&response).unwrap());
+    error.map_or(Ok(()), Err)
+}
+
+/// Export dated tasks as an iCalendar feed, to stdout or to `output`
+pub async fn export_ics(
+    client: &TodoistSyncClient,
+    filter: Option<&str>,
+    component: crate::ics::Component,
+    output: Option<&std::path::Path>,
+) -> Result<()> {
+    let (task_outputs, current_user) = load_task_outputs(client).await?;
+    let tasks = match filter {
+        Some(f) => filter_tasks(task_outputs, f, current_user.as_deref()),
+        None => task_outputs,
+    };
+    let items = client.sync_with_cache(&["items"]).await?.items;
+    let dues: HashMap<&str, &crate::sync::SyncDue> = items
+        .iter()
+        .filter_map(|t| t.due.as_ref().map(|d| (t.id.as_str(), d)))
+        .collect();
+    let dated: Vec<_> = tasks
+        .iter()
+        .filter_map(|t| dues.get(t.id.as_str()).map(|d| (t, *d)))
+        .collect();
+    let calendar = crate::ics::calendar(&dated, component, chrono::Utc::now());
+
+    let Some(path) = output else {
+        print!("{}", calendar);
+        return Ok(());
+    };
+    // Write a temporary file first so subscribers never read a partial calendar
+    let partial = path.with_extension("ics.partial");
+    std::fs::write(&partial, &calendar)?;
+    std::fs::rename(&partial, path)?;
+    let response = serde_json::json!({
+        "status": "success",
+        "file": path.display().to_string(),
+        "tasks": dated.len()
+    });
+    println!("{}", serde_json::to_string_pretty(&response).unwrap());
+    Ok(())
+}
+
+/// Print a backup of the whole account as JSON
+pub async fn export_all(client: &TodoistSyncClient) -> Result<()> {
+    let backup = load_backup(client).await?;
+    for warning in &backup.warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    println!("{}", serde_json::to_string_pretty(&backup).unwrap());
+    Ok(())
+}
+
+/// Synced resources plus the archived projects the Sync API leaves out
+async fn load_backup(client: &TodoistSyncClient) -> Result<crate::backup::Backup> {
+    let mut data = client
+        .snapshot(&[
+            "projects", "sections", "items", "labels", "filters", "notes",
+        ])
+        .await?;
+    let archived: Vec<_> = client
+        .get_archived_projects()
+        .await?
+        .into_iter()
+        .filter(|a| !data.projects.iter().any(|p| p.id == a.id))
+        .map(|mut p| {
+            p.is_archived = true;
+            p
+        })
+        .collect();
+    let warning = (!archived.is_empty()).then(|| {
+        format!(
+            "{} archived project(s) are backed up without their sections, tasks and comments",
+            archived.len()
+        )
+    });
+    data.projects.extend(archived);
+    let mut backup = crate::backup::Backup::new(data, chrono::Utc::now().to_rfc3339());
+    backup.warnings.extend(warning);
+    Ok(backup)
+}
+
+/// Recreate the resources of a backup, in batches of chained commands
+pub async fn import_backup(
+    client: &TodoistSyncClient,
+    path: &std::path::Path,
+    into: Option<&str>,
+) -> Result<()> {
+    let backup = crate::backup::Backup::parse(&std::fs::read_to_string(path)?)?;
+    let resolver = Resolver::load(client).await?;
+    let filters = client.get_filters().await?;
+    let label_names: Vec<&str> = resolver.labels().iter().map(|l| l.name.as_str()).collect();
+    let filter_names: Vec<&str> = filters.iter().map(|f| f.name.as_str()).collect();
+    let target = crate::backup::Target {
+        into,
+        inbox_id: resolver
+            .projects()
+            .iter()
+            .find(|p| p.inbox_project)
+            .map(|p| p.id.as_str()),
+        labels: &label_names,
+        filters: &filter_names,
+    };
+    let restore = crate::backup::restore(&backup, &target);
+    send_import(client, restore.commands, &restore.summary, restore.root).await
+}
+
+/// Add tasks exported by another tool
+pub async fn import_tasks(
+    client: &TodoistSyncClient,
+    path: &std::path::Path,
+    source: crate::import::Source,
+    into: Option<&str>,
+) -> Result<()> {
+    let entries = crate::import::parse(source, &std::fs::read_to_string(path)?)?;
+    let resolver = Resolver::load(client).await?;
+    let plan = crate::import::plan(&entries, resolver.projects(), into);
+    send_import(client, plan.commands, &plan.summary, plan.root).await
+}
+
+/// Sends the commands of an import in chunks, or prints them in dry-run mode.
+///
+/// When a chunk fails, the output lists the resources created before the
+/// failure, so they are not imported twice.
+async fn send_import(
+    client: &TodoistSyncClient,
+    commands: Vec<Command>,
+    summary: &impl serde::Serialize,
+    root: Option<String>,
+) -> Result<()> {
+    if client.is_dry_run() {
+        let response = serde_json::json!({
+            "status": "dry_run",
+            "action": "import",
+            "summary": summary,
+            "commands": commands
+        });
+        println!("{}", serde_json::to_string_pretty(&response).unwrap());
+        return Ok(());
+    }
+
+    let total = commands.len();
+    let created: Vec<(String, String)> = commands
+        .iter()
+        .filter_map(|c| Some((c.temp_id.clone()?, c.command_type().to_string())))
+        .collect();
+    let outcome = client.execute_chunked_partial(commands.into(), true).await;
+    let root_project_id = root.and_then(|temp_id| outcome.temp_id_mapping.get(&temp_id).cloned());
+
+    let error = outcome.error.or_else(|| {
+        outcome.failures.first().map(|(uuid, status)| {
+            crate::error::TodoError::Api(format!("Command {} failed: {}", uuid, status))
+        })
+    });
+    let Some(error) = error else {
+        let response = serde_json::json!({
+            "status": "success",
+            "action": "import",
+            "summary": summary,
+            "root_project_id": root_project_id
+        });
+        println!("{}", serde_json::to_string_pretty(&response).unwrap());
+        return Ok(());
+    };
+
+    let applied = total - outcome.unsent.len() - outcome.failures.len();
+    let created: Vec<serde_json::Value> = created
+        .iter()
+        .filter_map(|(temp_id, kind)| {
+            let id = outcome.temp_id_mapping.get(temp_id)?;
+            Some(serde_json::json!({"type": kind, "temp_id": temp_id, "id": id}))
+        })
+        .collect();
+    let response = serde_json::json!({
+        "status": "partial",
+        "action": "import",
+        "summary": summary,
+        "applied_commands": applied,
+        "total_commands": total,
+        "root_project_id": root_project_id,
+        "created": created
+    });
+    println!("{}", serde_json::to_string_pretty(&response).unwrap());
+    Err(crate::error::TodoError::Api(format!(
+        "Import stopped after {} of {} commands ({} resources created): {}",
+        applied,
+        total,
+        created.len(),
+        error
+    )))
+}
+
+/// Print the commands captured in dry-run mode and their predicted effect
+pub async fn dry_run_report(client: &TodoistSyncClient) -> Result<()> {
+    let response = dry_run_output(client).await?;
+    println!("{}", serde_json::to_string_pretty(&response).unwrap());
+    Ok(())
+}
+
+/// The commands captured in dry-run mode and their predicted effect
+async fn dry_run_output(client: &TodoistSyncClient) -> Result<serde_json::Value> {
+    let commands = client.take_planned_commands();
+    let data = match client.get_cached_data() {
+        Some(data) => data,
+        None => {
+            let response = client
+                .sync_with_cache(&["projects", "items", "sections", "labels", "filters"])
+                .await?;
+            crate::sync::CacheData {
+                projects: response.projects,
+                items: response.items,
+                sections: response.sections,
+                labels: response.labels,
+                filters: response.filters,
+                ..Default::default()
+            }
+        }
+    };
+    let cache_diff = crate::sync::predict_changes(&commands, &data);
+
+    Ok(serde_json::json!({
+        "status": "dry_run",
+        "commands": commands,
+        "cache_diff": cache_diff
+    }))
+}
+
+/// Print the JSON Schema for `batch` input
+pub fn batch_schema() {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&crate::sync::batch_schema()).unwrap()
+    );
+}
+
+pub fn generate_completions(shell: clap_complete::Shell) {
+    use clap::CommandFactory;
+    let mut cmd = crate::cli::Cli::command();
+    clap_complete::generate(shell, &mut cmd, "todorust", &mut std::io::stdout());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::prelude::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_get_tasks_handler() {
+        let server = MockServer::start();
+        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
+
+        server.mock(|when, then| {
+            when.method(POST).path("/sync");
+            then.status(200).json_body(json!({
+                "sync_token": "token123",
+                "items": [
+                    {
+                        "id": "1",
+                        "content": "Task 1",
+                        "priority": 4,
+                        "checked": false,
+                        "added_at": "2024-01-01T00:00:00Z",
+                        "child_order": 1
+                    }
+                ],
+                "projects": []
+            }));
+        });
+
+        let result = get_tasks(&client, None, &OutputFormat::Json, None, &[], None, None).await;
+        assert!(result.is_ok());
+
+        let sort = ["due".parse().unwrap(), "-priority".parse().unwrap()];
+        let group_by = Some(crate::listing::GroupBy::Section);
+        let result = get_tasks(
+            &client,
+            None,
+            &OutputFormat::Csv,
+            None,
+            &sort,
+            group_by,
+            Some(1),
+        );
+        assert!(result.await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_get_projects_handler() {
+        let server = MockServer::start();
+        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
+
+        server.mock(|when, then| {
+            when.method(POST).path("/sync");
+            then.status(200).json_body(json!({
+                "sync_token": "token123",
+                "projects": [
+                    {
+                        "id": "p1",
+                        "name": "Project 1",
                         "color": "blue",
                         "child_order": 1,
                         "added_at": "2024-01-01T00:00:00Z"
@@ -732,10 +2315,53 @@ mod tests {
             }));
         });
 
-        let result = get_projects(&client, &OutputFormat::Json, None).await;
+        let result = get_projects(&client, &OutputFormat::Json, None, false).await;
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_archived_projects_paginate_and_unarchive_by_name() {
+        let server = MockServer::start();
+        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
+
+        let second_page = server.mock(|when, then| {
+            when.method(GET)
+                .path("/projects/archived")
+                .query_param("cursor", "next");
+            then.status(200).json_body(json!({
+                "results": [{"id": "p2", "name": "Old Work"}],
+                "next_cursor": null
+            }));
+        });
+        server.mock(|when, then| {
+            when.method(GET).path("/projects/archived");
+            then.status(200).json_body(json!({
+                "results": [{"id": "p1", "name": "Attic"}],
+                "next_cursor": "next"
+            }));
+        });
+        let write = server.mock(|when, then| {
+            when.method(POST)
+                .path("/sync")
+                .body_includes("project_unarchive")
+                .body_includes("p2");
+            then.status(200)
+                .json_body(json!({"sync_token": "t", "sync_status": {}}));
+        });
+
+        let archived = client.get_archived_projects().await.unwrap();
+        assert_eq!(archived.len(), 2);
+        assert!(get_projects(&client, &OutputFormat::Json, None, true)
+            .await
+            .is_ok());
+
+        archive_project(&client, None, Some("old work".to_string()), false)
+            .await
+            .unwrap();
+        write.assert_calls(1);
+        second_page.assert_calls(3);
+    }
+
     #[tokio::test]
     async fn test_add_task_handler() {
         let server = MockServer::start();
@@ -757,7 +2383,13 @@ mod tests {
             None,
             None,
             None,
-            Some(4),
+            None,
+            None,
+            Some(Priority::URGENT),
+            None,
+            None,
+            None,
+            false,
             None,
         )
         .await;
@@ -765,38 +2397,93 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_batch_handler() {
+    async fn test_add_task_uses_inbox_or_default_project() {
         let server = MockServer::start();
         let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
 
+        let to_inbox = server.mock(|when, then| {
+            when.method(POST)
+                .path("/sync")
+                .body_includes("item_add")
+                .body_includes("%22project_id%22%3A%22p0%22");
+            then.status(200).json_body(json!({
+                "sync_token": "t",
+                "sync_status": {},
+                "temp_id_mapping": {"tmp": "t1"}
+            }));
+        });
+        let to_default = server.mock(|when, then| {
+            when.method(POST)
+                .path("/sync")
+                .body_includes("item_add")
+                .body_includes("%22project_id%22%3A%22p1%22");
+            then.status(200).json_body(json!({
+                "sync_token": "t",
+                "sync_status": {},
+                "temp_id_mapping": {"tmp": "t2"}
+            }));
+        });
         server.mock(|when, then| {
             when.method(POST).path("/sync");
             then.status(200).json_body(json!({
                 "sync_token": "token123",
-                "sync_status": {"uuid1": "ok"},
-                "temp_id_mapping": {"temp1": "real1"}
+                "projects": [
+                    {"id": "p0", "name": "Inbox", "inbox_project": true},
+                    {"id": "p1", "name": "Work"}
+                ]
             }));
         });
 
-        let commands_json = r#"[{"type": "item_add", "args": {"content": "Task 1"}}]"#;
-        let result = batch(&client, commands_json.to_string()).await;
-        assert!(result.is_ok());
+        let add = |inbox: bool| {
+            add_task(
+                &client,
+                Some("Task".to_string()),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                inbox,
+                Some("work"),
+            )
+        };
+        add(true).await.unwrap();
+        add(false).await.unwrap();
+        to_inbox.assert_calls(1);
+        to_default.assert_calls(1);
     }
 
     #[tokio::test]
-    async fn test_get_sections_handler() {
+    async fn test_add_quick_handler() {
         let server = MockServer::start();
         let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
 
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/sync")
+                .body_includes("commands")
+                .body_includes("section_id");
+            then.status(200).json_body(json!({
+                "sync_token": "token123",
+                "sync_status": {"uuid": "ok"},
+                "temp_id_mapping": {"temp": "real_id"}
+            }));
+        });
         server.mock(|when, then| {
             when.method(POST).path("/sync");
             then.status(200).json_body(json!({
                 "sync_token": "token123",
+                "projects": [{"id": "p1", "name": "Errands"}],
                 "sections": [
                     {
                         "id": "s1",
                         "project_id": "p1",
-                        "name": "Section 1",
+                        "name": "Groceries",
                         "order": 1,
                         "created_at": "2024-01-01T00:00:00Z"
                     }
@@ -804,35 +2491,821 @@ mod tests {
             }));
         });
 
-        let result = get_sections(&client, Some("p1"), &OutputFormat::Json, None).await;
-        assert!(result.is_ok());
+        let result = add_quick(&client, "Buy milk tomorrow #errands @store p1 /groc", None).await;
+        assert!(result.is_ok());
+        mock.assert();
+
+        // A dry run prints the parse instead of sending
+        let dry_run = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"))
+            .with_dry_run(true);
+        add_quick(&dry_run, "Buy milk tomorrow #errands p1 /groc", None)
+            .await
+            .unwrap();
+        mock.assert_calls(1);
+    }
+
+    #[test]
+    fn test_quick_add_preview_shows_parsed_fields() {
+        let parsed = crate::quick_add::parse("Buy milk tomorrow #Errands @store p1").unwrap();
+        assert_eq!(
+            quick_add_preview(&parsed),
+            json!({
+                "content": "Buy milk",
+                "due_string": "tomorrow",
+                "priority": "p1",
+                "labels": ["store"],
+                "project": "Errands",
+                "section": null
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_records_commands_without_sending() {
+        let server = MockServer::start();
+        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"))
+            .with_dry_run(true);
+        let write = server.mock(|when, then| {
+            when.method(POST).path("/sync").body_includes("commands");
+            then.status(500);
+        });
+        server.mock(|when, then| {
+            when.method(POST).path("/sync");
+            then.status(200).json_body(json!({
+                "sync_token": "token123",
+                "projects": [{"id": "p1", "name": "Errands"}]
+            }));
+        });
+
+        let result = add_task(
+            &client,
+            Some("Buy milk".to_string()),
+            None,
+            None,
+            None,
+            Some("Errands".to_string()),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            false,
+            None,
+        )
+        .await;
+        assert!(matches!(result, Err(crate::error::TodoError::DryRun)));
+        write.assert_calls(0);
+
+        let planned = client.take_planned_commands();
+        assert_eq!(planned.len(), 1);
+        assert_eq!(planned[0].command_type(), "item_add");
+
+        assert!(dry_run_report(&client).await.is_ok());
+        write.assert_calls(0);
+
+        // Quick add prints its own report, including the parse
+        assert!(add_quick(&client, "Buy milk #Errands", None).await.is_ok());
+        assert!(client.take_planned_commands().is_empty());
+        write.assert_calls(0);
+
+        // A move plans the other changes of the same edit too
+        let result = edit_task(
+            &client,
+            "t1".to_string(),
+            Some("Buy oat milk".to_string()),
+            None,
+            None,
+            Some("Errands".to_string()),
+            None,
+            Some(Priority::HIGH),
+            None,
+            vec![],
+            vec![],
+            None,
+            false,
+            None,
+        )
+        .await;
+        assert!(matches!(result, Err(crate::error::TodoError::DryRun)));
+        let planned = client.take_planned_commands();
+        let types: Vec<_> = planned.iter().map(|c| c.command_type()).collect();
+        assert_eq!(types, ["item_move", "item_update"]);
+        write.assert_calls(0);
+    }
+
+    #[tokio::test]
+    async fn test_undo_handler_replays_inverse_and_marks_entry() {
+        let server = MockServer::start();
+        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/sync")
+                .body_includes("item_uncomplete");
+            then.status(200)
+                .json_body(json!({"sync_token": "t", "sync_status": {}}));
+        });
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let journal = Journal::with_path(temp_dir.path().join("journal.json"));
+        let commands = crate::sync::CommandBuilder::new().item_close("t1").build();
+        journal
+            .record(
+                &commands,
+                crate::sync::CommandBuilder::new()
+                    .item_uncomplete("t1")
+                    .build(),
+                vec![],
+            )
+            .unwrap();
+
+        // A newer operation without inverse is skipped, not marked undone
+        let recurring = journal
+            .record(
+                &crate::sync::CommandBuilder::new().item_close("t2").build(),
+                vec![],
+                vec!["item_close cannot be undone: previous state unknown".to_string()],
+            )
+            .unwrap();
+
+        assert!(undo(&client, &journal, 1).await.is_ok());
+        mock.assert();
+        let remaining = journal.undoable(1).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, recurring.id);
+
+        // Nothing left to undo
+        let err = undo(&client, &journal, 1).await.unwrap_err();
+        assert!(err.to_string().contains("cannot be undone"));
+        assert!(history(&journal, 10).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_undo_remaps_ids_of_recreated_resources() {
+        let server = MockServer::start();
+        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
+        let recreate = server.mock(|when, then| {
+            when.method(POST).path("/sync").body_includes("item_add");
+            then.status(200).json_body(json!({
+                "sync_token": "t",
+                "sync_status": {},
+                "temp_id_mapping": {"t1": "n1"}
+            }));
+        });
+        let update = server.mock(|when, then| {
+            when.method(POST)
+                .path("/sync")
+                .body_includes("item_update")
+                .body_includes("%22n1%22");
+            then.status(200)
+                .json_body(json!({"sync_token": "t", "sync_status": {}}));
+        });
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let journal = Journal::with_path(temp_dir.path().join("journal.json"));
+        // edit t1, then delete t1
+        journal
+            .record(
+                &crate::sync::CommandBuilder::new()
+                    .item_update(
+                        crate::sync::ItemUpdateArgs::new("t1".to_string()).priority(Some(4)),
+                    )
+                    .build(),
+                crate::sync::CommandBuilder::new()
+                    .item_update(
+                        crate::sync::ItemUpdateArgs::new("t1".to_string()).priority(Some(1)),
+                    )
+                    .build(),
+                vec![],
+            )
+            .unwrap();
+        let mut readd = crate::sync::CommandBuilder::new()
+            .item_add(crate::sync::ItemAddArgs::new("Report".to_string()))
+            .build();
+        readd[0].temp_id = Some("t1".to_string());
+        journal
+            .record(
+                &crate::sync::CommandBuilder::new().item_delete("t1").build(),
+                readd,
+                vec![],
+            )
+            .unwrap();
+
+        undo(&client, &journal, 2).await.unwrap();
+        recreate.assert_calls(1);
+        update.assert_calls(1);
+    }
+
+    #[tokio::test]
+    async fn test_batch_handler() {
+        let server = MockServer::start();
+        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
+
+        server.mock(|when, then| {
+            when.method(POST).path("/sync");
+            then.status(200).json_body(json!({
+                "sync_token": "token123",
+                "sync_status": {"uuid1": "ok"},
+                "temp_id_mapping": {"temp1": "real1"}
+            }));
+        });
+
+        let commands_json = r#"[{"type": "item_add", "args": {"content": "Task 1"}}]"#;
+        let result = batch(
+            &client,
+            commands_json.to_string(),
+            false,
+            &Confirmation::assume_yes(),
+        )
+        .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_batch_handler_rejects_invalid_commands_before_sending() {
+        let server = MockServer::start();
+        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
+        let mock = server.mock(|when, then| {
+            when.method(POST).path("/sync");
+            then.status(200)
+                .json_body(json!({"sync_token": "t", "sync_status": {}}));
+        });
+
+        let commands_json = r#"[{"type": "item_add", "args": {"title": "Task 1"}}]"#;
+        let err = batch(
+            &client,
+            commands_json.to_string(),
+            false,
+            &Confirmation::assume_yes(),
+        )
+        .await
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("[0]"), "{}", err);
+        assert!(err.contains("args.title"), "{}", err);
+
+        let commands_json = r#"[{"type": "reminder_add", "args": {"item_id": "1"}}]"#;
+        let err = batch(
+            &client,
+            commands_json.to_string(),
+            false,
+            &Confirmation::assume_yes(),
+        )
+        .await
+        .unwrap_err()
+        .to_string();
+        assert!(
+            err.contains("unknown command type `reminder_add`"),
+            "{}",
+            err
+        );
+        mock.assert_calls(0);
+
+        // Sent unchecked only when explicitly allowed
+        batch(
+            &client,
+            commands_json.to_string(),
+            true,
+            &Confirmation::assume_yes(),
+        )
+        .await
+        .unwrap();
+        mock.assert_calls(1);
+    }
+
+    #[tokio::test]
+    async fn test_get_sections_handler() {
+        let server = MockServer::start();
+        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
+
+        server.mock(|when, then| {
+            when.method(POST).path("/sync");
+            then.status(200).json_body(json!({
+                "sync_token": "token123",
+                "sections": [
+                    {
+                        "id": "s1",
+                        "project_id": "p1",
+                        "name": "Section 1",
+                        "order": 1,
+                        "created_at": "2024-01-01T00:00:00Z"
+                    }
+                ]
+            }));
+        });
+
+        let result = get_sections(&client, Some("p1"), None, &OutputFormat::Json, None).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_collaborators_and_assignment() {
+        let server = MockServer::start();
+        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
+
+        let assign = server.mock(|when, then| {
+            when.method(POST)
+                .path("/sync")
+                .body_includes("item_add")
+                .body_includes("%22responsible_uid%22%3A%22u2%22");
+            then.status(200).json_body(json!({
+                "sync_token": "t",
+                "sync_status": {},
+                "temp_id_mapping": {"tmp": "t9"}
+            }));
+        });
+        server.mock(|when, then| {
+            when.method(POST).path("/sync");
+            then.status(200).json_body(json!({
+                "sync_token": "token123",
+                "projects": [
+                    {"id": "p1", "name": "Team", "shared": true},
+                    {"id": "p2", "name": "Solo"}
+                ],
+                "items": [
+                    {"id": "t1", "content": "Mine", "project_id": "p1", "responsible_uid": "u1"},
+                    {"id": "t2", "content": "Bea's", "project_id": "p1", "responsible_uid": "u2"},
+                    {"id": "t3", "content": "Nobody's", "project_id": "p1"}
+                ],
+                "collaborators": [
+                    {"id": "u2", "email": "bea@example.com", "full_name": "Bea Silva"},
+                    {"id": "u3", "email": "cal@example.com", "full_name": "Cal"}
+                ],
+                "collaborator_states": [
+                    {"project_id": "p1", "user_id": "u1", "state": "active"},
+                    {"project_id": "p1", "user_id": "u2", "state": "active"},
+                    {"project_id": "p1", "user_id": "u3", "state": "invited"}
+                ],
+                "user": {"id": "u1", "email": "me@example.com", "full_name": "Me"}
+            }));
+        });
+
+        get_collaborators(&client, None, Some("Team"), &OutputFormat::Json, None)
+            .await
+            .unwrap();
+
+        let (tasks, me) = load_task_outputs(&client).await.unwrap();
+        assert_eq!(tasks[1].assignee.as_deref(), Some("Bea Silva"));
+        let mine = filter_tasks(tasks, "assigned to: me", me.as_deref());
+        assert_eq!(mine.len(), 1);
+        assert_eq!(mine[0].id, "t1");
+
+        let add = |project: &str, assignee: &str| {
+            add_task(
+                &client,
+                Some("Review".to_string()),
+                None,
+                None,
+                None,
+                Some(project.to_string()),
+                None,
+                None,
+                None,
+                None,
+                Some(assignee.to_string()),
+                None,
+                false,
+                None,
+            )
+        };
+        add("Team", "BEA@example.com").await.unwrap();
+        assign.assert_calls(1);
+        // Invited collaborators and unshared projects can't be assigned
+        assert!(add("Team", "Cal").await.is_err());
+        assert!(add("Solo", "Bea")
+            .await
+            .unwrap_err()
+            .to_string()
+            .contains("not shared"));
+    }
+
+    #[tokio::test]
+    async fn test_get_agenda_handler() {
+        let server = MockServer::start();
+        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
+
+        server.mock(|when, then| {
+            when.method(POST).path("/sync");
+            then.status(200).json_body(json!({
+                "sync_token": "token123",
+                "items": [{
+                    "id": "t1",
+                    "content": "Standup",
+                    "due": {"date": "2026-01-20T09:00:00"},
+                    "duration": {"amount": 15, "unit": "minute"}
+                }]
+            }));
+        });
+
+        get_agenda(&client, "2026-01-20", &OutputFormat::Structured, None)
+            .await
+            .unwrap();
+        assert!(get_agenda(&client, "someday", &OutputFormat::Json, None)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_filters_handler() {
+        let server = MockServer::start();
+        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
+
+        server.mock(|when, then| {
+            when.method(POST).path("/sync");
+            then.status(200).json_body(json!({
+                "sync_token": "token123",
+                "filters": [
+                    {
+                        "id": "f1",
+                        "name": "Filter 1",
+                        "query": "today"
+                    }
+                ]
+            }));
+        });
+
+        let result = get_filters(&client, &OutputFormat::Json, None).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_get_labels_handler() {
+        let server = MockServer::start();
+        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
+
+        server.mock(|when, then| {
+            when.method(POST).path("/sync");
+            then.status(200).json_body(json!({
+                "sync_token": "token123",
+                "labels": [
+                    {
+                        "id": "l1",
+                        "name": "Label 1",
+                        "color": "red"
+                    }
+                ]
+            }));
+        });
+
+        let result = get_labels(&client, &OutputFormat::Json, None).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_edit_task_handler() {
+        let server = MockServer::start();
+        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
+
+        server.mock(|when, then| {
+            when.method(POST).path("/sync");
+            then.status(200).json_body(json!({
+                "sync_token": "token123",
+                "sync_status": {"uuid": "ok"}
+            }));
+        });
+
+        let result = edit_task(
+            &client,
+            "123".to_string(),
+            Some("Updated Title".to_string()),
+            None,
+            None,
+            None,
+            None,
+            Some(Priority::HIGH),
+            None,
+            vec![],
+            vec![],
+            None,
+            false,
+            None,
+        )
+        .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_edit_task_add_remove_labels_from_cache() {
+        let server = MockServer::start();
+        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
+
+        let write = server.mock(|when, then| {
+            when.method(POST)
+                .path("/sync")
+                .body_includes("commands")
+                .body_includes(r#"%22labels%22%3A%5B%22home%22%2C%22next%22%5D"#);
+            then.status(200)
+                .json_body(json!({"sync_token": "t", "sync_status": {}}));
+        });
+        server.mock(|when, then| {
+            when.method(POST).path("/sync");
+            then.status(200).json_body(json!({
+                "sync_token": "token123",
+                "items": [{"id": "123", "content": "a", "labels": ["someday", "home"]}]
+            }));
+        });
+
+        edit_task(
+            &client,
+            "123".to_string(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            vec!["next".to_string()],
+            vec!["Someday".to_string()],
+            None,
+            false,
+            None,
+        )
+        .await
+        .unwrap();
+        write.assert_calls(1);
+    }
+
+    #[tokio::test]
+    async fn test_edit_label_rename_updates_tasks() {
+        let server = MockServer::start();
+        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
+
+        let write = server.mock(|when, then| {
+            when.method(POST)
+                .path("/sync")
+                .body_includes("label_update")
+                .body_includes("item_update")
+                .body_includes("t1");
+            then.status(200)
+                .json_body(json!({"sync_token": "t", "sync_status": {}}));
+        });
+        server.mock(|when, then| {
+            when.method(POST).path("/sync");
+            then.status(200).json_body(json!({
+                "sync_token": "token123",
+                "labels": [{"id": "l1", "name": "someday", "color": "red"}],
+                "items": [
+                    {"id": "t1", "content": "a", "labels": ["someday", "home"]},
+                    {"id": "t2", "content": "b", "labels": ["home"]}
+                ]
+            }));
+        });
+
+        edit_label(
+            &client,
+            None,
+            Some("someday".to_string()),
+            Some("later".to_string()),
+            None,
+        )
+        .await
+        .unwrap();
+        write.assert_calls(1);
+    }
+
+    #[tokio::test]
+    async fn test_move_project_rejects_descendant_parent() {
+        let server = MockServer::start();
+        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
+
+        let write = server.mock(|when, then| {
+            when.method(POST)
+                .path("/sync")
+                .body_includes("project_move");
+            then.status(200)
+                .json_body(json!({"sync_token": "t", "sync_status": {}}));
+        });
+        server.mock(|when, then| {
+            when.method(POST).path("/sync");
+            then.status(200).json_body(json!({
+                "sync_token": "token123",
+                "projects": [
+                    {"id": "p1", "name": "Work"},
+                    {"id": "p2", "name": "Team", "parent_id": "p1"},
+                    {"id": "p3", "name": "Sprint", "parent_id": "p2"},
+                    {"id": "p4", "name": "Home"}
+                ]
+            }));
+        });
+
+        let err = move_project(
+            &client,
+            Some("p1".to_string()),
+            None,
+            Some("p3".to_string()),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("own subproject 'p3'"));
+        write.assert_calls(0);
+
+        move_project(
+            &client,
+            Some("p3".to_string()),
+            None,
+            Some("p4".to_string()),
+        )
+        .await
+        .unwrap();
+        write.assert_calls(1);
+    }
+
+    #[tokio::test]
+    async fn test_edit_label_rename_fails_for_unknown_label() {
+        let server = MockServer::start();
+        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
+
+        let write = server.mock(|when, then| {
+            when.method(POST)
+                .path("/sync")
+                .body_includes("label_update");
+            then.status(200)
+                .json_body(json!({"sync_token": "t", "sync_status": {}}));
+        });
+        let read = server.mock(|when, then| {
+            when.method(POST).path("/sync");
+            then.status(200).json_body(json!({
+                "sync_token": "token123",
+                "labels": [{"id": "l1", "name": "someday", "color": "red"}],
+                "items": [{"id": "t1", "content": "a", "labels": ["someday"]}]
+            }));
+        });
+
+        let err = edit_label(
+            &client,
+            Some("l9".to_string()),
+            None,
+            Some("later".to_string()),
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("Label 'l9' not found"));
+        // The cached labels missed it, so the full label list was read too
+        read.assert_calls(2);
+        write.assert_calls(0);
+    }
+
+    #[tokio::test]
+    async fn test_delete_task_handler() {
+        let server = MockServer::start();
+        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
+
+        server.mock(|when, then| {
+            when.method(POST).path("/sync");
+            then.status(200).json_body(json!({
+                "sync_token": "token123",
+                "sync_status": {"uuid": "ok"}
+            }));
+        });
+
+        let result = delete_task(&client, "123".to_string(), &Confirmation::assume_yes()).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_move_task_handler() {
+        let server = MockServer::start();
+        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
+
+        server.mock(|when, then| {
+            when.method(POST).path("/sync");
+            then.status(200).json_body(json!({
+                "sync_token": "token123",
+                "sync_status": {"uuid": "ok"}
+            }));
+        });
+
+        let result = move_task(
+            &client,
+            "123".to_string(),
+            Some("proj1".to_string()),
+            None,
+            None,
+            None,
+            false,
+        )
+        .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_move_task_handler_by_name() {
+        let server = MockServer::start();
+        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
+
+        server.mock(|when, then| {
+            when.method(POST).path("/sync");
+            then.status(200).json_body(json!({
+                "sync_token": "token123",
+                "sync_status": {"uuid": "ok"},
+                "projects": [
+                    {"id": "p1", "name": "Work"},
+                    {"id": "p2", "name": "Workout"}
+                ],
+                "sections": [
+                    {
+                        "id": "s1",
+                        "project_id": "p1",
+                        "name": "Backlog",
+                        "order": 1,
+                        "created_at": "2024-01-01T00:00:00Z"
+                    }
+                ]
+            }));
+        });
+
+        let result = move_task(
+            &client,
+            "123".to_string(),
+            None,
+            None,
+            None,
+            Some("work/back".to_string()),
+            false,
+        )
+        .await;
+        assert!(result.is_ok());
+
+        let result = move_task(
+            &client,
+            "123".to_string(),
+            None,
+            Some("wo".to_string()),
+            None,
+            None,
+            false,
+        )
+        .await;
+        assert!(matches!(
+            result,
+            Err(crate::error::TodoError::InvalidInput(ref msg)) if msg.contains("Ambiguous project")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_complete_reopen_task_handlers() {
+        let server = MockServer::start();
+        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
+
+        server.mock(|when, then| {
+            when.method(POST).path("/sync");
+            then.status(200).json_body(json!({
+                "sync_token": "token123",
+                "sync_status": {"uuid": "ok"}
+            }));
+        });
+
+        assert!(complete_task(&client, "123".to_string(), false)
+            .await
+            .is_ok());
+        assert!(reopen_task(&client, "123".to_string()).await.is_ok());
     }
 
     #[tokio::test]
-    async fn test_get_filters_handler() {
+    async fn test_complete_recurring_task_closes_unless_forever() {
         let server = MockServer::start();
         let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
 
+        let close = server.mock(|when, then| {
+            when.method(POST).path("/sync").body_includes("item_close");
+            then.status(200)
+                .json_body(json!({"sync_token": "t", "sync_status": {}}));
+        });
+        let complete = server.mock(|when, then| {
+            when.method(POST)
+                .path("/sync")
+                .body_includes("item_complete");
+            then.status(200)
+                .json_body(json!({"sync_token": "t", "sync_status": {}}));
+        });
         server.mock(|when, then| {
             when.method(POST).path("/sync");
             then.status(200).json_body(json!({
                 "sync_token": "token123",
-                "filters": [
-                    {
-                        "id": "f1",
-                        "name": "Filter 1",
-                        "query": "today"
-                    }
-                ]
+                "items": [{
+                    "id": "123",
+                    "content": "Water plants",
+                    "due": {"date": "2026-01-27", "is_recurring": true, "string": "every tuesday"}
+                }]
             }));
         });
 
-        let result = get_filters(&client, &OutputFormat::Json, None).await;
-        assert!(result.is_ok());
+        complete_task(&client, "123".to_string(), false)
+            .await
+            .unwrap();
+        close.assert_calls(1);
+        complete.assert_calls(0);
+
+        complete_task(&client, "123".to_string(), true)
+            .await
+            .unwrap();
+        complete.assert_calls(1);
     }
 
     #[tokio::test]
-    async fn test_get_labels_handler() {
+    async fn test_get_task_handler() {
         let server = MockServer::start();
         let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
 
@@ -840,22 +3313,26 @@ mod tests {
             when.method(POST).path("/sync");
             then.status(200).json_body(json!({
                 "sync_token": "token123",
-                "labels": [
+                "items": [
                     {
-                        "id": "l1",
-                        "name": "Label 1",
-                        "color": "red"
+                        "id": "1",
+                        "content": "Task 1",
+                        "priority": 4,
+                        "checked": false,
+                        "added_at": "2024-01-01T00:00:00Z",
+                        "child_order": 1
                     }
-                ]
+                ],
+                "projects": []
             }));
         });
 
-        let result = get_labels(&client, &OutputFormat::Json, None).await;
+        let result = get_task(&client, "1", &OutputFormat::Json, None).await;
         assert!(result.is_ok());
     }
 
     #[tokio::test]
-    async fn test_edit_task_handler() {
+    async fn test_edit_project_handler() {
         let server = MockServer::start();
         let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
 
@@ -867,14 +3344,13 @@ mod tests {
             }));
         });
 
-        let result = edit_task(
+        let result = edit_project(
             &client,
-            "123".to_string(),
-            Some("Updated Title".to_string()),
+            Some("p1".to_string()),
             None,
+            Some("New Name".to_string()),
             None,
             None,
-            Some(3),
             None,
         )
         .await;
@@ -882,24 +3358,88 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_delete_task_handler() {
+    async fn test_project_hierarchy_handlers() {
         let server = MockServer::start();
         let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
 
+        let edit = server.mock(|when, then| {
+            when.method(POST)
+                .path("/sync")
+                .body_includes("project_update")
+                .body_includes("view_style")
+                .body_includes("favorite");
+            then.status(200)
+                .json_body(json!({"sync_token": "t", "sync_status": {}}));
+        });
+        let mv = server.mock(|when, then| {
+            when.method(POST)
+                .path("/sync")
+                .body_includes("project_move")
+                .body_includes("p1");
+            then.status(200)
+                .json_body(json!({"sync_token": "t", "sync_status": {}}));
+        });
         server.mock(|when, then| {
             when.method(POST).path("/sync");
             then.status(200).json_body(json!({
                 "sync_token": "token123",
-                "sync_status": {"uuid": "ok"}
+                "projects": [
+                    {"id": "p1", "name": "Work"},
+                    {"id": "p2", "name": "Q1", "parent_id": "p1"}
+                ],
+                "sync_status": {},
+                "temp_id_mapping": {"tmp": "p3"}
             }));
         });
 
-        let result = delete_task(&client, "123".to_string()).await;
-        assert!(result.is_ok());
+        edit_project(
+            &client,
+            None,
+            Some("work".to_string()),
+            None,
+            None,
+            Some(true),
+            Some("board".to_string()),
+        )
+        .await
+        .unwrap();
+        edit.assert_calls(1);
+
+        move_project(
+            &client,
+            Some("p2".to_string()),
+            None,
+            Some("Work".to_string()),
+        )
+        .await
+        .unwrap();
+        move_project(&client, Some("p2".to_string()), None, None)
+            .await
+            .unwrap();
+        mv.assert_calls(1);
+
+        assert!(move_project(
+            &client,
+            None,
+            Some("Work".to_string()),
+            Some("p1".to_string())
+        )
+        .await
+        .is_err());
+        assert!(add_project(
+            &client,
+            "Q2".to_string(),
+            Some("Work".to_string()),
+            None,
+            false
+        )
+        .await
+        .is_ok());
+        assert!(reorder_projects(&client, "p2,p1".to_string()).await.is_ok());
     }
 
     #[tokio::test]
-    async fn test_move_task_handler() {
+    async fn test_edit_section_handler() {
         let server = MockServer::start();
         let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
 
@@ -911,12 +3451,18 @@ mod tests {
             }));
         });
 
-        let result = move_task(&client, "123".to_string(), "proj1".to_string(), None).await;
+        let result = edit_section(
+            &client,
+            Some("s1".to_string()),
+            None,
+            Some("New Name".to_string()),
+        )
+        .await;
         assert!(result.is_ok());
     }
 
     #[tokio::test]
-    async fn test_complete_reopen_task_handlers() {
+    async fn test_delete_project_section_handlers() {
         let server = MockServer::start();
         let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
 
@@ -928,86 +3474,231 @@ mod tests {
             }));
         });
 
-        assert!(complete_task(&client, "123".to_string()).await.is_ok());
-        assert!(reopen_task(&client, "123".to_string()).await.is_ok());
+        let yes = Confirmation::assume_yes();
+        assert!(delete_project(&client, Some("p1".to_string()), None, &yes)
+            .await
+            .is_ok());
+        assert!(delete_section(&client, Some("s1".to_string()), None, &yes)
+            .await
+            .is_ok());
+        assert!(delete_label(&client, Some("l1".to_string()), None, &yes)
+            .await
+            .is_ok());
+        assert!(delete_filter(&client, "f1".to_string(), &yes).await.is_ok());
+        assert!(archive_project(&client, Some("p1".to_string()), None, true)
+            .await
+            .is_ok());
+        assert!(archive_section(&client, Some("s1".to_string()), None, true)
+            .await
+            .is_ok());
+        assert!(
+            archive_section(&client, Some("s1".to_string()), None, false)
+                .await
+                .is_ok()
+        );
+        assert!(move_section(
+            &client,
+            Some("s1".to_string()),
+            None,
+            Some("p2".to_string()),
+            None
+        )
+        .await
+        .is_ok());
     }
 
     #[tokio::test]
-    async fn test_get_task_handler() {
+    async fn test_delete_project_requires_confirmation() {
         let server = MockServer::start();
         let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
 
+        let write = server.mock(|when, then| {
+            when.method(POST).path("/sync").body_includes("commands");
+            then.status(200)
+                .json_body(json!({"sync_token": "t", "sync_status": {}}));
+        });
         server.mock(|when, then| {
             when.method(POST).path("/sync");
             then.status(200).json_body(json!({
                 "sync_token": "token123",
-                "items": [
-                    {
-                        "id": "1",
-                        "content": "Task 1",
-                        "priority": 4,
-                        "checked": false,
-                        "added_at": "2024-01-01T00:00:00Z",
-                        "child_order": 1
-                    }
+                "projects": [
+                    {"id": "p1", "name": "Work"},
+                    {"id": "p2", "name": "Team", "parent_id": "p1"},
+                    {"id": "p3", "name": "Sprint", "parent_id": "p2"},
+                    {"id": "p4", "name": "Home"}
                 ],
-                "projects": []
+                "items": [
+                    {"id": "t1", "content": "a", "project_id": "p1"},
+                    {"id": "t2", "content": "b", "project_id": "p1"},
+                    {"id": "t3", "content": "c", "project_id": "p3"},
+                    {"id": "t4", "content": "d", "project_id": "p4"}
+                ]
             }));
         });
 
-        let result = get_task(&client, "1", &OutputFormat::Json, None).await;
-        assert!(result.is_ok());
+        let err = delete_project(
+            &client,
+            None,
+            Some("work".to_string()),
+            &Confirmation::non_interactive(false, true),
+        )
+        .await
+        .unwrap_err()
+        .to_string();
+        assert!(
+            err.contains(
+                "Delete project 'Work' and its 2 subproject(s), with 3 task(s) in 3 projects?"
+            ),
+            "{}",
+            err
+        );
+
+        let err = delete_project(
+            &client,
+            None,
+            Some("home".to_string()),
+            &Confirmation::non_interactive(false, true),
+        )
+        .await
+        .unwrap_err()
+        .to_string();
+        assert!(
+            err.contains("Delete project 'Home' and its 1 task(s)?"),
+            "{}",
+            err
+        );
+        write.assert_calls(0);
     }
 
     #[tokio::test]
-    async fn test_edit_project_handler() {
+    async fn test_edit_tasks_by_label_sends_single_batch() {
         let server = MockServer::start();
         let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
 
+        let write = server.mock(|when, then| {
+            when.method(POST)
+                .path("/sync")
+                .body_includes("item_update")
+                .body_includes("t1")
+                .body_includes("t2");
+            then.status(200)
+                .json_body(json!({"sync_token": "t", "sync_status": {}}));
+        });
         server.mock(|when, then| {
             when.method(POST).path("/sync");
             then.status(200).json_body(json!({
                 "sync_token": "token123",
-                "sync_status": {"uuid": "ok"}
+                "projects": [{"id": "p1", "name": "Work"}],
+                "items": [
+                    {"id": "t1", "content": "a", "project_id": "p1", "labels": ["someday"]},
+                    {"id": "t2", "content": "b", "project_id": "p1", "labels": ["Someday", "home"]},
+                    {"id": "t3", "content": "someday maybe", "project_id": "p1", "labels": []}
+                ]
             }));
         });
 
-        let result = edit_project(&client, "p1".to_string(), Some("New Name".to_string())).await;
-        assert!(result.is_ok());
+        edit_tasks(
+            &client,
+            "@someday".to_string(),
+            vec!["next".to_string()],
+            vec!["someday".to_string()],
+            None,
+            50,
+            &Confirmation::assume_yes(),
+        )
+        .await
+        .unwrap();
+        write.assert_calls(1);
     }
 
     #[tokio::test]
-    async fn test_edit_section_handler() {
+    async fn test_bulk_filters_are_strict() {
         let server = MockServer::start();
         let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
 
+        let write = server.mock(|when, then| {
+            when.method(POST)
+                .path("/sync")
+                .body_includes("item_delete")
+                .body_includes("t1");
+            then.status(200)
+                .json_body(json!({"sync_token": "t", "sync_status": {}}));
+        });
         server.mock(|when, then| {
             when.method(POST).path("/sync");
             then.status(200).json_body(json!({
                 "sync_token": "token123",
-                "sync_status": {"uuid": "ok"}
+                "projects": [{"id": "p1", "name": "Work"}],
+                "labels": [{"id": "l1", "name": "home", "color": "red"}],
+                "items": [
+                    {"id": "t1", "content": "a", "project_id": "p1", "priority": 4},
+                    {"id": "t2", "content": "review p1 draft", "project_id": "p1", "priority": 1}
+                ]
             }));
         });
 
-        let result = edit_section(&client, "s1".to_string(), Some("New Name".to_string())).await;
-        assert!(result.is_ok());
+        let yes = Confirmation::assume_yes();
+        for filter in ["draft", "@hoem", "#Wrok"] {
+            let err = delete_tasks(&client, filter.to_string(), 50, &yes)
+                .await
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains(filter), "{}", err);
+        }
+        write.assert_calls(0);
+
+        // Scripts see the match count and must pass --yes
+        let err = delete_tasks(
+            &client,
+            "p1".to_string(),
+            50,
+            &Confirmation::non_interactive(false, false),
+        )
+        .await
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("Delete 1 task(s) matching 'p1'?"), "{}", err);
+        assert!(err.contains("--yes"), "{}", err);
+        write.assert_calls(0);
+
+        // "p1" is the priority only, not a substring of the content
+        delete_tasks(&client, "p1".to_string(), 50, &yes)
+            .await
+            .unwrap();
+        write.assert_calls(1);
     }
 
     #[tokio::test]
-    async fn test_delete_project_section_handlers() {
+    async fn test_complete_tasks_limit_and_chunking() {
         let server = MockServer::start();
         let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
 
+        let write = server.mock(|when, then| {
+            when.method(POST).path("/sync").body_includes("commands");
+            then.status(200)
+                .json_body(json!({"sync_token": "t", "sync_status": {}}));
+        });
+        let items: Vec<_> = (0..150)
+            .map(|i| json!({"id": format!("t{}", i), "content": format!("report {}", i)}))
+            .collect();
         server.mock(|when, then| {
             when.method(POST).path("/sync");
-            then.status(200).json_body(json!({
-                "sync_token": "token123",
-                "sync_status": {"uuid": "ok"}
-            }));
+            then.status(200)
+                .json_body(json!({"sync_token": "token123", "items": items}));
         });
 
-        assert!(delete_project(&client, "p1".to_string()).await.is_ok());
-        assert!(delete_section(&client, "s1".to_string()).await.is_ok());
+        let yes = Confirmation::assume_yes();
+        let err = complete_tasks(&client, "search: report".to_string(), 50, false, &yes)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("matches 150 tasks"), "{}", err);
+        write.assert_calls(0);
+
+        complete_tasks(&client, "search: report".to_string(), 200, false, &yes)
+            .await
+            .unwrap();
+        write.assert_calls(2);
     }
 
     #[tokio::test]
@@ -1019,13 +3710,15 @@ mod tests {
             when.method(POST).path("/sync");
             then.status(200).json_body(json!({
                 "sync_token": "token123",
+                "labels": [{"id": "l1", "name": "old", "color": "red"}],
                 "sync_status": {"uuid": "ok"}
             }));
         });
 
         assert!(edit_label(
             &client,
-            "l1".to_string(),
+            Some("l1".to_string()),
+            None,
             Some("n".to_string()),
             Some("c".to_string())
         )
@@ -1066,11 +3759,413 @@ mod tests {
         generate_completions(Shell::Bash);
     }
 
-    #[test]
-    fn test_validate_priority() {
-        assert!(validate_priority(1));
-        assert!(validate_priority(4));
-        assert!(!validate_priority(0));
-        assert!(!validate_priority(5));
+    #[tokio::test]
+    async fn test_md_sync_handler() {
+        use crate::markdown::{Fields, SyncState};
+
+        let server = MockServer::start();
+        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
+        let write = server.mock(|when, then| {
+            when.method(POST)
+                .path("/sync")
+                .body_includes("commands")
+                .body_includes("item_complete")
+                .body_includes("item_update")
+                .body_includes("item_add");
+            then.respond_with(|req: &httpmock::HttpMockRequest| {
+                // Map the temp id of the new task to a real id
+                let body = req.body_string();
+                let start = body.find("%22temp_id%22%3A%22").unwrap() + 19;
+                let temp_id = &body[start..start + body[start..].find("%22").unwrap()];
+                let response = json!({
+                    "sync_token": "t",
+                    "sync_status": {},
+                    "temp_id_mapping": {temp_id: "t5"}
+                });
+                httpmock::HttpMockResponse::builder()
+                    .status(200)
+                    .body(response.to_string())
+                    .build()
+            });
+        });
+        server.mock(|when, then| {
+            when.method(POST).path("/sync");
+            then.status(200).json_body(json!({
+                "sync_token": "t",
+                "projects": [{"id": "p1", "name": "Errands"}],
+                "labels": [{"id": "l1", "name": "health", "color": "red"}],
+                "items": [
+                    {"id": "t1", "content": "Buy milk", "project_id": "p1"},
+                    {"id": "t2", "content": "Call Bea", "project_id": "p1"},
+                    {"id": "t3", "content": "Write the report", "project_id": "p1"},
+                    {"id": "t4", "content": "Pay gas", "project_id": "p1"}
+                ]
+            }));
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.md");
+        std::fs::write(
+            &path,
+            "## Errands\n\n\
+             - [x] Buy milk <!-- todoist-id:t1 -->\n\
+             - [ ] Call Bea <!-- todoist-id:t2 --> ⏫\n\
+             - [ ] Write report <!-- todoist-id:t3 -->\n\
+             - [ ] Pay rent <!-- todoist-id:t4 -->\n\
+             - [ ] Book dentist #health\n",
+        )
+        .unwrap();
+        let state = SyncState::with_path(dir.path().join("md_sync.json"));
+        let fields = |content: &str| Fields {
+            content: content.to_string(),
+            ..Fields::default()
+        };
+        state
+            .save(
+                &path,
+                HashMap::from([
+                    ("t3".to_string(), fields("Write report")),
+                    ("t4".to_string(), fields("Pay bill")),
+                ]),
+            )
+            .unwrap();
+
+        md_sync(&client, &state, &path, None).await.unwrap();
+        write.assert();
+
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "## Errands\n\n\
+             - [x] Buy milk <!-- todoist-id:t1 -->\n\
+             - [ ] Call Bea <!-- todoist-id:t2 --> ⏫\n\
+             - [ ] Write the report <!-- todoist-id:t3 -->\n\
+             - [ ] Pay rent <!-- todoist-id:t4 -->\n\
+             - [ ] Book dentist <!-- todoist-id:t5 --> #health\n"
+        );
+        let recorded = state.load(&path).unwrap();
+        assert!(recorded["t1"].checked);
+        assert_eq!(recorded["t3"].content, "Write the report");
+        // The conflict stays unresolved
+        assert_eq!(recorded["t4"].content, "Pay bill");
+        assert_eq!(recorded["t5"].labels, vec!["health"]);
+    }
+
+    #[tokio::test]
+    async fn test_md_sync_keeps_results_of_partial_failure() {
+        use crate::markdown::{Fields, SyncState};
+
+        let server = MockServer::start();
+        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
+        let write = server.mock(|when, then| {
+            when.method(POST).path("/sync").body_includes("commands");
+            then.respond_with(|req: &httpmock::HttpMockRequest| {
+                let body = req.body_string();
+                let value = |from: usize, key: &str| {
+                    let start = from + body[from..].find(key).unwrap() + key.len();
+                    body[start..start + body[start..].find("%22").unwrap()].to_string()
+                };
+                // The update of the remotely deleted task fails
+                let update = body.find("item_update").unwrap();
+                let temp_id = value(0, "%22temp_id%22%3A%22");
+                let response = json!({
+                    "sync_token": "t",
+                    "sync_status": {value(update, "%22uuid%22%3A%22"): {"error": "Item not found"}},
+                    "temp_id_mapping": {temp_id: "t5"}
+                });
+                httpmock::HttpMockResponse::builder()
+                    .status(200)
+                    .body(response.to_string())
+                    .build()
+            });
+        });
+        server.mock(|when, then| {
+            when.method(POST).path("/sync");
+            then.status(200).json_body(json!({
+                "sync_token": "t",
+                "projects": [{"id": "p1", "name": "Errands"}],
+                "items": [
+                    {"id": "t1", "content": "Buy milk", "project_id": "p1"},
+                    {"id": "t2", "content": "Call Bea", "project_id": "p1"}
+                ]
+            }));
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.md");
+        std::fs::write(
+            &path,
+            "## Errands\n\n\
+             - [x] Buy milk <!-- todoist-id:t1 -->\n\
+             - [ ] Call Bea today <!-- todoist-id:t2 -->\n\
+             - [ ] Book dentist\n",
+        )
+        .unwrap();
+        let state = SyncState::with_path(dir.path().join("md_sync.json"));
+        let fields = |content: &str| Fields {
+            content: content.to_string(),
+            ..Fields::default()
+        };
+        state
+            .save(
+                &path,
+                HashMap::from([
+                    ("t1".to_string(), fields("Buy milk")),
+                    ("t2".to_string(), fields("Call Bea")),
+                ]),
+            )
+            .unwrap();
+
+        let err = md_sync(&client, &state, &path, None).await.unwrap_err();
+        assert!(err.to_string().contains("1 Markdown sync commands failed"));
+        write.assert();
+
+        // The created task keeps its id, so the next sync does not add it again
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "## Errands\n\n\
+             - [x] Buy milk <!-- todoist-id:t1 -->\n\
+             - [ ] Call Bea today <!-- todoist-id:t2 -->\n\
+             - [ ] Book dentist <!-- todoist-id:t5 -->\n"
+        );
+        let recorded = state.load(&path).unwrap();
+        assert!(recorded["t1"].checked);
+        assert_eq!(recorded["t5"].content, "Book dentist");
+        // The failed update is retried on the next sync
+        assert_eq!(recorded["t2"].content, "Call Bea");
+    }
+
+    #[tokio::test]
+    async fn test_export_ics_to_file() {
+        let server = MockServer::start();
+        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
+        server.mock(|when, then| {
+            when.method(POST).path("/sync");
+            then.status(200).json_body(json!({
+                "sync_token": "t",
+                "projects": [{"id": "p1", "name": "Work"}],
+                "items": [
+                    {"id": "t1", "content": "Review", "project_id": "p1",
+                     "due": {"date": "2026-01-20", "is_recurring": true, "string": "every tuesday"}},
+                    {"id": "t2", "content": "Undated", "project_id": "p1"},
+                    {"id": "t3", "content": "Elsewhere", "project_id": "p2",
+                     "due": {"date": "2026-01-21"}}
+                ]
+            }));
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("todoist.ics");
+        export_ics(
+            &client,
+            Some("#Work"),
+            crate::ics::Component::Event,
+            Some(&path),
+        )
+        .await
+        .unwrap();
+
+        let ics = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 1);
+        assert!(ics.contains("UID:task-t1@todorust\r\n"));
+        assert!(ics.contains("RRULE:FREQ=WEEKLY;BYDAY=TU\r\n"));
+        assert!(!dir.path().join("todoist.ics.partial").exists());
+    }
+
+    fn write_backup(dir: &tempfile::TempDir, tasks: usize) -> std::path::PathBuf {
+        let items: Vec<_> = (0..tasks)
+            .map(|i| json!({"id": format!("t{}", i), "content": format!("Task {}", i), "project_id": "old"}))
+            .collect();
+        let backup = json!({
+            "version": 1,
+            "exported_at": "2026-10-19T00:00:00Z",
+            "projects": [{"id": "old", "name": "Work"}],
+            "items": items,
+            "sections": [],
+            "labels": [],
+            "filters": []
+        });
+        let path = dir.path().join("backup.json");
+        std::fs::write(&path, backup.to_string()).unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn test_export_all_handler() {
+        let server = MockServer::start();
+        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
+        let mock = server.mock(|when, then| {
+            when.method(POST).path("/sync").body_includes("notes");
+            then.status(200).json_body(json!({
+                "sync_token": "t",
+                "projects": [{"id": "p1", "name": "Work"}],
+                "notes": [{"id": "n1", "item_id": "t1", "content": "See doc"}]
+            }));
+        });
+
+        let archived = server.mock(|when, then| {
+            when.method(GET).path("/projects/archived");
+            then.status(200).json_body(json!({
+                "results": [{"id": "p9", "name": "Old"}],
+                "next_cursor": null
+            }));
+        });
+
+        assert!(export_all(&client).await.is_ok());
+        mock.assert();
+        archived.assert();
+
+        let backup = load_backup(&client).await.unwrap();
+        let old = backup.data.projects.iter().find(|p| p.id == "p9").unwrap();
+        assert!(old.is_archived);
+        assert_eq!(backup.warnings.len(), 1);
+        assert!(backup.warnings[0].contains("1 archived project(s)"));
+    }
+
+    #[tokio::test]
+    async fn test_import_backup_dry_run_sends_nothing() {
+        let server = MockServer::start();
+        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"))
+            .with_dry_run(true);
+        let write = server.mock(|when, then| {
+            when.method(POST).path("/sync").body_includes("commands");
+            then.status(500);
+        });
+        server.mock(|when, then| {
+            when.method(POST).path("/sync");
+            then.status(200)
+                .json_body(json!({"sync_token": "t", "projects": []}));
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_backup(&dir, 2);
+        assert!(import_backup(&client, &path, Some("Restored"))
+            .await
+            .is_ok());
+        write.assert_calls(0);
+    }
+
+    #[tokio::test]
+    async fn test_import_backup_resolves_temp_ids_across_chunks() {
+        let server = MockServer::start();
+        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
+        let first = server.mock(|when, then| {
+            when.method(POST)
+                .path("/sync")
+                .body_includes("commands")
+                .body_includes("project_add");
+            then.respond_with(|req: &httpmock::HttpMockRequest| {
+                // The project is the first command of the batch
+                let body = req.body_string();
+                let start = body.find("%22temp_id%22%3A%22").unwrap() + 19;
+                let temp_id = &body[start..start + body[start..].find("%22").unwrap()];
+                let response = json!({
+                    "sync_token": "t",
+                    "sync_status": {},
+                    "temp_id_mapping": {temp_id: "p9"}
+                });
+                httpmock::HttpMockResponse::builder()
+                    .status(200)
+                    .body(response.to_string())
+                    .build()
+            });
+        });
+        let second = server.mock(|when, then| {
+            when.method(POST)
+                .path("/sync")
+                .body_includes("commands")
+                .body_includes("%22project_id%22%3A%22p9%22");
+            then.status(200)
+                .json_body(json!({"sync_token": "t", "sync_status": {}}));
+        });
+        server.mock(|when, then| {
+            when.method(POST).path("/sync");
+            then.status(200)
+                .json_body(json!({"sync_token": "t", "projects": []}));
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        // One project and 100 tasks do not fit in one request
+        let path = write_backup(&dir, crate::sync::MAX_COMMANDS_PER_REQUEST);
+        import_backup(&client, &path, None).await.unwrap();
+        first.assert();
+        second.assert();
+    }
+
+    #[tokio::test]
+    async fn test_import_backup_reports_progress_on_failure() {
+        let server = MockServer::start();
+        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/sync")
+                .body_includes("commands")
+                .body_includes("project_add");
+            then.respond_with(|req: &httpmock::HttpMockRequest| {
+                let body = req.body_string();
+                let start = body.find("%22temp_id%22%3A%22").unwrap() + 19;
+                let temp_id = &body[start..start + body[start..].find("%22").unwrap()];
+                let response = json!({
+                    "sync_token": "t",
+                    "sync_status": {},
+                    "temp_id_mapping": {temp_id: "p9"}
+                });
+                httpmock::HttpMockResponse::builder()
+                    .status(200)
+                    .body(response.to_string())
+                    .build()
+            });
+        });
+        let second = server.mock(|when, then| {
+            when.method(POST).path("/sync").body_includes("commands");
+            then.status(500);
+        });
+        server.mock(|when, then| {
+            when.method(POST).path("/sync");
+            then.status(200)
+                .json_body(json!({"sync_token": "t", "projects": []}));
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_backup(&dir, crate::sync::MAX_COMMANDS_PER_REQUEST);
+        let err = import_backup(&client, &path, None)
+            .await
+            .unwrap_err()
+            .to_string();
+        second.assert();
+        assert!(
+            err.contains("Import stopped after 100 of 101 commands (1 resources created)"),
+            "{}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn test_import_tasks_from_todotxt() {
+        let server = MockServer::start();
+        let client = TodoistSyncClient::new_with_url("token".to_string(), server.url("/sync"));
+        let write = server.mock(|when, then| {
+            when.method(POST)
+                .path("/sync")
+                .body_includes("item_add")
+                .body_includes("%22project_id%22%3A%22p1%22")
+                .body_includes("item_complete");
+            then.status(200)
+                .json_body(json!({"sync_token": "t", "sync_status": {}}));
+        });
+        server.mock(|when, then| {
+            when.method(POST).path("/sync");
+            then.status(200).json_body(json!({
+                "sync_token": "t",
+                "projects": [{"id": "p1", "name": "Home"}]
+            }));
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("todo.txt");
+        std::fs::write(&path, "(A) Fix sink +Home @tools\nx Pay rent +Home\n").unwrap();
+        import_tasks(&client, &path, crate::import::Source::Todotxt, None)
+            .await
+            .unwrap();
+        write.assert();
     }
 }
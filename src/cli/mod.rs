@@ -1,13 +1,31 @@
 use crate::formatter::OutputFormat;
+use crate::models::{Duration, Priority};
 use clap::{Parser, Subcommand};
 
 #[derive(Parser)]
 #[command(name = "todorust")]
 #[command(author, version, about, long_about = None)]
 pub struct Cli {
+    /// json, checklist, structured, obsidian, table, csv, tsv, ndjson or template:<name>
     #[arg(long, short, global = true, default_value = "json")]
     pub format: OutputFormat,
 
+    /// Render output with a template file
+    #[arg(long, global = true, conflicts_with = "template_string")]
+    pub template: Option<std::path::PathBuf>,
+
+    /// Render output with an inline template, e.g. '{% for t in tasks %}{{ t.content }}\n{% endfor %}'
+    #[arg(long, global = true)]
+    pub template_string: Option<String>,
+
+    /// Print the commands a write would send, and the predicted changes, without sending them
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+
+    /// Skip confirmation prompts for destructive commands
+    #[arg(long, short, global = true)]
+    pub yes: bool,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -49,6 +67,14 @@ pub enum Commands {
     #[command(subcommand)]
     Move(MoveCommands),
 
+    /// Archive a project or section
+    #[command(subcommand)]
+    Archive(ArchiveCommands),
+
+    /// Unarchive a project or section
+    #[command(subcommand)]
+    Unarchive(UnarchiveCommands),
+
     /// Reorder resources
     #[command(subcommand)]
     Reorder(ReorderCommands),
@@ -61,10 +87,52 @@ pub enum Commands {
     #[command(subcommand)]
     Cache(CacheCommands),
 
+    /// Sync checklists in Markdown notes
+    #[command(subcommand)]
+    Md(MdCommands),
+
+    /// Export a backup of the account, or tasks to other formats
+    Export(ExportCommand),
+
+    /// Restore a backup written by `export --all`, or import tasks from other tools
+    Import {
+        /// Backup file, or a file in the `--from` format
+        file: std::path::PathBuf,
+        /// Read tasks from another tool instead of a backup
+        #[arg(long, value_enum)]
+        from: Option<crate::import::Source>,
+        /// Create everything below a new project with this name
+        #[arg(long)]
+        into: Option<String>,
+    },
+
     /// Execute multiple commands in a single batch request
     Batch {
-        /// JSON array of commands
-        commands: String,
+        /// JSON array of commands, validated before sending
+        #[arg(required_unless_present = "schema")]
+        commands: Option<String>,
+
+        /// Send commands with unknown types or fields unchecked instead of rejecting them
+        #[arg(long)]
+        allow_unknown: bool,
+
+        /// Print the JSON Schema of the accepted commands and exit
+        #[arg(long, conflicts_with = "commands")]
+        schema: bool,
+    },
+
+    /// Revert recent write operations using the local undo journal
+    Undo {
+        /// Number of operations to revert
+        #[arg(long, default_value_t = 1)]
+        steps: usize,
+    },
+
+    /// List recent write operations from the undo journal
+    History {
+        /// Maximum number of operations to show
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
     },
 
     /// Generate shell completion scripts
@@ -95,61 +163,109 @@ pub enum GetCommands {
     Tasks {
         #[arg(long)]
         filter: Option<String>,
+        /// json, checklist, structured, obsidian, table, csv, tsv, ndjson or template:<name>
         #[arg(long, short)]
         format: Option<OutputFormat>,
-        /// Specific fields to include in JSON output (e.g., "id,content")
-        #[arg(long)]
+        /// Fields to include in JSON, CSV/TSV and NDJSON output, or the table columns; supports
+        /// paths, renames and exclusions (e.g., "id,title:content,labels[0],-description")
+        #[arg(long, visible_alias = "columns")]
         fields: Option<String>,
-        /// Limit the number of tasks returned
+        /// Sort keys: due, priority, created, project, section, order or content;
+        /// prefix with - to reverse (e.g. "due,priority,-created")
+        #[arg(long, value_delimiter = ',', allow_hyphen_values = true)]
+        sort: Vec<crate::listing::SortKey>,
+        /// Group the tasks under headings (a `group` column in CSV, TSV and NDJSON)
+        #[arg(long, value_enum)]
+        group_by: Option<crate::listing::GroupBy>,
+        /// Limit the number of tasks returned, after sorting
         #[arg(long)]
         limit: Option<usize>,
     },
     /// Get all projects
     Projects {
+        /// json, checklist, structured, obsidian, table, csv, tsv, ndjson or template:<name>
         #[arg(long, short)]
         format: Option<OutputFormat>,
-        /// Specific fields to include in JSON output
-        #[arg(long)]
+        /// Specific fields to include in JSON, CSV/TSV and NDJSON output, or the table columns
+        #[arg(long, visible_alias = "columns")]
         fields: Option<String>,
+        /// List archived projects instead of active ones
+        #[arg(long)]
+        archived: bool,
     },
     /// Get a specific task
     Task {
         #[arg(long)]
         task_id: String,
+        /// json, checklist, structured, obsidian, table, csv, tsv, ndjson or template:<name>
         #[arg(long, short)]
         format: Option<OutputFormat>,
-        /// Specific fields to include in JSON output
-        #[arg(long)]
+        /// Specific fields to include in JSON, CSV/TSV and NDJSON output, or the table columns
+        #[arg(long, visible_alias = "columns")]
         fields: Option<String>,
     },
     /// Get all sections (optionally filtered by project)
     Sections {
-        #[arg(long)]
+        #[arg(long, conflicts_with = "project")]
         project_id: Option<String>,
+        /// Project name or ID
+        #[arg(long)]
+        project: Option<String>,
+        /// json, checklist, structured, obsidian, table, csv, tsv, ndjson or template:<name>
         #[arg(long, short)]
         format: Option<OutputFormat>,
-        /// Specific fields to include in JSON output
+        /// Specific fields to include in JSON, CSV/TSV and NDJSON output, or the table columns
+        #[arg(long, visible_alias = "columns")]
+        fields: Option<String>,
+    },
+    /// Show one day's timed tasks with their durations, flagging overlaps
+    Agenda {
+        /// today, tomorrow, yesterday or YYYY-MM-DD
+        #[arg(long, default_value = "today")]
+        date: String,
+        /// json, checklist, structured, obsidian, table, csv, tsv, ndjson or template:<name>
+        #[arg(long, short)]
+        format: Option<OutputFormat>,
+        /// Specific fields to include in JSON, CSV/TSV and NDJSON output, or the table columns
+        #[arg(long, visible_alias = "columns")]
+        fields: Option<String>,
+    },
+    /// Get collaborators (optionally only the members of a shared project)
+    Collaborators {
+        #[arg(long, conflicts_with = "project")]
+        project_id: Option<String>,
+        /// Project name or ID
         #[arg(long)]
+        project: Option<String>,
+        /// json, checklist, structured, obsidian, table, csv, tsv, ndjson or template:<name>
+        #[arg(long, short)]
+        format: Option<OutputFormat>,
+        /// Specific fields to include in JSON, CSV/TSV and NDJSON output, or the table columns
+        #[arg(long, visible_alias = "columns")]
         fields: Option<String>,
     },
     /// Get all filters
     Filters {
+        /// json, checklist, structured, obsidian, table, csv, tsv, ndjson or template:<name>
         #[arg(long, short)]
         format: Option<OutputFormat>,
-        /// Specific fields to include in JSON output
-        #[arg(long)]
+        /// Specific fields to include in JSON, CSV/TSV and NDJSON output, or the table columns
+        #[arg(long, visible_alias = "columns")]
         fields: Option<String>,
     },
     /// Get all labels
     Labels {
+        /// json, checklist, structured, obsidian, table, csv, tsv, ndjson or template:<name>
         #[arg(long, short)]
         format: Option<OutputFormat>,
-        /// Specific fields to include in JSON output
-        #[arg(long)]
+        /// Specific fields to include in JSON, CSV/TSV and NDJSON output, or the table columns
+        #[arg(long, visible_alias = "columns")]
         fields: Option<String>,
     },
 }
 
+// Parsed once per run, so the size of `Task` doesn't matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Clone, Subcommand)]
 pub enum AddCommands {
     /// Create a new task
@@ -160,28 +276,56 @@ pub enum AddCommands {
         content: Option<String>,
         #[arg(long)]
         description: Option<String>,
-        #[arg(long)]
+        #[arg(long, conflicts_with = "project")]
         project_id: Option<String>,
+        /// Project name or ID
+        #[arg(long)]
+        project: Option<String>,
+        /// Section name, ID or "Project/Section" path
+        #[arg(long)]
+        section: Option<String>,
         #[arg(long)]
         due_date: Option<String>,
+        /// Priority: p1-p4 (p1 = urgent) or urgent/high/medium/low
         #[arg(long)]
-        priority: Option<u8>,
+        priority: Option<Priority>,
         #[arg(long)]
         labels: Option<String>,
+        /// Collaborator email, name or ID to assign the task to ("me" for yourself)
+        #[arg(long)]
+        assignee: Option<String>,
+        /// Expected duration, e.g. 45m, 1h30m or 2d
+        #[arg(long)]
+        duration: Option<Duration>,
+        /// Add the task to the Inbox, ignoring `default_project`
+        #[arg(long, conflicts_with_all = ["project", "project_id"])]
+        inbox: bool,
+        /// json, checklist, structured, obsidian, table, csv, tsv, ndjson or template:<name>
         #[arg(long, short)]
         format: Option<OutputFormat>,
     },
+    /// Create a task from quick-add text, e.g. "Buy milk tomorrow #Errands @store p1 /Groceries"
+    Quick {
+        /// Task text with optional #project, /section, @label, p1-p4 and due date
+        text: String,
+    },
     /// Create a new section
     Section {
         #[arg(long)]
         name: String,
+        #[arg(long, required_unless_present = "project", conflicts_with = "project")]
+        project_id: Option<String>,
+        /// Project name or ID
         #[arg(long)]
-        project_id: String,
+        project: Option<String>,
     },
     /// Create a new project
     Project {
         #[arg(long)]
         name: String,
+        /// Parent project name or ID, to nest the new project
+        #[arg(long)]
+        parent: Option<String>,
         #[arg(long)]
         color: Option<String>,
         #[arg(long, action = clap::ArgAction::SetTrue)]
@@ -215,33 +359,88 @@ pub enum EditCommands {
         title: Option<String>,
         #[arg(long)]
         content: Option<String>,
-        #[arg(long)]
+        #[arg(long, conflicts_with = "project")]
         project_id: Option<String>,
+        /// Project name or ID to move the task to
+        #[arg(long)]
+        project: Option<String>,
         #[arg(long)]
         due_date: Option<String>,
+        /// Priority: p1-p4 (p1 = urgent) or urgent/high/medium/low
         #[arg(long)]
-        priority: Option<u8>,
+        priority: Option<Priority>,
+        /// Replace all labels (comma-separated)
         #[arg(long)]
         labels: Option<String>,
+        /// Label to add, keeping the existing ones (repeatable)
+        #[arg(long = "add-label", conflicts_with = "labels")]
+        add_label: Vec<String>,
+        /// Label to remove, keeping the others (repeatable)
+        #[arg(long = "remove-label", conflicts_with = "labels")]
+        remove_label: Vec<String>,
+        /// Collaborator email, name or ID to assign the task to ("me" for yourself)
+        #[arg(long)]
+        assignee: Option<String>,
+        /// Remove the task's assignee
+        #[arg(long, conflicts_with = "assignee")]
+        unassign: bool,
+        /// Expected duration, e.g. 45m, 1h30m or 2d
+        #[arg(long)]
+        duration: Option<Duration>,
+    },
+    /// Edit every active task matching a filter
+    Tasks {
+        /// e.g. "@someday", "#Inbox", "p1" or "search: report"; unknown expressions are rejected
+        #[arg(long)]
+        filter: String,
+        /// Label to add (repeatable)
+        #[arg(long = "add-label")]
+        add_label: Vec<String>,
+        /// Label to remove (repeatable)
+        #[arg(long = "remove-label")]
+        remove_label: Vec<String>,
+        /// Priority: p1-p4 (p1 = urgent) or urgent/high/medium/low
+        #[arg(long)]
+        priority: Option<Priority>,
+        /// Refuse to act when the filter matches more tasks than this
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
     },
     /// Edit a project
     Project {
+        #[arg(long, required_unless_present = "project", conflicts_with = "project")]
+        project_id: Option<String>,
+        /// Project name or ID
         #[arg(long)]
-        project_id: String,
+        project: Option<String>,
         #[arg(long)]
         name: Option<String>,
+        #[arg(long)]
+        color: Option<String>,
+        /// Mark (true) or unmark (false) the project as favorite
+        #[arg(long)]
+        favorite: Option<bool>,
+        /// Project layout
+        #[arg(long, value_parser = crate::sync::VIEW_STYLES)]
+        view_style: Option<String>,
     },
     /// Edit a section
     Section {
+        #[arg(long, required_unless_present = "section", conflicts_with = "section")]
+        section_id: Option<String>,
+        /// Section name, ID or "Project/Section" path
         #[arg(long)]
-        section_id: String,
+        section: Option<String>,
         #[arg(long)]
         name: Option<String>,
     },
     /// Edit a label
     Label {
+        #[arg(long, required_unless_present = "label", conflicts_with = "label")]
+        label_id: Option<String>,
+        /// Label name or ID
         #[arg(long)]
-        label_id: String,
+        label: Option<String>,
         #[arg(long)]
         name: Option<String>,
         #[arg(long)]
@@ -262,10 +461,25 @@ pub enum EditCommands {
 
 #[derive(Clone, Subcommand)]
 pub enum CompleteCommands {
-    /// Complete a task
+    /// Complete a task (recurring tasks move to their next occurrence)
     Task {
         #[arg(long)]
         task_id: String,
+        /// Complete a recurring task for good instead of advancing it
+        #[arg(long)]
+        forever: bool,
+    },
+    /// Complete every active task matching a filter
+    Tasks {
+        /// e.g. "@someday", "#Inbox", "p1" or "search: report"; unknown expressions are rejected
+        #[arg(long)]
+        filter: String,
+        /// Refuse to act when the filter matches more tasks than this
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+        /// Complete recurring tasks for good instead of advancing them
+        #[arg(long)]
+        forever: bool,
     },
 }
 
@@ -285,15 +499,43 @@ pub enum DeleteCommands {
         #[arg(long)]
         task_id: String,
     },
+    /// Delete every active task matching a filter
+    Tasks {
+        /// e.g. "@someday", "#Inbox", "p1" or "search: report"; unknown expressions are rejected
+        #[arg(long)]
+        filter: String,
+        /// Refuse to act when the filter matches more tasks than this
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+    },
     /// Delete a project
     Project {
+        #[arg(long, required_unless_present = "project", conflicts_with = "project")]
+        project_id: Option<String>,
+        /// Project name or ID
         #[arg(long)]
-        project_id: String,
+        project: Option<String>,
     },
     /// Delete a section
     Section {
+        #[arg(long, required_unless_present = "section", conflicts_with = "section")]
+        section_id: Option<String>,
+        /// Section name, ID or "Project/Section" path
         #[arg(long)]
-        section_id: String,
+        section: Option<String>,
+    },
+    /// Delete a label
+    Label {
+        #[arg(long, required_unless_present = "label", conflicts_with = "label")]
+        label_id: Option<String>,
+        /// Label name or ID
+        #[arg(long)]
+        label: Option<String>,
+    },
+    /// Delete a filter
+    Filter {
+        #[arg(long)]
+        filter_id: String,
     },
 }
 
@@ -304,14 +546,113 @@ pub enum MoveCommands {
     Task {
         #[arg(long)]
         task_id: String,
+        #[arg(
+            long,
+            required_unless_present_any = ["project", "inbox"],
+            conflicts_with = "project"
+        )]
+        project_id: Option<String>,
+        /// Project name or ID
+        #[arg(long)]
+        project: Option<String>,
+        #[arg(long, conflicts_with = "section")]
+        section_id: Option<String>,
+        /// Section name, ID or "Project/Section" path
+        #[arg(long)]
+        section: Option<String>,
+        /// Move the task to the Inbox
+        #[arg(long, conflicts_with_all = ["project", "project_id"])]
+        inbox: bool,
+    },
+    /// Nest a project under another one, or move it to the top level
+    Project {
+        #[arg(long, required_unless_present = "project", conflicts_with = "project")]
+        project_id: Option<String>,
+        /// Project name or ID
+        #[arg(long)]
+        project: Option<String>,
+        /// New parent project name or ID
+        #[arg(long, required_unless_present = "root", conflicts_with = "root")]
+        parent: Option<String>,
+        /// Move the project to the top level
+        #[arg(long)]
+        root: bool,
+    },
+    /// Move a section to a different project
+    Section {
+        #[arg(long, required_unless_present = "section", conflicts_with = "section")]
+        section_id: Option<String>,
+        /// Section name, ID or "Project/Section" path
+        #[arg(long)]
+        section: Option<String>,
+        #[arg(long, required_unless_present = "project", conflicts_with = "project")]
+        project_id: Option<String>,
+        /// Project name or ID
+        #[arg(long)]
+        project: Option<String>,
+    },
+    /// Move every active task matching a filter
+    Tasks {
+        /// e.g. "@someday", "#Inbox", "p1" or "search: report"; unknown expressions are rejected
+        #[arg(long)]
+        filter: String,
+        #[arg(long, required_unless_present = "project", conflicts_with = "project")]
+        project_id: Option<String>,
+        /// Project name or ID
+        #[arg(long)]
+        project: Option<String>,
+        #[arg(long, conflicts_with = "section")]
+        section_id: Option<String>,
+        /// Section name, ID or "Project/Section" path
+        #[arg(long)]
+        section: Option<String>,
+        /// Refuse to act when the filter matches more tasks than this
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+    },
+}
+
+#[derive(Clone, Subcommand)]
+pub enum ArchiveCommands {
+    /// Archive a project
+    Project {
+        #[arg(long, required_unless_present = "project", conflicts_with = "project")]
+        project_id: Option<String>,
+        /// Project name or ID
         #[arg(long)]
-        project_id: String,
+        project: Option<String>,
+    },
+    /// Archive a section
+    Section {
+        #[arg(long, required_unless_present = "section", conflicts_with = "section")]
+        section_id: Option<String>,
+        /// Section name, ID or "Project/Section" path
         #[arg(long)]
+        section: Option<String>,
+    },
+}
+
+#[derive(Clone, Subcommand)]
+pub enum UnarchiveCommands {
+    /// Unarchive a project
+    Project {
+        #[arg(long, required_unless_present = "project", conflicts_with = "project")]
+        project_id: Option<String>,
+        /// Archived project name or ID
+        #[arg(long)]
+        project: Option<String>,
+    },
+    /// Unarchive a section
+    Section {
+        #[arg(long, required_unless_present = "section", conflicts_with = "section")]
         section_id: Option<String>,
+        /// Section name, ID or "Project/Section" path
+        #[arg(long)]
+        section: Option<String>,
     },
 }
 
-/// Reorder commands - for reordering sections
+/// Reorder commands - for reordering sections and projects
 #[derive(Clone, Subcommand)]
 pub enum ReorderCommands {
     /// Reorder sections within a project
@@ -320,6 +661,12 @@ pub enum ReorderCommands {
         #[arg(long)]
         section_ids: String,
     },
+    /// Reorder sibling projects
+    Projects {
+        /// Project IDs in desired order (comma-separated)
+        #[arg(long)]
+        project_ids: String,
+    },
 }
 
 /// Sync commands - for synchronizing data with Todoist
@@ -341,6 +688,45 @@ pub enum CacheCommands {
     Clear,
 }
 
+/// Markdown commands - for syncing checklists in notes files
+#[derive(Parser)]
+pub enum MdCommands {
+    /// Two-way sync of the `- [ ]` lines of a file: pushes ticks, edits and new
+    /// lines, pulls changes made in Todoist and writes new task ids back
+    Sync {
+        /// Markdown file, e.g. one written with `--format obsidian`
+        file: std::path::PathBuf,
+    },
+}
+
+/// Export command - for backups and using tasks in other applications
+#[derive(Parser)]
+#[command(args_conflicts_with_subcommands = true, arg_required_else_help = true)]
+pub struct ExportCommand {
+    /// Print projects, sections, tasks, labels, filters and comments as a JSON backup
+    #[arg(long)]
+    pub all: bool,
+
+    #[command(subcommand)]
+    pub command: Option<ExportCommands>,
+}
+
+#[derive(Subcommand)]
+pub enum ExportCommands {
+    /// Write dated tasks as an iCalendar feed for calendar apps
+    Ics {
+        #[arg(long)]
+        filter: Option<String>,
+        /// Write events (all-day or timed) or to-dos
+        #[arg(long, value_enum, default_value_t)]
+        component: crate::ics::Component,
+        /// Write the calendar to this file instead of stdout
+        #[arg(long, short)]
+        output: Option<std::path::PathBuf>,
+    },
+}
+
+pub mod confirm;
 pub mod error;
 pub mod handlers;
 
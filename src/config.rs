@@ -14,6 +14,12 @@ pub struct Config {
     /// Cache time-to-live in seconds (default: 300 = 5 minutes)
     #[serde(default)]
     pub cache_ttl: u64,
+    /// Refuse destructive commands without `--yes` even when not running in a terminal
+    #[serde(default)]
+    pub require_confirmation: bool,
+    /// Project name or ID used by `add task` when no project is given (default: Inbox)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_project: Option<String>,
 }
 
 impl Default for Config {
@@ -21,10 +27,22 @@ impl Default for Config {
         Self {
             api_token: String::new(),
             cache_ttl: 300,
+            require_confirmation: false,
+            default_project: None,
         }
     }
 }
 
+/// `TODORUST_CONFIG_DIR`, or `todorust` in the platform config directory
+pub fn config_dir() -> Result<PathBuf> {
+    if let Ok(dir) = std::env::var("TODORUST_CONFIG_DIR") {
+        return Ok(PathBuf::from(dir));
+    }
+    Ok(dirs::config_dir()
+        .ok_or_else(|| TodoError::Config("Cannot find config directory".to_string()))?
+        .join("todorust"))
+}
+
 pub fn load_config() -> Result<Config> {
     // First, check for API token in environment variable
     if let Ok(token) = std::env::var("TODORUST_API_TOKEN") {
@@ -37,15 +55,7 @@ pub fn load_config() -> Result<Config> {
     }
 
     // Then, check for config file
-    let config_dir = if let Ok(dir) = std::env::var("TODORUST_CONFIG_DIR") {
-        PathBuf::from(dir)
-    } else {
-        dirs::config_dir()
-            .ok_or_else(|| TodoError::Config("Cannot find config directory".to_string()))?
-            .join("todorust")
-    };
-
-    let config_path = config_dir.join("config.toml");
+    let config_path = config_dir()?.join("config.toml");
 
     if !config_path.exists() {
         return Err(TodoError::ConfigNotFound);
@@ -60,13 +70,7 @@ pub fn load_config() -> Result<Config> {
 }
 
 pub fn init_config(api_token: &str) -> Result<()> {
-    let config_dir = if let Ok(dir) = std::env::var("TODORUST_CONFIG_DIR") {
-        PathBuf::from(dir)
-    } else {
-        dirs::config_dir()
-            .ok_or_else(|| TodoError::Config("Cannot find config directory".to_string()))?
-            .join("todorust")
-    };
+    let config_dir = config_dir()?;
 
     fs::create_dir_all(&config_dir)
         .map_err(|e| TodoError::Config(format!("Cannot create config directory: {}", e)))?;
@@ -99,6 +103,18 @@ mod tests {
 
         let config: Config = toml::from_str(toml_str).unwrap();
         assert_eq!(config.api_token, "test_token_123");
+        assert!(config.default_project.is_none());
+    }
+
+    #[test]
+    fn test_config_default_project() {
+        let toml_str = r#"
+            api_token = "t"
+            default_project = "Work"
+        "#;
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.default_project.as_deref(), Some("Work"));
     }
 
     #[test]
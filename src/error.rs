@@ -32,6 +32,13 @@ pub enum TodoError {
 
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
+
+    #[error("Template error: {0}")]
+    Template(String),
+
+    /// Returned by write operations in dry-run mode instead of sending commands
+    #[error("Dry run: commands were not sent")]
+    DryRun,
 }
 
 impl From<serde_json::Error> for TodoError {
@@ -46,6 +53,12 @@ impl From<toml::de::Error> for TodoError {
     }
 }
 
+impl From<minijinja::Error> for TodoError {
+    fn from(err: minijinja::Error) -> Self {
+        TodoError::Template(err.to_string())
+    }
+}
+
 pub type Result<T> = std::result::Result<T, TodoError>;
 
 #[cfg(test)]
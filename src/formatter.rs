@@ -2,69 +2,322 @@
  * # Output Formatting
  *
  * This module provides utilities for formatting task, project, and filter output
- * in various formats (JSON, Checklist, Structured).
+ * in various formats (JSON, Checklist, Structured, Table, CSV/TSV, NDJSON).
  *
  * ## Supported Formats
  *
  * - **JSON**: Machine-readable JSON output
  * - **Checklist**: Simple checkbox format
  * - **Structured**: Hierarchical format with project groupings
+ * - **Obsidian**: Tasks grouped by project with Obsidian Tasks metadata (dates, priority, tags)
+ * - **Table**: Aligned columns for reading at a terminal
+ * - **CSV/TSV**: One row per item with a header line, for spreadsheets
+ * - **NDJSON**: One compact JSON object per line, for pipelines
+ * - **Template**: User-defined templates (see [`crate::template`]); checklist and
+ *   structured are built-in templates
  */
 
-use crate::models::{Filter, Project, TaskOutput};
+use crate::agenda::{Agenda, AgendaEntry};
+use crate::listing::Grouped;
+use crate::models::{Collaborator, Filter, Project, TaskOutput};
+use crate::projection::Projection;
 use crate::sync::{SyncFilter, SyncLabel, SyncSection};
-use clap::ValueEnum;
+use crate::table::{Color, RenderOptions, Table};
+use crate::template::builtin;
+use serde::Serialize;
 use serde_json::Value;
+use std::str::FromStr;
 
-#[derive(Clone, Debug, PartialEq, ValueEnum)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum OutputFormat {
     Json,
     Checklist,
     Structured,
+    /// Obsidian Tasks / Dataview checklist; other resources use the checklist
+    Obsidian,
+    Table,
+    Csv,
+    Tsv,
+    Ndjson,
+    /// A user template, holding its source
+    Template(String),
+}
+
+/// Accepts the format names, or `template:<name>` for a template stored in the config directory.
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(name) = s.strip_prefix("template:") {
+            return crate::template::load_named(name)
+                .map(OutputFormat::Template)
+                .map_err(|e| e.to_string());
+        }
+        match s.to_lowercase().as_str() {
+            "json" => Ok(OutputFormat::Json),
+            "checklist" => Ok(OutputFormat::Checklist),
+            "structured" => Ok(OutputFormat::Structured),
+            "obsidian" => Ok(OutputFormat::Obsidian),
+            "table" => Ok(OutputFormat::Table),
+            "csv" => Ok(OutputFormat::Csv),
+            "tsv" => Ok(OutputFormat::Tsv),
+            "ndjson" => Ok(OutputFormat::Ndjson),
+            _ => Err(format!(
+                "unknown format '{}'; expected json, checklist, structured, obsidian, table, csv, \
+                 tsv, ndjson or template:<name>",
+                s
+            )),
+        }
+    }
 }
 
 pub trait Formattable {
     fn format(&self, format: &OutputFormat) -> String;
     /// Formats the object as JSON, optionally filtering specific fields.
     fn format_filtered(&self, fields: Option<&str>) -> String;
+    /// Formats the object as a table, optionally choosing the columns.
+    fn format_table(&self, columns: Option<&str>) -> String;
+    /// The rows written by the CSV, TSV and NDJSON formats, as a JSON array.
+    fn records(&self) -> Value;
+    /// The data templates are rendered against.
+    fn template_context(&self) -> Value;
+
+    /// Formats the records as CSV, TSV or NDJSON, optionally filtering specific fields.
+    fn format_records(&self, format: &OutputFormat, fields: Option<&str>) -> String {
+        write_records(self.records(), format, fields)
+    }
+
+    /// Formats for output; `fields` projects JSON and record output and picks table columns
+    /// (see [`crate::projection`]). Unknown field names are warned about on stderr.
+    /// Template errors are returned rather than printed.
+    fn format_output(
+        &self,
+        format: &OutputFormat,
+        fields: Option<&str>,
+    ) -> crate::error::Result<String> {
+        if let Some(f) = fields {
+            match format {
+                OutputFormat::Json
+                | OutputFormat::Table
+                | OutputFormat::Csv
+                | OutputFormat::Tsv
+                | OutputFormat::Ndjson => {
+                    for name in Projection::parse(f).unknown(&self.records()) {
+                        eprintln!("Warning: unknown field '{}' in --fields", name);
+                    }
+                }
+                _ => eprintln!(
+                    "Warning: --fields only applies to the json, table, csv, tsv and ndjson formats"
+                ),
+            }
+        }
+        Ok(match (format, fields) {
+            (OutputFormat::Json, Some(_)) => self.format_filtered(fields),
+            (OutputFormat::Table, _) => self.format_table(fields),
+            (OutputFormat::Csv | OutputFormat::Tsv | OutputFormat::Ndjson, _) => {
+                self.format_records(format, fields)
+            }
+            (OutputFormat::Template(source), _) => {
+                crate::template::render(source, self.template_context())?
+            }
+            _ => self.format(format),
+        })
+    }
 }
 
-/// Filters a JSON value to include only specific fields.
-pub fn filter_json(value: Value, fields: &str) -> Value {
-    let field_list: Vec<&str> = fields.split(',').map(|s| s.trim()).collect();
+/// Writes `rows` as CSV, TSV or NDJSON, optionally projecting specific fields.
+fn write_records(rows: Value, format: &OutputFormat, fields: Option<&str>) -> String {
+    let projection = fields.map(Projection::parse);
+    let rows = match &projection {
+        Some(p) => p.apply(rows),
+        None => rows,
+    };
+    let rows = match rows {
+        Value::Array(rows) => rows,
+        _ => Vec::new(),
+    };
+    let columns = projection.as_ref().and_then(Projection::columns);
+    match format {
+        OutputFormat::Csv => delimited(&rows, columns, ','),
+        OutputFormat::Tsv => delimited(&rows, columns, '\t'),
+        _ => rows
+            .iter()
+            .map(|row| serde_json::to_string(row).unwrap_or_default())
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
 
-    match value {
-        Value::Array(items) => {
-            let filtered_items: Vec<Value> = items
-                .into_iter()
-                .map(|item| filter_object(item, &field_list))
-                .collect();
-            Value::Array(filtered_items)
+/// Renders a template for `format`, where errors can only be shown in place of the output
+fn render_template(item: &impl Formattable, source: &str) -> String {
+    crate::template::render(source, item.template_context()).unwrap_or_else(|e| e.to_string())
+}
+
+/// Writes rows with a header line; the columns are the given ones in order,
+/// or every field in declaration order.
+fn delimited(rows: &[Value], columns: Option<Vec<&str>>, separator: char) -> String {
+    let columns: Vec<String> = match columns {
+        Some(c) => c.into_iter().map(String::from).collect(),
+        None => {
+            let mut columns: Vec<String> = Vec::new();
+            for key in rows
+                .iter()
+                .filter_map(Value::as_object)
+                .flat_map(|r| r.keys())
+            {
+                if !columns.contains(key) {
+                    columns.push(key.clone());
+                }
+            }
+            columns
         }
-        _ => filter_object(value, &field_list),
+    };
+
+    let line = |cells: Vec<String>| {
+        cells
+            .iter()
+            .map(|cell| escape_cell(cell, separator))
+            .collect::<Vec<_>>()
+            .join(&separator.to_string())
+    };
+    let mut lines = vec![line(columns.clone())];
+    for row in rows {
+        lines.push(line(
+            columns
+                .iter()
+                .map(|c| record_text(row.get(c).unwrap_or(&Value::Null)))
+                .collect(),
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Text of a record field; lists are joined with `;`, objects written as JSON
+fn record_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items.iter().map(record_text).collect::<Vec<_>>().join(";"),
+        other => other.to_string(),
     }
 }
 
-fn filter_object(value: Value, fields: &[&str]) -> Value {
-    if let Value::Object(map) = value {
-        let mut filtered_map = serde_json::Map::new();
-        for &field in fields {
-            if let Some(val) = map.get(field) {
-                filtered_map.insert(field.to_string(), val.clone());
+/// CSV quotes cells containing the separator, quotes or line breaks (RFC 4180);
+/// TSV escapes tabs, line breaks and backslashes instead.
+fn escape_cell(cell: &str, separator: char) -> String {
+    if separator == '\t' {
+        cell.replace('\\', "\\\\")
+            .replace('\t', "\\t")
+            .replace('\n', "\\n")
+            .replace('\r', "\\r")
+    } else if cell.contains([separator, '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+/// Renders serialized rows as a table sized for stdout.
+///
+/// `columns` is a `--fields` expression; exclusions alone drop columns from
+/// `defaults`, which are used without it.
+fn table(
+    rows: Value,
+    defaults: &[&str],
+    columns: Option<&str>,
+    color: impl Fn(&Value, &str) -> Option<Color>,
+) -> String {
+    let rows = match rows {
+        Value::Array(rows) => rows,
+        _ => Vec::new(),
+    };
+    let projection = columns.map(Projection::parse).unwrap_or_default();
+    let Some(columns) = projection.columns() else {
+        let columns: Vec<&str> = defaults
+            .iter()
+            .copied()
+            .filter(|c| !projection.excludes(c))
+            .collect();
+        return Table::from_rows(&rows, &columns, color).render(RenderOptions::detect());
+    };
+    // Projected values are added to the rows, so colors still see every field
+    let rows: Vec<Value> = rows
+        .into_iter()
+        .map(|row| match (projection.apply(row.clone()), row) {
+            (Value::Object(projected), Value::Object(mut row)) => {
+                row.extend(projected);
+                Value::Object(row)
             }
+            (projected, _) => projected,
+        })
+        .collect();
+    Table::from_rows(&rows, &columns, color).render(RenderOptions::detect())
+}
+
+fn to_rows<T: Serialize>(items: &[T]) -> Value {
+    serde_json::to_value(items).unwrap_or(Value::Array(vec![]))
+}
+
+const TASK_COLUMNS: &[&str] = &[
+    "id",
+    "content",
+    "priority_label",
+    "due_date",
+    "project_name",
+    "labels",
+];
+const PROJECT_COLUMNS: &[&str] = &[
+    "id",
+    "name",
+    "color",
+    "view_style",
+    "is_shared",
+    "is_favorite",
+];
+const SECTION_COLUMNS: &[&str] = &["id", "name", "project_id"];
+const FILTER_COLUMNS: &[&str] = &["id", "name", "query"];
+const LABEL_COLUMNS: &[&str] = &["id", "name", "color", "is_favorite"];
+const COLLABORATOR_COLUMNS: &[&str] = &["id", "name", "email", "state"];
+const AGENDA_COLUMNS: &[&str] = &["start", "end", "content", "project_name", "overlaps_with"];
+
+/// Completed tasks are dimmed, priorities colored and overdue dates red
+fn task_color(task: &Value, column: &str) -> Option<Color> {
+    if task["is_completed"] == true {
+        return Some(Color::Dim);
+    }
+    match column {
+        "priority" | "priority_label" => match task["priority_label"].as_str() {
+            Some("p1") => Some(Color::Red),
+            Some("p2") => Some(Color::Yellow),
+            Some("p3") => Some(Color::Blue),
+            _ => None,
+        },
+        "due_date" | "due_datetime" | "due_string" => {
+            let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+            let due = task["due_date"].as_str()?;
+            (due.get(..10).unwrap_or(due) < today.as_str()).then_some(Color::Red)
         }
-        Value::Object(filtered_map)
-    } else {
-        value
+        _ => None,
     }
 }
 
+/// Filters a JSON value with a `--fields` expression (see [`crate::projection`]).
+pub fn filter_json(value: Value, fields: &str) -> Value {
+    Projection::parse(fields).apply(value)
+}
+
 impl Formattable for Vec<TaskOutput> {
     fn format(&self, format: &OutputFormat) -> String {
         match format {
             OutputFormat::Json => format_json(self),
-            OutputFormat::Checklist => format_checklist(self),
-            OutputFormat::Structured => format_structured(self),
+            OutputFormat::Checklist => render_template(self, builtin::TASKS_CHECKLIST),
+            OutputFormat::Structured => render_template(self, builtin::TASKS_STRUCTURED),
+            OutputFormat::Obsidian => render_template(self, builtin::TASKS_OBSIDIAN),
+            OutputFormat::Table => self.format_table(None),
+            OutputFormat::Csv | OutputFormat::Tsv | OutputFormat::Ndjson => {
+                self.format_records(format, None)
+            }
+            OutputFormat::Template(source) => render_template(self, source),
         }
     }
 
@@ -76,70 +329,114 @@ impl Formattable for Vec<TaskOutput> {
             serde_json::to_string_pretty(&json).unwrap_or_default()
         }
     }
+
+    fn format_table(&self, columns: Option<&str>) -> String {
+        table(to_rows(self), TASK_COLUMNS, columns, task_color)
+    }
+
+    fn records(&self) -> Value {
+        to_rows(self)
+    }
+
+    fn template_context(&self) -> Value {
+        crate::template::context("tasks", to_rows(self))
+    }
 }
 
 fn format_json(tasks: &[TaskOutput]) -> String {
     serde_json::to_string_pretty(tasks).unwrap_or_default()
 }
 
-fn format_checklist(tasks: &[TaskOutput]) -> String {
-    tasks
-        .iter()
-        .map(|task| {
-            let checkbox = if task.is_completed { "[x]" } else { "[ ]" };
-            if let Some(ref project) = task.project_name {
-                format!("- {} {} ({})", checkbox, task.content, project)
-            } else {
-                format!("- {} {}", checkbox, task.content)
+/// Groups are headings in the text formats and tables, keys of a JSON object,
+/// and a leading `group` field in records.
+impl Formattable for Grouped {
+    fn format(&self, format: &OutputFormat) -> String {
+        match format {
+            OutputFormat::Json => self.format_filtered(None),
+            OutputFormat::Checklist => render_template(self, builtin::TASKS_CHECKLIST),
+            OutputFormat::Structured => render_template(self, builtin::TASKS_STRUCTURED),
+            OutputFormat::Obsidian => render_template(self, builtin::TASKS_OBSIDIAN),
+            OutputFormat::Table => self.format_table(None),
+            OutputFormat::Csv | OutputFormat::Tsv | OutputFormat::Ndjson => {
+                self.format_records(format, None)
             }
-        })
-        .collect::<Vec<_>>()
-        .join("\n")
-}
+            OutputFormat::Template(source) => render_template(self, source),
+        }
+    }
 
-fn format_structured(tasks: &[TaskOutput]) -> String {
-    use std::collections::HashMap;
+    fn format_filtered(&self, fields: Option<&str>) -> String {
+        let groups: serde_json::Map<String, Value> = self
+            .groups
+            .iter()
+            .map(|group| {
+                let rows = to_rows(&group.tasks);
+                let rows = match fields {
+                    Some(f) => filter_json(rows, f),
+                    None => rows,
+                };
+                (group.name.clone(), rows)
+            })
+            .collect();
+        serde_json::to_string_pretty(&groups).unwrap_or_default()
+    }
 
-    let mut grouped: HashMap<&str, Vec<&TaskOutput>> = HashMap::new();
+    fn format_table(&self, columns: Option<&str>) -> String {
+        self.groups
+            .iter()
+            .map(|group| format!("## {}\n{}", group.name, group.tasks.format_table(columns)))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
 
-    for task in tasks {
-        let project = task.project_name.as_deref().unwrap_or("Inbox");
-        grouped.entry(project).or_default().push(task);
+    fn records(&self) -> Value {
+        let mut rows = Vec::new();
+        for group in &self.groups {
+            for row in group.tasks.records().as_array().into_iter().flatten() {
+                let mut record = serde_json::Map::new();
+                record.insert("group".to_string(), Value::String(group.name.clone()));
+                if let Value::Object(fields) = row {
+                    record.extend(fields.clone());
+                }
+                rows.push(Value::Object(record));
+            }
+        }
+        Value::Array(rows)
     }
 
-    let mut projects: Vec<_> = grouped.into_iter().collect();
-    projects.sort_by(|a, b| a.0.cmp(b.0));
+    fn format_records(&self, format: &OutputFormat, fields: Option<&str>) -> String {
+        // The group column is kept when picking fields
+        let fields = fields.map(|f| match Projection::parse(f).columns() {
+            Some(columns) if !columns.contains(&"group") => format!("group,{}", f),
+            _ => f.to_string(),
+        });
+        write_records(self.records(), format, fields.as_deref())
+    }
 
-    projects
-        .iter()
-        .map(|(project, tasks)| {
-            let tasks_str = tasks
-                .iter()
-                .map(|task| {
-                    let checkbox = if task.is_completed { "[x]" } else { "[ ]" };
-                    if task.priority > 1 {
-                        format!(
-                            "- {} {} (Priority: {})",
-                            checkbox, task.content, task.priority
-                        )
-                    } else {
-                        format!("- {} {}", checkbox, task.content)
-                    }
-                })
-                .collect::<Vec<_>>()
-                .join("\n");
-            format!("## {}\n\n{}", project, tasks_str)
-        })
-        .collect::<Vec<_>>()
-        .join("\n\n")
+    fn template_context(&self) -> Value {
+        let mut context = crate::template::context("tasks", to_rows(&self.tasks));
+        let groups: Vec<Value> = self
+            .groups
+            .iter()
+            .map(|group| serde_json::json!({"name": group.name, "tasks": to_rows(&group.tasks)}))
+            .collect();
+        context["groups"] = crate::template::strip_nulls(Value::Array(groups));
+        context
+    }
 }
 
 impl Formattable for Vec<Project> {
     fn format(&self, format: &OutputFormat) -> String {
         match format {
             OutputFormat::Json => format_json_projects(self),
-            OutputFormat::Checklist => format_projects_checklist(self),
-            OutputFormat::Structured => format_projects_structured(self),
+            OutputFormat::Checklist | OutputFormat::Obsidian => {
+                render_template(self, builtin::PROJECTS_CHECKLIST)
+            }
+            OutputFormat::Structured => render_template(self, builtin::PROJECTS_STRUCTURED),
+            OutputFormat::Table => self.format_table(None),
+            OutputFormat::Csv | OutputFormat::Tsv | OutputFormat::Ndjson => {
+                self.format_records(format, None)
+            }
+            OutputFormat::Template(source) => render_template(self, source),
         }
     }
 
@@ -151,60 +448,96 @@ impl Formattable for Vec<Project> {
             serde_json::to_string_pretty(&json).unwrap_or_default()
         }
     }
+
+    fn format_table(&self, columns: Option<&str>) -> String {
+        // Children follow their parent, indented by depth
+        let rows = project_rows(self)
+            .into_iter()
+            .map(|mut row| {
+                let depth = row["depth"].as_u64().unwrap_or(0) as usize;
+                let name = row["name"].as_str().unwrap_or_default().to_string();
+                row["name"] = Value::String(format!("{}{}", "  ".repeat(depth), name));
+                row
+            })
+            .collect();
+        table(Value::Array(rows), PROJECT_COLUMNS, columns, |_, _| None)
+    }
+
+    fn records(&self) -> Value {
+        to_rows(self)
+    }
+
+    fn template_context(&self) -> Value {
+        crate::template::context("projects", Value::Array(project_rows(self)))
+    }
 }
 
 fn format_json_projects(projects: &[Project]) -> String {
     serde_json::to_string_pretty(projects).unwrap_or_default()
 }
 
-fn format_projects_checklist(projects: &[Project]) -> String {
-    projects
-        .iter()
-        .map(|project| {
-            let indicator = if project.is_favorite { "⭐ " } else { "" };
-            format!(
-                "- [ ] {}{}{}",
-                indicator,
-                project.name,
-                if project.is_shared { " (shared)" } else { "" }
-            )
-        })
-        .collect::<Vec<_>>()
-        .join("\n")
-}
+/// Orders projects depth-first so children follow their parent, with their depth.
+///
+/// Projects whose parent is not in the list are treated as top-level.
+fn project_tree(projects: &[Project]) -> Vec<(usize, &Project)> {
+    fn visit<'a>(
+        project: &'a Project,
+        depth: usize,
+        projects: &'a [Project],
+        out: &mut Vec<(usize, &'a Project)>,
+    ) {
+        if out.iter().any(|(_, p)| p.id == project.id) {
+            return;
+        }
+        out.push((depth, project));
+        for child in projects
+            .iter()
+            .filter(|p| p.parent_id.as_deref() == Some(project.id.as_str()))
+        {
+            visit(child, depth + 1, projects, out);
+        }
+    }
 
-fn format_projects_structured(projects: &[Project]) -> String {
-    projects
-        .iter()
-        .map(|project| {
-            let mut meta_parts = Vec::new();
-            if project.is_favorite {
-                meta_parts.push("⭐ Favorite");
-            }
-            if project.is_shared {
-                meta_parts.push("👥 Shared");
-            }
-            let meta = if meta_parts.is_empty() {
-                String::new()
-            } else {
-                format!("**Meta:** {} | ", meta_parts.join(" | "))
-            };
+    let mut out = Vec::with_capacity(projects.len());
+    for project in projects.iter().filter(|p| {
+        p.parent_id
+            .as_ref()
+            .is_none_or(|parent| !projects.iter().any(|q| &q.id == parent))
+    }) {
+        visit(project, 0, projects, &mut out);
+    }
+    // Parent cycles have no root; list them at the top level
+    for project in projects {
+        visit(project, 0, projects, &mut out);
+    }
+    out
+}
 
-            format!(
-                "### {}\n\n**Color:** {}\n**ID:** {}\n{}",
-                project.name, project.color, project.id, meta
-            )
+/// Projects in tree order, each with its `depth`
+fn project_rows(projects: &[Project]) -> Vec<Value> {
+    project_tree(projects)
+        .into_iter()
+        .map(|(depth, project)| {
+            let mut row = serde_json::to_value(project).unwrap_or(Value::Null);
+            row["depth"] = Value::from(depth);
+            row
         })
-        .collect::<Vec<_>>()
-        .join("\n\n")
+        .collect()
 }
 
 impl Formattable for Vec<Filter> {
     fn format(&self, format: &OutputFormat) -> String {
         match format {
             OutputFormat::Json => format_json_filters(self),
-            OutputFormat::Checklist => format_filters_checklist(self),
-            OutputFormat::Structured => format_filters_structured(self),
+            OutputFormat::Checklist | OutputFormat::Obsidian => {
+                render_template(self, builtin::FILTERS_CHECKLIST)
+            }
+            OutputFormat::Structured => render_template(self, builtin::FILTERS_STRUCTURED),
+            OutputFormat::Table => self.format_table(None),
+            OutputFormat::Csv | OutputFormat::Tsv | OutputFormat::Ndjson => {
+                self.format_records(format, None)
+            }
+            OutputFormat::Template(source) => render_template(self, source),
         }
     }
 
@@ -216,39 +549,37 @@ impl Formattable for Vec<Filter> {
             serde_json::to_string_pretty(&json).unwrap_or_default()
         }
     }
-}
 
-fn format_json_filters(filters: &[Filter]) -> String {
-    serde_json::to_string_pretty(filters).unwrap_or_default()
-}
+    fn format_table(&self, columns: Option<&str>) -> String {
+        table(to_rows(self), FILTER_COLUMNS, columns, |_, _| None)
+    }
+
+    fn records(&self) -> Value {
+        to_rows(self)
+    }
 
-fn format_filters_checklist(filters: &[Filter]) -> String {
-    filters
-        .iter()
-        .map(|filter| format!("- [ ] {} ({})", filter.name, filter.query))
-        .collect::<Vec<_>>()
-        .join("\n")
+    fn template_context(&self) -> Value {
+        crate::template::context("filters", to_rows(self))
+    }
 }
 
-fn format_filters_structured(filters: &[Filter]) -> String {
-    filters
-        .iter()
-        .map(|filter| {
-            format!(
-                "### {}\n\n**Filter:** `{}`\n**ID:** {}\n",
-                filter.name, filter.query, filter.id
-            )
-        })
-        .collect::<Vec<_>>()
-        .join("\n\n")
+fn format_json_filters(filters: &[Filter]) -> String {
+    serde_json::to_string_pretty(filters).unwrap_or_default()
 }
 
 impl Formattable for Vec<SyncSection> {
     fn format(&self, format: &OutputFormat) -> String {
         match format {
             OutputFormat::Json => format_json_sections(self),
-            OutputFormat::Checklist => format_sections_checklist(self),
-            OutputFormat::Structured => format_sections_structured(self),
+            OutputFormat::Checklist | OutputFormat::Obsidian => {
+                render_template(self, builtin::SECTIONS_CHECKLIST)
+            }
+            OutputFormat::Structured => render_template(self, builtin::SECTIONS_STRUCTURED),
+            OutputFormat::Table => self.format_table(None),
+            OutputFormat::Csv | OutputFormat::Tsv | OutputFormat::Ndjson => {
+                self.format_records(format, None)
+            }
+            OutputFormat::Template(source) => render_template(self, source),
         }
     }
 
@@ -260,55 +591,37 @@ impl Formattable for Vec<SyncSection> {
             serde_json::to_string_pretty(&json).unwrap_or_default()
         }
     }
-}
-
-fn format_json_sections(sections: &[SyncSection]) -> String {
-    serde_json::to_string_pretty(sections).unwrap_or_default()
-}
 
-fn format_sections_checklist(sections: &[SyncSection]) -> String {
-    sections
-        .iter()
-        .map(|section| format!("- [ ] {} (Project: {})", section.name, section.project_id))
-        .collect::<Vec<_>>()
-        .join("\n")
-}
-
-fn format_sections_structured(sections: &[SyncSection]) -> String {
-    use std::collections::HashMap;
-
-    let mut grouped: HashMap<&str, Vec<&SyncSection>> = HashMap::new();
+    fn format_table(&self, columns: Option<&str>) -> String {
+        table(to_rows(self), SECTION_COLUMNS, columns, |_, _| None)
+    }
 
-    for section in sections {
-        grouped
-            .entry(section.project_id.as_str())
-            .or_default()
-            .push(section);
+    fn records(&self) -> Value {
+        to_rows(self)
     }
 
-    let mut projects: Vec<_> = grouped.into_iter().collect();
-    projects.sort_by(|a, b| a.0.cmp(b.0));
+    fn template_context(&self) -> Value {
+        crate::template::context("sections", to_rows(self))
+    }
+}
 
-    projects
-        .iter()
-        .map(|(project_id, sections)| {
-            let sections_str = sections
-                .iter()
-                .map(|s| format!("- [ ] {} (ID: {})", s.name, s.id))
-                .collect::<Vec<_>>()
-                .join("\n");
-            format!("## Project: {}\n\n{}", project_id, sections_str)
-        })
-        .collect::<Vec<_>>()
-        .join("\n\n")
+fn format_json_sections(sections: &[SyncSection]) -> String {
+    serde_json::to_string_pretty(sections).unwrap_or_default()
 }
 
 impl Formattable for Vec<SyncFilter> {
     fn format(&self, format: &OutputFormat) -> String {
         match format {
             OutputFormat::Json => format_json_sync_filters(self),
-            OutputFormat::Checklist => format_sync_filters_checklist(self),
-            OutputFormat::Structured => format_sync_filters_structured(self),
+            OutputFormat::Checklist | OutputFormat::Obsidian => {
+                render_template(self, builtin::FILTERS_CHECKLIST)
+            }
+            OutputFormat::Structured => render_template(self, builtin::FILTERS_STRUCTURED),
+            OutputFormat::Table => self.format_table(None),
+            OutputFormat::Csv | OutputFormat::Tsv | OutputFormat::Ndjson => {
+                self.format_records(format, None)
+            }
+            OutputFormat::Template(source) => render_template(self, source),
         }
     }
 
@@ -320,39 +633,37 @@ impl Formattable for Vec<SyncFilter> {
             serde_json::to_string_pretty(&json).unwrap_or_default()
         }
     }
-}
 
-fn format_json_sync_filters(filters: &[SyncFilter]) -> String {
-    serde_json::to_string_pretty(filters).unwrap_or_default()
-}
+    fn format_table(&self, columns: Option<&str>) -> String {
+        table(to_rows(self), FILTER_COLUMNS, columns, |_, _| None)
+    }
+
+    fn records(&self) -> Value {
+        to_rows(self)
+    }
 
-fn format_sync_filters_checklist(filters: &[SyncFilter]) -> String {
-    filters
-        .iter()
-        .map(|filter| format!("- [ ] {} ({})", filter.name, filter.query))
-        .collect::<Vec<_>>()
-        .join("\n")
+    fn template_context(&self) -> Value {
+        crate::template::context("filters", to_rows(self))
+    }
 }
 
-fn format_sync_filters_structured(filters: &[SyncFilter]) -> String {
-    filters
-        .iter()
-        .map(|filter| {
-            format!(
-                "### {}\n\n**Filter:** `{}`\n**ID:** {}\n",
-                filter.name, filter.query, filter.id
-            )
-        })
-        .collect::<Vec<_>>()
-        .join("\n\n")
+fn format_json_sync_filters(filters: &[SyncFilter]) -> String {
+    serde_json::to_string_pretty(filters).unwrap_or_default()
 }
 
 impl Formattable for Vec<SyncLabel> {
     fn format(&self, format: &OutputFormat) -> String {
         match format {
             OutputFormat::Json => format_json_sync_labels(self),
-            OutputFormat::Checklist => format_sync_labels_checklist(self),
-            OutputFormat::Structured => format_sync_labels_structured(self),
+            OutputFormat::Checklist | OutputFormat::Obsidian => {
+                render_template(self, builtin::LABELS_CHECKLIST)
+            }
+            OutputFormat::Structured => render_template(self, builtin::LABELS_STRUCTURED),
+            OutputFormat::Table => self.format_table(None),
+            OutputFormat::Csv | OutputFormat::Tsv | OutputFormat::Ndjson => {
+                self.format_records(format, None)
+            }
+            OutputFormat::Template(source) => render_template(self, source),
         }
     }
 
@@ -364,44 +675,120 @@ impl Formattable for Vec<SyncLabel> {
             serde_json::to_string_pretty(&json).unwrap_or_default()
         }
     }
+
+    fn format_table(&self, columns: Option<&str>) -> String {
+        table(to_rows(self), LABEL_COLUMNS, columns, |_, _| None)
+    }
+
+    fn records(&self) -> Value {
+        to_rows(self)
+    }
+
+    fn template_context(&self) -> Value {
+        crate::template::context("labels", to_rows(self))
+    }
 }
 
 fn format_json_sync_labels(labels: &[SyncLabel]) -> String {
     serde_json::to_string_pretty(labels).unwrap_or_default()
 }
 
-fn format_sync_labels_checklist(labels: &[SyncLabel]) -> String {
-    labels
-        .iter()
-        .map(|label| {
-            let fav = if label.is_favorite { "⭐ " } else { "" };
-            format!("- [ ] {}{} (Color: {})", fav, label.name, label.color)
-        })
-        .collect::<Vec<_>>()
-        .join("\n")
+impl Formattable for Vec<Collaborator> {
+    fn format(&self, format: &OutputFormat) -> String {
+        match format {
+            OutputFormat::Json => format_json_collaborators(self),
+            OutputFormat::Checklist | OutputFormat::Obsidian => {
+                render_template(self, builtin::COLLABORATORS_CHECKLIST)
+            }
+            OutputFormat::Structured => render_template(self, builtin::COLLABORATORS_STRUCTURED),
+            OutputFormat::Table => self.format_table(None),
+            OutputFormat::Csv | OutputFormat::Tsv | OutputFormat::Ndjson => {
+                self.format_records(format, None)
+            }
+            OutputFormat::Template(source) => render_template(self, source),
+        }
+    }
+
+    fn format_filtered(&self, fields: Option<&str>) -> String {
+        let json = serde_json::to_value(self).unwrap_or(Value::Array(vec![]));
+        if let Some(f) = fields {
+            serde_json::to_string_pretty(&filter_json(json, f)).unwrap_or_default()
+        } else {
+            serde_json::to_string_pretty(&json).unwrap_or_default()
+        }
+    }
+
+    fn format_table(&self, columns: Option<&str>) -> String {
+        table(to_rows(self), COLLABORATOR_COLUMNS, columns, |_, _| None)
+    }
+
+    fn records(&self) -> Value {
+        to_rows(self)
+    }
+
+    fn template_context(&self) -> Value {
+        crate::template::context("collaborators", to_rows(self))
+    }
 }
 
-fn format_sync_labels_structured(labels: &[SyncLabel]) -> String {
-    labels
-        .iter()
-        .map(|label| {
-            let fav = if label.is_favorite {
-                "**Favorite:** Yes\n"
-            } else {
-                ""
-            };
-            format!(
-                "### {}\n\n**Color:** {}\n**ID:** {}\n{}",
-                label.name, label.color, label.id, fav
-            )
+fn format_json_collaborators(collaborators: &[Collaborator]) -> String {
+    serde_json::to_string_pretty(collaborators).unwrap_or_default()
+}
+
+impl Formattable for Agenda {
+    fn format(&self, format: &OutputFormat) -> String {
+        match format {
+            OutputFormat::Json => serde_json::to_string_pretty(self).unwrap_or_default(),
+            OutputFormat::Checklist | OutputFormat::Obsidian => {
+                render_template(self, builtin::AGENDA_CHECKLIST)
+            }
+            OutputFormat::Structured => render_template(self, builtin::AGENDA_STRUCTURED),
+            OutputFormat::Table => self.format_table(None),
+            OutputFormat::Csv | OutputFormat::Tsv | OutputFormat::Ndjson => {
+                self.format_records(format, None)
+            }
+            OutputFormat::Template(source) => render_template(self, source),
+        }
+    }
+
+    /// Fields pick from the entries, like the table and record formats
+    fn format_filtered(&self, fields: Option<&str>) -> String {
+        let mut json = serde_json::to_value(self).unwrap_or(Value::Null);
+        if let Some(f) = fields {
+            for key in ["timed", "all_day"] {
+                json[key] = filter_json(json[key].take(), f);
+            }
+        }
+        serde_json::to_string_pretty(&json).unwrap_or_default()
+    }
+
+    fn format_table(&self, columns: Option<&str>) -> String {
+        table(self.records(), AGENDA_COLUMNS, columns, |entry, _| {
+            entry
+                .get("overlaps_with")
+                .is_some_and(|o| o.as_array().is_some_and(|o| !o.is_empty()))
+                .then_some(Color::Red)
         })
-        .collect::<Vec<_>>()
-        .join("\n\n")
+    }
+
+    /// Timed entries first, then the all-day ones
+    fn records(&self) -> Value {
+        let entries: Vec<&AgendaEntry> = self.timed.iter().chain(&self.all_day).collect();
+        to_rows(&entries)
+    }
+
+    fn template_context(&self) -> Value {
+        let agenda = serde_json::to_value(self).unwrap_or(Value::Null);
+        let mut context = crate::template::context("agenda", agenda);
+        context["items"] = crate::template::strip_nulls(self.records());
+        context
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::Priority;
 
     fn mock_tasks() -> Vec<TaskOutput> {
         vec![
@@ -411,10 +798,20 @@ mod tests {
                 description: Some("Task 1 details".to_string()),
                 project_name: Some("Work".to_string()),
                 is_completed: true,
-                priority: 4,
+                completed_at: None,
+                priority: Priority::URGENT,
+                priority_label: "p1".to_string(),
                 labels: vec![],
+                responsible_uid: None,
+                assignee: None,
+                duration: None,
                 project_id: Some("p1".to_string()),
+                section_id: None,
+                section_name: None,
                 due_date: None,
+                due_datetime: None,
+                due_string: None,
+                is_recurring: false,
                 created_at: "2026-01-10T10:00:00Z".to_string(),
                 order: 1,
             },
@@ -424,10 +821,20 @@ mod tests {
                 description: None,
                 project_name: Some("Personal".to_string()),
                 is_completed: false,
-                priority: 2,
+                completed_at: None,
+                priority: Priority::MEDIUM,
+                priority_label: "p3".to_string(),
                 labels: vec![],
+                responsible_uid: None,
+                assignee: None,
+                duration: None,
                 project_id: Some("p2".to_string()),
+                section_id: None,
+                section_name: None,
                 due_date: None,
+                due_datetime: None,
+                due_string: None,
+                is_recurring: false,
                 created_at: "2026-01-11T10:00:00Z".to_string(),
                 order: 2,
             },
@@ -449,9 +856,21 @@ mod tests {
         assert!(output.contains("## Personal"));
         assert!(output.contains("## Work"));
         assert!(output.contains("- [x] Task 1"));
-        assert!(output.contains("(Priority: 4)"));
+        assert!(output.contains("(Priority: p1, API 4)"));
         assert!(output.contains("- [ ] Task 2"));
-        assert!(output.contains("(Priority: 2)"));
+        assert!(output.contains("(Priority: p3, API 2)"));
+    }
+
+    #[test]
+    fn test_format_recurring_marker() {
+        let mut tasks = mock_tasks();
+        tasks[1].is_recurring = true;
+        tasks[1].due_string = Some("every monday".to_string());
+        let output = tasks.format(&OutputFormat::Checklist);
+        assert!(output.contains("- [ ] Task 2 (Personal) 🔁 every monday"));
+        assert!(!output.contains("Task 1 (Work) 🔁"));
+        let output = tasks.format(&OutputFormat::Structured);
+        assert!(output.contains("(Priority: p3, API 2) 🔁 every monday"));
     }
 
     #[test]
@@ -478,10 +897,20 @@ mod tests {
             description: None,
             project_name: None,
             is_completed: true,
-            priority: 1,
+            completed_at: None,
+            priority: Priority::LOW,
+            priority_label: "p4".to_string(),
             labels: vec![],
+            responsible_uid: None,
+            assignee: None,
+            duration: None,
             project_id: None,
+            section_id: None,
+            section_name: None,
             due_date: None,
+            due_datetime: None,
+            due_string: None,
+            is_recurring: false,
             created_at: "2026-01-10T10:00:00Z".to_string(),
             order: 1,
         }];
@@ -498,10 +927,20 @@ mod tests {
             description: None,
             project_name: None,
             is_completed: false,
-            priority: 1,
+            completed_at: None,
+            priority: Priority::LOW,
+            priority_label: "p4".to_string(),
             labels: vec![],
+            responsible_uid: None,
+            assignee: None,
+            duration: None,
             project_id: None,
+            section_id: None,
+            section_name: None,
             due_date: None,
+            due_datetime: None,
+            due_string: None,
+            is_recurring: false,
             created_at: "2026-01-10T10:00:00Z".to_string(),
             order: 1,
         }];
@@ -521,6 +960,7 @@ mod tests {
                 color: "blue".to_string(),
                 is_shared: false,
                 is_favorite: true,
+                ..Default::default()
             },
             Project {
                 id: "2".to_string(),
@@ -528,6 +968,7 @@ mod tests {
                 color: "green".to_string(),
                 is_shared: true,
                 is_favorite: false,
+                ..Default::default()
             },
         ];
 
@@ -544,6 +985,7 @@ mod tests {
             color: "blue".to_string(),
             is_shared: false,
             is_favorite: true,
+            ..Default::default()
         }];
 
         let output = projects.format(&OutputFormat::Structured);
@@ -552,6 +994,191 @@ mod tests {
         assert!(output.contains("⭐ Favorite"));
     }
 
+    #[test]
+    fn test_format_projects_nested() {
+        let project = |id: &str, name: &str, parent_id: Option<&str>| Project {
+            id: id.to_string(),
+            name: name.to_string(),
+            parent_id: parent_id.map(str::to_string),
+            ..Default::default()
+        };
+        let projects = vec![
+            project("3", "Q1", Some("2")),
+            project("1", "Personal", None),
+            project("2", "Work", None),
+            project("4", "Errands", Some("1")),
+        ];
+
+        let checklist = projects.format(&OutputFormat::Checklist);
+        assert_eq!(
+            checklist,
+            "- [ ] Personal\n  - [ ] Errands\n- [ ] Work\n  - [ ] Q1"
+        );
+
+        let structured = projects.format(&OutputFormat::Structured);
+        assert!(structured.contains("### Work"));
+        assert!(structured.contains("#### Q1"));
+    }
+
+    #[test]
+    fn test_format_table() {
+        let tasks = mock_tasks();
+        let output = tasks.format(&OutputFormat::Table);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("ID  CONTENT  PRIORITY_LABEL"));
+        assert!(lines[1].starts_with("1   Task 1   p1"));
+
+        let output = tasks
+            .format_output(&OutputFormat::Table, Some("content, project_name"))
+            .unwrap();
+        assert_eq!(
+            output,
+            "CONTENT  PROJECT_NAME\nTask 1   Work\nTask 2   Personal"
+        );
+    }
+
+    #[test]
+    fn test_format_csv() {
+        let mut tasks = mock_tasks();
+        tasks[0].content = "Say \"hi\", then leave".to_string();
+        tasks[0].labels = vec!["home".to_string(), "errand".to_string()];
+
+        let output = tasks.format(&OutputFormat::Csv);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("id,content,description,project_id,project_name,"));
+        assert!(lines[1].starts_with("1,\"Say \"\"hi\"\", then leave\",Task 1 details,p1,Work,"));
+        assert!(lines[1].contains(",home;errand,"));
+
+        let output = tasks
+            .format_output(&OutputFormat::Csv, Some("project_name,id"))
+            .unwrap();
+        assert_eq!(output, "project_name,id\nWork,1\nPersonal,2");
+    }
+
+    #[test]
+    fn test_format_tsv_escapes() {
+        let mut tasks = mock_tasks();
+        tasks[0].description = Some("line one\nline\ttwo".to_string());
+        let output = tasks
+            .format_output(&OutputFormat::Tsv, Some("id,description"))
+            .unwrap();
+        assert_eq!(output, "id\tdescription\n1\tline one\\nline\\ttwo\n2\t");
+    }
+
+    #[test]
+    fn test_format_ndjson() {
+        let tasks = mock_tasks();
+        let output = tasks
+            .format_output(&OutputFormat::Ndjson, Some("id,priority_label"))
+            .unwrap();
+        assert_eq!(
+            output,
+            "{\"id\":\"1\",\"priority_label\":\"p1\"}\n{\"id\":\"2\",\"priority_label\":\"p3\"}"
+        );
+
+        let full = tasks.format(&OutputFormat::Ndjson);
+        for line in full.lines() {
+            let task: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert!(task["content"].is_string());
+        }
+    }
+
+    #[test]
+    fn test_format_obsidian() {
+        let mut tasks = mock_tasks();
+        tasks[0].completed_at = Some("2026-01-12T12:00:00Z".to_string());
+        tasks[0].due_date = Some("2026-01-12".to_string());
+        tasks[1].labels = vec!["deep work".to_string(), "home".to_string()];
+        tasks[1].due_date = Some("2026-01-20T09:00:00".to_string());
+        tasks[1].due_string = Some("every monday 9am".to_string());
+        tasks[1].is_recurring = true;
+
+        let output = tasks.format(&OutputFormat::Obsidian);
+        assert_eq!(
+            output,
+            "## Personal\n\n\
+             - [ ] Task 2 <!-- todoist-id:2 --> #deep-work #home 🔼 🔁 every monday 9am 📅 2026-01-20\n\n\
+             ## Work\n\n\
+             - [x] Task 1 <!-- todoist-id:1 --> 🔺 📅 2026-01-12 ✅ 2026-01-12"
+        );
+
+        // Other resources fall back to the checklist
+        let filters = vec![Filter {
+            id: "1".to_string(),
+            name: "Today".to_string(),
+            query: "today".to_string(),
+        }];
+        assert_eq!(
+            filters.format(&OutputFormat::Obsidian),
+            "- [ ] Today (today)"
+        );
+    }
+
+    #[test]
+    fn test_output_format_from_str() {
+        assert_eq!("CSV".parse::<OutputFormat>(), Ok(OutputFormat::Csv));
+        assert_eq!("table".parse::<OutputFormat>(), Ok(OutputFormat::Table));
+        assert!("yaml".parse::<OutputFormat>().is_err());
+        assert!("template:".parse::<OutputFormat>().is_err());
+    }
+
+    #[test]
+    fn test_format_template() {
+        let tasks = mock_tasks();
+        let template = OutputFormat::Template(
+            "{% for task in tasks %}{{ task.id }}:{{ task.project_name }}{% endfor %}".to_string(),
+        );
+        assert_eq!(
+            tasks.format_output(&template, None).unwrap(),
+            "1:Work2:Personal"
+        );
+
+        let broken = OutputFormat::Template("{% if %}".to_string());
+        assert!(tasks.format_output(&broken, None).is_err());
+    }
+
+    #[test]
+    fn test_task_color() {
+        let task = |priority: &str, due: &str, completed: bool| serde_json::json!({"priority_label": priority, "due_date": due, "is_completed": completed});
+        assert_eq!(
+            task_color(&task("p1", "2099-01-01", false), "priority_label"),
+            Some(Color::Red)
+        );
+        assert_eq!(
+            task_color(&task("p4", "2099-01-01", false), "priority_label"),
+            None
+        );
+        assert_eq!(
+            task_color(&task("p4", "2099-01-01", false), "due_date"),
+            None
+        );
+        assert_eq!(
+            task_color(&task("p4", "2020-01-01T09:00:00", false), "due_date"),
+            Some(Color::Red)
+        );
+        assert_eq!(
+            task_color(&task("p1", "2020-01-01", true), "content"),
+            Some(Color::Dim)
+        );
+    }
+
+    #[test]
+    fn test_format_projects_table_nested() {
+        let project = |id: &str, name: &str, parent_id: Option<&str>| Project {
+            id: id.to_string(),
+            name: name.to_string(),
+            parent_id: parent_id.map(str::to_string),
+            ..Default::default()
+        };
+        let projects = vec![project("2", "Q1", Some("1")), project("1", "Work", None)];
+        assert_eq!(
+            projects.format_table(Some("name,id")),
+            "NAME  ID\nWork  1\n  Q1  2"
+        );
+    }
+
     #[test]
     fn test_format_projects_json() {
         let projects = vec![Project {
@@ -560,6 +1187,7 @@ mod tests {
             color: "red".to_string(),
             is_shared: false,
             is_favorite: false,
+            ..Default::default()
         }];
 
         let output = projects.format(&OutputFormat::Json);
@@ -725,6 +1353,7 @@ mod tests {
             name: "Label 1".to_string(),
             color: "blue".to_string(),
             is_favorite: false,
+            is_deleted: false,
         }];
         let output = labels.format(&OutputFormat::Json);
         assert!(output.contains("Label 1"));
@@ -737,6 +1366,7 @@ mod tests {
             name: "Label 1".to_string(),
             color: "blue".to_string(),
             is_favorite: false,
+            is_deleted: false,
         }];
         let output = labels.format(&OutputFormat::Checklist);
         assert!(output.contains("- [ ] Label 1"));
@@ -749,15 +1379,147 @@ mod tests {
             name: "Label 1".to_string(),
             color: "blue".to_string(),
             is_favorite: false,
+            is_deleted: false,
         }];
         let output = labels.format(&OutputFormat::Structured);
         assert!(output.contains("### Label 1"));
     }
 
+    #[test]
+    fn test_format_collaborators() {
+        let collaborators = vec![
+            Collaborator {
+                id: "u1".to_string(),
+                name: "Bea".to_string(),
+                email: "bea@example.com".to_string(),
+                state: Some("active".to_string()),
+            },
+            Collaborator {
+                id: "u2".to_string(),
+                name: "Cal".to_string(),
+                email: "cal@example.com".to_string(),
+                state: Some("invited".to_string()),
+            },
+        ];
+        let output = collaborators.format(&OutputFormat::Checklist);
+        assert!(output.contains("- [ ] Bea <bea@example.com>\n"));
+        assert!(output.contains("- [ ] Cal <cal@example.com> (invited)"));
+        let output = collaborators.format(&OutputFormat::Structured);
+        assert!(output.contains("### Bea"));
+        assert!(output.contains("**State:** invited"));
+    }
+
+    #[test]
+    fn test_format_agenda() {
+        let entry =
+            |id: &str, start: Option<&str>, end: Option<&str>, overlaps: &[&str]| AgendaEntry {
+                id: id.to_string(),
+                content: id.to_uppercase(),
+                project_name: Some("Work".to_string()),
+                start: start.map(str::to_string),
+                end: end.map(str::to_string),
+                duration: end.map(|_| crate::models::Duration::minutes(45)),
+                overlaps_with: overlaps.iter().map(|s| s.to_string()).collect(),
+            };
+        let agenda = Agenda {
+            date: "2026-01-20".to_string(),
+            timed: vec![
+                entry("standup", Some("09:00"), Some("09:45"), &["review"]),
+                entry("review", Some("09:30"), None, &["standup"]),
+            ],
+            all_day: vec![entry("rent", None, None, &[])],
+        };
+        let output = agenda.format(&OutputFormat::Checklist);
+        assert!(output.contains("- [ ] 09:00–09:45 STANDUP (Work) ⚠️ overlaps: REVIEW"));
+        assert!(output.contains("- [ ] 09:30 REVIEW (Work) ⚠️ overlaps: STANDUP"));
+        assert!(output.contains("- [ ] RENT (Work)"));
+        let output = agenda.format(&OutputFormat::Structured);
+        assert!(output.contains("## Agenda for 2026-01-20"));
+        assert!(output.contains("**Scheduled:** 0h45m"));
+        assert!(output.contains("### All day"));
+
+        // Fields pick from the entries, which are also what is validated
+        assert!(Projection::parse("content,start")
+            .unknown(&agenda.records())
+            .is_empty());
+        let json: Value = serde_json::from_str(
+            &agenda
+                .format_output(&OutputFormat::Json, Some("content"))
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "date": "2026-01-20",
+                "timed": [{"content": "STANDUP"}, {"content": "REVIEW"}],
+                "all_day": [{"content": "RENT"}]
+            })
+        );
+    }
+
     #[test]
     fn test_filter_json_invalid_input() {
         let json = serde_json::json!("not an object");
         let filtered = filter_json(json.clone(), "id");
         assert_eq!(filtered, json);
     }
+
+    #[test]
+    fn test_format_grouped_tasks() {
+        let grouped = crate::listing::group(mock_tasks(), crate::listing::GroupBy::Priority);
+
+        let output = grouped.format(&OutputFormat::Checklist);
+        assert_eq!(
+            output,
+            "## p1\n\n- [x] Task 1 (Work)\n\n## p3\n\n- [ ] Task 2 (Personal)"
+        );
+        let output = grouped.format(&OutputFormat::Structured);
+        assert!(output.starts_with("## p1\n\n- [x] Task 1 (Priority: p1, API 4)\n\n## p3"));
+
+        let json: Value = serde_json::from_str(
+            &grouped
+                .format_output(&OutputFormat::Json, Some("id"))
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"p1": [{"id": "1"}], "p3": [{"id": "2"}]})
+        );
+
+        let csv = grouped
+            .format_output(&OutputFormat::Csv, Some("id"))
+            .unwrap();
+        assert_eq!(csv, "group,id\np1,1\np3,2");
+        let ndjson = grouped.format(&OutputFormat::Ndjson);
+        assert!(ndjson.starts_with("{\"group\":\"p1\",\"id\":\"1\""));
+    }
+
+    #[test]
+    fn test_fields_projection() {
+        let mut tasks = mock_tasks();
+        tasks[0].labels = vec!["home".to_string()];
+
+        let output = tasks
+            .format_output(&OutputFormat::Table, Some("title:content,labels[0]"))
+            .unwrap();
+        assert_eq!(output, "TITLE   LABELS[0]\nTask 1  home\nTask 2");
+        let output = tasks
+            .format_output(&OutputFormat::Table, Some("-priority_label,-labels"))
+            .unwrap();
+        assert!(output.starts_with("ID  CONTENT  DUE_DATE  PROJECT_NAME\n"));
+
+        let output = tasks
+            .format_output(&OutputFormat::Csv, Some("id,title:content"))
+            .unwrap();
+        assert_eq!(output, "id,title\n1,Task 1\n2,Task 2");
+        let output = tasks
+            .format_output(
+                &OutputFormat::Ndjson,
+                Some("-description,-section_id,-section_name"),
+            )
+            .unwrap();
+        assert!(output.starts_with("{\"id\":\"1\",\"content\":\"Task 1\",\"project_id\":\"p1\","));
+    }
 }
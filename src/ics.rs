@@ -0,0 +1,453 @@
+//! # iCalendar
+//!
+//! Writes dated tasks as an iCalendar (RFC 5545) feed, so calendar apps can
+//! subscribe to due dates. Tasks become all-day or timed events (or to-dos),
+//! with a UID derived from the task id so refreshed feeds update entries in
+//! place. Simple recurring due strings such as `every monday` or `every 2
+//! weeks` become an `RRULE`; other recurring tasks appear at their next date.
+//! Times in a named time zone are written in UTC, so no `VTIMEZONE` is needed.
+
+use crate::models::{Duration, Priority, TaskOutput};
+use crate::sync::SyncDue;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+/// Longest content line in octets, before folding
+const LINE_LIMIT: usize = 75;
+
+/// Calendar component written for each task
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Component {
+    /// VEVENT, shown by every calendar app
+    #[default]
+    Event,
+    /// VTODO, with status and priority
+    Todo,
+}
+
+/// Renders `tasks` with their due dates as a VCALENDAR; `stamp` is the DTSTAMP
+/// of every entry
+pub fn calendar(
+    tasks: &[(&TaskOutput, &SyncDue)],
+    component: Component,
+    stamp: DateTime<Utc>,
+) -> String {
+    let mut out = String::new();
+    for line in [
+        "BEGIN:VCALENDAR",
+        "VERSION:2.0",
+        "PRODID:-//todorust//Todoist export//EN",
+        "CALSCALE:GREGORIAN",
+        "X-WR-CALNAME:Todoist",
+    ] {
+        push_line(&mut out, line);
+    }
+    for (task, due) in tasks {
+        if let Some(start) = Start::from_due(due) {
+            write_entry(&mut out, task, due, &start, component, stamp);
+        }
+    }
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+/// Start of an entry: a whole day or a point in time
+#[derive(Debug, Clone, PartialEq)]
+enum Start {
+    Date(NaiveDate),
+    /// Fixed to UTC
+    Utc(NaiveDateTime),
+    /// Floating wall-clock time, the same in every time zone
+    Local(NaiveDateTime),
+}
+
+impl Start {
+    fn from_due(due: &SyncDue) -> Option<Self> {
+        let value = due.datetime.as_deref().or(due.date.as_deref())?;
+        if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            return Some(Start::Date(date));
+        }
+        let (value, utc) = match value.strip_suffix('Z') {
+            Some(value) => (value, true),
+            None => (value, false),
+        };
+        let datetime = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f").ok()?;
+        if utc {
+            return Some(Start::Utc(datetime));
+        }
+        // Unknown zones and skipped local times stay floating
+        let utc = due
+            .timezone
+            .as_deref()
+            .and_then(|tz| tz.parse::<chrono_tz::Tz>().ok())
+            .and_then(|tz| tz.from_local_datetime(&datetime).earliest())
+            .map(|local| local.naive_utc());
+        Some(match utc {
+            Some(utc) => Start::Utc(utc),
+            None => Start::Local(datetime),
+        })
+    }
+
+    /// `DTSTART`-style property, e.g. `DTSTART;VALUE=DATE:20260120`
+    fn property(&self, name: &str) -> String {
+        match self {
+            Start::Date(date) => format!("{};VALUE=DATE:{}", name, date.format("%Y%m%d")),
+            Start::Utc(datetime) => format!("{}:{}Z", name, datetime.format("%Y%m%dT%H%M%S")),
+            Start::Local(datetime) => format!("{}:{}", name, datetime.format("%Y%m%dT%H%M%S")),
+        }
+    }
+}
+
+fn write_entry(
+    out: &mut String,
+    task: &TaskOutput,
+    due: &SyncDue,
+    start: &Start,
+    component: Component,
+    stamp: DateTime<Utc>,
+) {
+    let name = match component {
+        Component::Event => "VEVENT",
+        Component::Todo => "VTODO",
+    };
+    push_line(out, &format!("BEGIN:{}", name));
+    push_line(out, &format!("UID:task-{}@todorust", task.id));
+    push_line(out, &format!("DTSTAMP:{}", stamp.format("%Y%m%dT%H%M%SZ")));
+    push_line(out, &start.property("DTSTART"));
+    match component {
+        Component::Event => {
+            if let Some(end) = event_end(start, task.duration.as_ref()) {
+                push_line(out, &end);
+            }
+        }
+        Component::Todo => push_line(out, &start.property("DUE")),
+    }
+    push_line(out, &format!("SUMMARY:{}", escape(&task.content)));
+    if let Some(description) = task.description.as_deref().filter(|d| !d.is_empty()) {
+        push_line(out, &format!("DESCRIPTION:{}", escape(description)));
+    }
+    if !task.labels.is_empty() {
+        let labels: Vec<String> = task.labels.iter().map(|l| escape(l)).collect();
+        push_line(out, &format!("CATEGORIES:{}", labels.join(",")));
+    }
+    push_line(
+        out,
+        &format!("URL:https://app.todoist.com/app/task/{}", task.id),
+    );
+    if let Some(rule) = due
+        .string
+        .as_deref()
+        .filter(|_| due.is_recurring)
+        .and_then(rrule)
+    {
+        push_line(out, &format!("RRULE:{}", rule));
+    }
+    if component == Component::Todo {
+        if let Some(priority) = todo_priority(task.priority) {
+            push_line(out, &format!("PRIORITY:{}", priority));
+        }
+        let status = if task.is_completed {
+            "COMPLETED"
+        } else {
+            "NEEDS-ACTION"
+        };
+        push_line(out, &format!("STATUS:{}", status));
+    }
+    push_line(out, &format!("END:{}", name));
+}
+
+/// End of an event: the next day for all-day tasks, the task duration for timed ones
+fn event_end(start: &Start, duration: Option<&Duration>) -> Option<String> {
+    match start {
+        Start::Date(date) => {
+            let days = duration
+                .filter(|d| d.unit == "day")
+                .map_or(1, |d| d.amount.max(1));
+            let end = *date + chrono::Duration::days(days as i64);
+            Some(format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")))
+        }
+        _ => duration.map(|d| match d.unit.as_str() {
+            "day" => format!("DURATION:P{}D", d.amount),
+            _ => format!("DURATION:PT{}M", d.amount),
+        }),
+    }
+}
+
+/// RFC 5545 priority: 1 is highest, 9 lowest; p4 is left undefined
+fn todo_priority(priority: Priority) -> Option<u8> {
+    match priority.display_level() {
+        1 => Some(1),
+        2 => Some(3),
+        3 => Some(5),
+        _ => None,
+    }
+}
+
+/// Translates a simple recurring due string into an RRULE, e.g.
+/// `every other monday at 9am` -> `FREQ=WEEKLY;INTERVAL=2;BYDAY=MO`.
+///
+/// Returns `None` for anything more involved, such as `every 3rd friday`,
+/// `every! day` or end dates.
+pub fn rrule(due_string: &str) -> Option<String> {
+    let text = due_string.trim().to_lowercase();
+    // The time of day is part of DTSTART
+    let text = text.split(" at ").next().unwrap_or_default().trim();
+    let rest = match text {
+        "daily" => "day",
+        "weekly" => "week",
+        "monthly" => "month",
+        "yearly" | "annually" => "year",
+        _ => text.strip_prefix("every ")?.trim(),
+    };
+
+    let (interval, unit) = match rest.split_once(' ') {
+        Some(("other", unit)) => (2, unit),
+        Some((n, unit)) if n.parse::<u32>().is_ok_and(|n| n > 0) => (n.parse().ok()?, unit),
+        _ => (1, rest),
+    };
+    let freq = match unit.trim_end_matches('s') {
+        "day" => Some("DAILY"),
+        "week" => Some("WEEKLY"),
+        "month" => Some("MONTHLY"),
+        "year" => Some("YEARLY"),
+        _ => None,
+    };
+    let interval = if interval > 1 {
+        format!(";INTERVAL={}", interval)
+    } else {
+        String::new()
+    };
+    if let Some(freq) = freq {
+        return Some(format!("FREQ={}{}", freq, interval));
+    }
+
+    let days = match unit {
+        "weekday" | "workday" => vec!["MO", "TU", "WE", "TH", "FR"],
+        _ => unit
+            .replace(" and ", ",")
+            .split(',')
+            .map(|d| weekday(d.trim()))
+            .collect::<Option<Vec<_>>>()?,
+    };
+    Some(format!("FREQ=WEEKLY{};BYDAY={}", interval, days.join(",")))
+}
+
+fn weekday(name: &str) -> Option<&'static str> {
+    let name = name.trim_end_matches('s');
+    let day = match name.get(..3)? {
+        "mon" => "MO",
+        "tue" => "TU",
+        "wed" => "WE",
+        "thu" => "TH",
+        "fri" => "FR",
+        "sat" => "SA",
+        "sun" => "SU",
+        _ => return None,
+    };
+    let full = [
+        "monday",
+        "tuesday",
+        "wednesday",
+        "thursday",
+        "friday",
+        "saturday",
+        "sunday",
+    ];
+    // `mon`, `tues`, `thurs` and full names, not `monkey`
+    let known = name.len() <= 5 || full.contains(&name);
+    known.then_some(day)
+}
+
+/// Escapes a TEXT value
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Appends a content line, folded at 75 octets without splitting characters
+fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > LINE_LIMIT {
+            out.push_str("\r\n ");
+            // The leading space counts towards the folded line
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: &str, content: &str) -> TaskOutput {
+        TaskOutput {
+            id: id.to_string(),
+            content: content.to_string(),
+            description: None,
+            project_id: None,
+            project_name: None,
+            section_id: None,
+            section_name: None,
+            due_date: None,
+            due_datetime: None,
+            due_string: None,
+            is_recurring: false,
+            is_completed: false,
+            completed_at: None,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            order: 1,
+            priority: Priority::LOW,
+            priority_label: "p4".to_string(),
+            labels: vec![],
+            responsible_uid: None,
+            assignee: None,
+            duration: None,
+        }
+    }
+
+    fn due(date: &str, datetime: Option<&str>, timezone: Option<&str>) -> SyncDue {
+        SyncDue {
+            date: Some(date.to_string()),
+            is_recurring: false,
+            datetime: datetime.map(str::to_string),
+            timezone: timezone.map(str::to_string),
+            string: None,
+            lang: None,
+        }
+    }
+
+    fn stamp() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-01-19T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_calendar_all_day_event() {
+        let mut task = task("123", "Pay rent, water; gas");
+        task.labels = vec!["home".to_string(), "bills".to_string()];
+        let mut due = due("2026-01-20", None, None);
+        due.is_recurring = true;
+        due.string = Some("every month".to_string());
+
+        let ics = calendar(&[(&task, &due)], Component::Event, stamp());
+        assert_eq!(
+            ics,
+            "BEGIN:VCALENDAR\r\n\
+             VERSION:2.0\r\n\
+             PRODID:-//todorust//Todoist export//EN\r\n\
+             CALSCALE:GREGORIAN\r\n\
+             X-WR-CALNAME:Todoist\r\n\
+             BEGIN:VEVENT\r\n\
+             UID:task-123@todorust\r\n\
+             DTSTAMP:20260119T120000Z\r\n\
+             DTSTART;VALUE=DATE:20260120\r\n\
+             DTEND;VALUE=DATE:20260121\r\n\
+             SUMMARY:Pay rent\\, water\\; gas\r\n\
+             CATEGORIES:home,bills\r\n\
+             URL:https://app.todoist.com/app/task/123\r\n\
+             RRULE:FREQ=MONTHLY\r\n\
+             END:VEVENT\r\n\
+             END:VCALENDAR\r\n"
+        );
+    }
+
+    #[test]
+    fn test_calendar_timed_todo() {
+        let mut task = task("7", "Standup");
+        task.priority = Priority::URGENT;
+        task.duration = Some(Duration::minutes(15));
+        let fixed = due(
+            "2026-01-20",
+            Some("2026-01-20T08:00:00Z"),
+            Some("Europe/Berlin"),
+        );
+        let local = due(
+            "2026-01-20",
+            Some("2026-01-20T09:00:00"),
+            Some("Europe/Berlin"),
+        );
+        let floating = due("2026-01-20T09:00:00", None, None);
+
+        let ics = calendar(&[(&task, &fixed)], Component::Todo, stamp());
+        assert!(ics.contains("BEGIN:VTODO\r\n"));
+        assert!(ics.contains("DTSTART:20260120T080000Z\r\nDUE:20260120T080000Z\r\n"));
+        assert!(ics.contains("PRIORITY:1\r\nSTATUS:NEEDS-ACTION\r\n"));
+
+        let ics = calendar(
+            &[(&task, &local), (&task, &floating)],
+            Component::Event,
+            stamp(),
+        );
+        // 09:00 in Berlin is 08:00 UTC in winter
+        assert!(ics.contains("DTSTART:20260120T080000Z\r\nDURATION:PT15M\r\n"));
+        assert!(ics.contains("DTSTART:20260120T090000\r\n"));
+        assert!(!ics.contains("TZID"));
+
+        let unknown = due(
+            "2026-01-20",
+            Some("2026-01-20T09:00:00"),
+            Some("Mars/Olympus"),
+        );
+        let ics = calendar(&[(&task, &unknown)], Component::Event, stamp());
+        assert!(ics.contains("DTSTART:20260120T090000\r\n"));
+    }
+
+    #[test]
+    fn test_calendar_skips_undated() {
+        let task = task("1", "Someday");
+        let due = SyncDue {
+            date: None,
+            ..due("", None, None)
+        };
+        let ics = calendar(&[(&task, &due)], Component::Event, stamp());
+        assert!(!ics.contains("BEGIN:VEVENT"));
+    }
+
+    #[test]
+    fn test_rrule() {
+        assert_eq!(rrule("every day").as_deref(), Some("FREQ=DAILY"));
+        assert_eq!(rrule("Daily at 9am").as_deref(), Some("FREQ=DAILY"));
+        assert_eq!(
+            rrule("every 3 weeks").as_deref(),
+            Some("FREQ=WEEKLY;INTERVAL=3")
+        );
+        assert_eq!(
+            rrule("every other year").as_deref(),
+            Some("FREQ=YEARLY;INTERVAL=2")
+        );
+        assert_eq!(
+            rrule("every mon, wed and friday at 10:00").as_deref(),
+            Some("FREQ=WEEKLY;BYDAY=MO,WE,FR")
+        );
+        assert_eq!(
+            rrule("every other tuesday").as_deref(),
+            Some("FREQ=WEEKLY;INTERVAL=2;BYDAY=TU")
+        );
+        assert_eq!(
+            rrule("every weekday").as_deref(),
+            Some("FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR")
+        );
+        assert_eq!(rrule("every 3rd friday"), None);
+        assert_eq!(rrule("every! day"), None);
+        assert_eq!(rrule("every day until feb 1"), None);
+        assert_eq!(rrule("tomorrow"), None);
+    }
+
+    #[test]
+    fn test_push_line_folds_long_lines() {
+        let mut out = String::new();
+        push_line(&mut out, &format!("SUMMARY:{}", "é".repeat(60)));
+        let lines: Vec<&str> = out.split("\r\n").collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines.iter().all(|l| l.len() <= LINE_LIMIT));
+        assert!(lines[1].starts_with(' '));
+        assert_eq!(lines[2], "");
+    }
+}
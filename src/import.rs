@@ -0,0 +1,517 @@
+//! # Import
+//!
+//! Reads tasks exported by other task managers (todo.txt, Taskwarrior's
+//! `task export` and CSV) and turns them into `item_add` commands. Projects
+//! are matched to existing ones by name, or created; contexts and tags become
+//! labels, which Todoist creates on first use. Completed tasks are added and
+//! then completed, so the history comes along.
+//!
+//! Recurrences are not carried over: Taskwarrior templates are skipped and
+//! their pending instances imported as plain tasks.
+
+use crate::backup::add;
+use crate::error::{Result, TodoError};
+use crate::models::Priority;
+use crate::sync::{
+    Command, CommandKind, IdArgs, ItemAddArgs, NoteAddArgs, ProjectAddArgs, SyncProject,
+};
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone, Timelike, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Format of the file to import
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Source {
+    /// todo.txt lines with (A) priorities, +projects, @contexts and due:
+    Todotxt,
+    /// JSON from `task export`
+    Taskwarrior,
+    /// CSV with a header row, including Todoist's own template
+    Csv,
+}
+
+/// A task read from another tool
+#[derive(Debug, Default, PartialEq)]
+pub struct Entry {
+    pub content: String,
+    pub description: Option<String>,
+    pub project: Option<String>,
+    pub labels: Vec<String>,
+    pub priority: Option<Priority>,
+    /// Date (`2026-01-05`) or local date and time (`2026-01-05 17:00`)
+    pub due: Option<String>,
+    pub completed: bool,
+    pub comments: Vec<String>,
+}
+
+pub fn parse(source: Source, text: &str) -> Result<Vec<Entry>> {
+    match source {
+        Source::Todotxt => Ok(parse_todotxt(text)),
+        Source::Taskwarrior => parse_taskwarrior(text),
+        Source::Csv => parse_csv(text),
+    }
+}
+
+fn is_date(token: &str) -> bool {
+    NaiveDate::parse_from_str(token, "%Y-%m-%d").is_ok()
+}
+
+/// Priority letter: A-C are p1-p3, anything later is the default p4
+fn letter_priority(letter: &str) -> Option<Priority> {
+    match letter {
+        "A" => Some(Priority::URGENT),
+        "B" => Some(Priority::HIGH),
+        "C" => Some(Priority::MEDIUM),
+        _ => None,
+    }
+}
+
+fn parse_todotxt(text: &str) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    for line in text.lines() {
+        let mut tokens = line.split_whitespace().peekable();
+        let mut entry = Entry::default();
+        if tokens.peek() == Some(&"x") {
+            entry.completed = true;
+            tokens.next();
+        }
+        if let Some(letter) = tokens
+            .peek()
+            .and_then(|t| t.strip_prefix('(')?.strip_suffix(')'))
+            .filter(|l| l.len() == 1 && l.chars().all(|c| c.is_ascii_uppercase()))
+        {
+            entry.priority = letter_priority(letter);
+            tokens.next();
+        }
+        // Completion and creation dates
+        while tokens.peek().is_some_and(|t| is_date(t)) {
+            tokens.next();
+        }
+
+        let mut words = Vec::new();
+        for token in tokens {
+            if let Some(project) = token.strip_prefix('+').filter(|p| !p.is_empty()) {
+                entry.project.get_or_insert_with(|| project.to_string());
+            } else if let Some(context) = token.strip_prefix('@').filter(|c| !c.is_empty()) {
+                entry.labels.push(context.to_string());
+            } else if let Some(due) = token.strip_prefix("due:").filter(|d| is_date(d)) {
+                entry.due = Some(due.to_string());
+            } else if let Some(letter) = token.strip_prefix("pri:") {
+                // Kept by some clients when a task is completed
+                entry.priority = letter_priority(letter);
+            } else {
+                words.push(token);
+            }
+        }
+        entry.content = words.join(" ");
+        if !entry.content.is_empty() {
+            entries.push(entry);
+        }
+    }
+    entries
+}
+
+#[derive(serde::Deserialize)]
+struct WarriorTask {
+    description: String,
+    #[serde(default)]
+    status: String,
+    project: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    priority: Option<String>,
+    due: Option<String>,
+    #[serde(default)]
+    annotations: Vec<WarriorAnnotation>,
+}
+
+#[derive(serde::Deserialize)]
+struct WarriorAnnotation {
+    description: String,
+}
+
+/// Taskwarrior timestamps are UTC; dates without a time are local midnight
+fn warrior_date(value: &str) -> Option<String> {
+    let utc = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%SZ").ok()?;
+    let local = Utc.from_utc_datetime(&utc).with_timezone(&Local);
+    Some(if local.hour() == 0 && local.minute() == 0 {
+        local.format("%Y-%m-%d").to_string()
+    } else {
+        local.format("%Y-%m-%d %H:%M").to_string()
+    })
+}
+
+fn parse_taskwarrior(text: &str) -> Result<Vec<Entry>> {
+    let invalid = |e: serde_json::Error| TodoError::InvalidInput(format!("Invalid export: {}", e));
+    // `task export` writes an array; older versions write one task per line
+    let tasks: Vec<WarriorTask> = if text.trim_start().starts_with('[') {
+        serde_json::from_str(text).map_err(invalid)?
+    } else {
+        text.lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| serde_json::from_str(l.trim().trim_end_matches(',')))
+            .collect::<std::result::Result<_, _>>()
+            .map_err(invalid)?
+    };
+
+    Ok(tasks
+        .into_iter()
+        .filter(|t| !matches!(t.status.as_str(), "deleted" | "recurring"))
+        .map(|task| Entry {
+            content: task.description,
+            description: None,
+            project: task.project.filter(|p| !p.is_empty()),
+            labels: task.tags,
+            priority: match task.priority.as_deref() {
+                Some("H") => Some(Priority::URGENT),
+                Some("M") => Some(Priority::HIGH),
+                Some("L") => Some(Priority::MEDIUM),
+                _ => None,
+            },
+            due: task.due.as_deref().and_then(warrior_date),
+            completed: task.status == "completed",
+            comments: task
+                .annotations
+                .into_iter()
+                .map(|a| a.description)
+                .collect(),
+        })
+        .filter(|e| !e.content.is_empty())
+        .collect())
+}
+
+/// Splits CSV text into records, honoring quoted fields with commas,
+/// doubled quotes and line breaks
+fn csv_records(text: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => record.push(std::mem::take(&mut field)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records.retain(|r| r.iter().any(|f| !f.trim().is_empty()));
+    records
+}
+
+/// Priority as `p1`, `1` (p1 highest, as in Todoist's CSV) or a word
+fn csv_priority(value: &str) -> Option<Priority> {
+    let value = value.trim().to_lowercase();
+    match value.as_str() {
+        "urgent" | "highest" => Some(Priority::URGENT),
+        "high" => Some(Priority::HIGH),
+        "medium" => Some(Priority::MEDIUM),
+        "low" | "none" | "" => None,
+        _ => value
+            .trim_start_matches('p')
+            .parse()
+            .ok()
+            .and_then(Priority::from_display)
+            .filter(|p| *p != Priority::LOW),
+    }
+}
+
+fn parse_csv(text: &str) -> Result<Vec<Entry>> {
+    let mut records = csv_records(text).into_iter();
+    let Some(header) = records.next() else {
+        return Ok(Vec::new());
+    };
+    let header: Vec<String> = header
+        .iter()
+        .map(|h| h.trim().to_lowercase().replace([' ', '-'], "_"))
+        .collect();
+    let column = |names: &[&str]| header.iter().position(|h| names.contains(&h.as_str()));
+    let content = column(&["content", "task", "title", "name"]).ok_or_else(|| {
+        TodoError::InvalidInput(
+            "CSV needs a content column (content, task, title or name)".to_string(),
+        )
+    })?;
+    let kind = column(&["type"]);
+    let description = column(&["description", "notes"]);
+    let project = column(&["project", "list"]);
+    let labels = column(&["labels", "tags", "contexts", "context"]);
+    let priority = column(&["priority"]);
+    let due = column(&["due", "due_date", "date"]);
+    let completed = column(&["completed", "done", "status"]);
+
+    let mut entries = Vec::new();
+    for record in records {
+        let field = |index: Option<usize>| {
+            index
+                .and_then(|i| record.get(i))
+                .map(|v| v.trim())
+                .filter(|v| !v.is_empty())
+        };
+        // Todoist's template also lists sections and notes
+        if field(kind).is_some_and(|k| !k.eq_ignore_ascii_case("task")) {
+            continue;
+        }
+        let Some(content) = field(Some(content)) else {
+            continue;
+        };
+        entries.push(Entry {
+            content: content.to_string(),
+            description: field(description).map(str::to_string),
+            project: field(project).map(str::to_string),
+            labels: field(labels)
+                .map(|l| {
+                    l.split([',', ';'])
+                        .map(|l| l.trim().trim_start_matches('@').to_string())
+                        .filter(|l| !l.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            priority: field(priority).and_then(csv_priority),
+            due: field(due).map(str::to_string),
+            completed: field(completed).is_some_and(|v| {
+                matches!(
+                    v.to_lowercase().as_str(),
+                    "x" | "1" | "y" | "yes" | "true" | "done" | "completed"
+                )
+            }),
+            comments: Vec::new(),
+        });
+    }
+    Ok(entries)
+}
+
+/// Number of resources an import creates
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct Summary {
+    pub projects: usize,
+    pub tasks: usize,
+    pub completed: usize,
+    pub comments: usize,
+    /// Existing projects the tasks are added to
+    pub existing: Vec<String>,
+}
+
+/// Commands adding imported tasks
+pub struct Plan {
+    pub commands: Vec<Command>,
+    pub summary: Summary,
+    /// Temp id of the new root project
+    pub root: Option<String>,
+}
+
+/// Builds the commands adding `entries`. Projects are matched by name
+/// (ignoring case) to `projects`, unless everything goes below a new `into`
+/// project; tasks without a project go to the Inbox, or to `into`.
+pub fn plan(entries: &[Entry], projects: &[SyncProject], into: Option<&str>) -> Plan {
+    let mut commands = Vec::new();
+    let mut summary = Summary::default();
+    let root = into.map(|name| {
+        summary.projects += 1;
+        add(
+            &mut commands,
+            CommandKind::ProjectAdd(ProjectAddArgs::new(name.to_string())),
+        )
+    });
+
+    // Lowercase name -> project id or temp id
+    let mut project_ids: HashMap<String, String> = HashMap::new();
+    for entry in entries {
+        let Some(name) = entry.project.as_deref() else {
+            continue;
+        };
+        let key = name.to_lowercase();
+        if project_ids.contains_key(&key) {
+            continue;
+        }
+        let existing = projects
+            .iter()
+            .find(|p| !p.is_archived && p.name.to_lowercase() == key)
+            .filter(|_| root.is_none());
+        let id = match existing {
+            Some(project) => {
+                summary.existing.push(project.name.clone());
+                project.id.clone()
+            }
+            None => {
+                summary.projects += 1;
+                let args = ProjectAddArgs::new(name.to_string()).parent_id(root.clone());
+                add(&mut commands, CommandKind::ProjectAdd(args))
+            }
+        };
+        project_ids.insert(key, id);
+    }
+
+    for entry in entries {
+        let project_id = match entry.project.as_deref() {
+            Some(name) => project_ids.get(&name.to_lowercase()).cloned(),
+            None => root.clone(),
+        };
+        let args = ItemAddArgs::new(entry.content.clone())
+            .description(entry.description.clone())
+            .project_id(project_id)
+            .due_string(entry.due.clone())
+            .priority(entry.priority.map(Priority::api_value))
+            .labels((!entry.labels.is_empty()).then(|| entry.labels.clone()));
+        let temp_id = add(&mut commands, CommandKind::ItemAdd(args));
+        summary.tasks += 1;
+        for comment in &entry.comments {
+            let args = NoteAddArgs {
+                item_id: temp_id.clone(),
+                content: comment.clone(),
+            };
+            add(&mut commands, CommandKind::NoteAdd(args));
+            summary.comments += 1;
+        }
+        if entry.completed {
+            commands.push(Command::new(CommandKind::ItemComplete(IdArgs::new(
+                &temp_id,
+            ))));
+            summary.completed += 1;
+        }
+    }
+
+    Plan {
+        commands,
+        summary,
+        root,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_todotxt() {
+        let text = "(A) 2026-01-01 Call mom +Family @phone due:2026-01-05 note:x\n\
+                    x 2026-01-03 2026-01-01 Pay rent +Home pri:B\n\
+                    \n\
+                    (D) Someday maybe";
+        let entries = parse(Source::Todotxt, text).unwrap();
+        assert_eq!(
+            entries[0],
+            Entry {
+                content: "Call mom note:x".to_string(),
+                project: Some("Family".to_string()),
+                labels: vec!["phone".to_string()],
+                priority: Some(Priority::URGENT),
+                due: Some("2026-01-05".to_string()),
+                ..Default::default()
+            }
+        );
+        assert!(entries[1].completed);
+        assert_eq!(entries[1].content, "Pay rent");
+        assert_eq!(entries[1].priority, Some(Priority::HIGH));
+        assert_eq!(entries[2].priority, None);
+        assert_eq!(entries.len(), 3);
+    }
+
+    #[test]
+    fn test_parse_taskwarrior() {
+        let midnight = Local
+            .with_ymd_and_hms(2026, 1, 5, 0, 0, 0)
+            .unwrap()
+            .with_timezone(&Utc)
+            .format("%Y%m%dT%H%M%SZ")
+            .to_string();
+        let export = json!([
+            {"description": "Fix bike", "status": "pending", "project": "Home",
+             "tags": ["garage"], "priority": "H", "due": midnight,
+             "annotations": [{"entry": "20260101T100000Z", "description": "Buy a chain"}]},
+            {"description": "Old", "status": "deleted"},
+            {"description": "Weekly review", "status": "recurring", "recur": "weekly"},
+            {"description": "Done", "status": "completed", "priority": "L"}
+        ]);
+        let entries = parse(Source::Taskwarrior, &export.to_string()).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].project.as_deref(), Some("Home"));
+        assert_eq!(entries[0].due.as_deref(), Some("2026-01-05"));
+        assert_eq!(entries[0].comments, vec!["Buy a chain"]);
+        assert_eq!(entries[1].priority, Some(Priority::MEDIUM));
+        assert!(entries[1].completed);
+
+        let lines = "{\"description\": \"A\"},\n{\"description\": \"B\"}\n";
+        assert_eq!(parse(Source::Taskwarrior, lines).unwrap().len(), 2);
+        assert!(parse(Source::Taskwarrior, "[{]").is_err());
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let text = "\u{feff}TYPE,CONTENT,PRIORITY,DATE,Labels,Done\r\n\
+                    task,\"Plan, then \"\"ship\"\"\",1,2026-02-01,\"@work; deep\",\r\n\
+                    section,Later,,,,\n\
+                    task,\"Two\nlines\",p4,,,yes\n";
+        let entries = parse(Source::Csv, text).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].content, "Plan, then \"ship\"");
+        assert_eq!(entries[0].priority, Some(Priority::URGENT));
+        assert_eq!(entries[0].due.as_deref(), Some("2026-02-01"));
+        assert_eq!(entries[0].labels, vec!["work", "deep"]);
+        assert_eq!(entries[1].content, "Two\nlines");
+        assert_eq!(entries[1].priority, None);
+        assert!(entries[1].completed);
+
+        assert!(parse(Source::Csv, "foo,bar\n1,2\n").is_err());
+    }
+
+    #[test]
+    fn test_plan_matches_and_creates_projects() {
+        let projects: Vec<SyncProject> = serde_json::from_value(json!([
+            {"id": "p1", "name": "Home"},
+            {"id": "p2", "name": "Work", "is_archived": true}
+        ]))
+        .unwrap();
+        let entries = parse(
+            Source::Todotxt,
+            "Fix sink +home\nx Report +Work\nCall +Home\nMilk",
+        )
+        .unwrap();
+
+        let plan = plan(&entries, &projects, None);
+        let kinds: Vec<_> = plan
+            .commands
+            .iter()
+            .map(|c| c.kind.command_type())
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                "project_add",
+                "item_add",
+                "item_add",
+                "item_complete",
+                "item_add",
+                "item_add"
+            ]
+        );
+        let project_of = |i: usize| {
+            serde_json::to_value(&plan.commands[i].kind).unwrap()["args"]["project_id"].clone()
+        };
+        assert_eq!(project_of(1), json!("p1"));
+        assert_eq!(project_of(2), json!(plan.commands[0].temp_id));
+        assert_eq!(project_of(5), json!(null));
+        assert_eq!(plan.summary.existing, vec!["Home"]);
+        assert_eq!(plan.summary.projects, 1);
+        assert_eq!(plan.summary.completed, 1);
+
+        // Below a new root nothing is matched
+        let plan = super::plan(&entries, &projects, Some("Imported"));
+        assert_eq!(plan.summary.projects, 3);
+        assert!(plan.summary.existing.is_empty());
+        assert_eq!(plan.root, plan.commands[0].temp_id);
+    }
+}
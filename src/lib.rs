@@ -28,14 +28,28 @@
 //! ## Modules
 //!
 //! - [`sync`]: Todoist Sync API client for efficient batch operations
+//! - [`agenda`]: Day view of timed tasks for time blocking
 //! - [`api`]: Legacy REST API client (deprecated, use [`sync`] instead)
 //! - [`formatter`]: Output formatting utilities
+//! - [`quick_add`]: Parser for Todoist-style quick-add text
+//! - [`resolver`]: Name-based lookup of projects, sections and labels
 
+pub mod agenda;
+pub mod backup;
 pub mod config;
 pub mod error;
 pub mod formatter;
+pub mod ics;
+pub mod import;
+pub mod listing;
+pub mod markdown;
 pub mod models;
+pub mod projection;
+pub mod quick_add;
+pub mod resolver;
 pub mod sync;
+pub mod table;
+pub mod template;
 
 pub use formatter::{Formattable, OutputFormat};
 pub use models::Project;
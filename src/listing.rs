@@ -0,0 +1,293 @@
+//! # Sorting and Grouping
+//!
+//! Orders task listings by several keys (`--sort due,priority,-created`) and
+//! splits them into groups (`--group-by project`). Every output format renders
+//! the groups: headings in the text formats and tables, an object of task
+//! arrays in JSON and a `group` column in CSV, TSV and NDJSON.
+
+use crate::models::TaskOutput;
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+/// Task field a listing can be sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortField {
+    /// Due date, earliest first; tasks without one come last
+    Due,
+    /// Most urgent (p1) first
+    Priority,
+    Created,
+    /// Project name; tasks without a project come last
+    Project,
+    /// Section name; tasks without a section come last
+    Section,
+    /// Position within the project, as in the Todoist app
+    Order,
+    Content,
+}
+
+/// A sort field, optionally reversed with a leading `-`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortKey {
+    pub field: SortField,
+    pub descending: bool,
+}
+
+impl FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (descending, name) = match s.strip_prefix('-') {
+            Some(name) => (true, name),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let field = match name.to_lowercase().as_str() {
+            "due" | "due_date" => SortField::Due,
+            "priority" => SortField::Priority,
+            "created" | "created_at" => SortField::Created,
+            "project" | "project_name" => SortField::Project,
+            "section" | "section_name" => SortField::Section,
+            "order" => SortField::Order,
+            "content" => SortField::Content,
+            _ => {
+                return Err(format!(
+                    "unknown sort key '{}'; expected due, priority, created, project, section, \
+                     order or content, prefixed with - to reverse",
+                    name
+                ))
+            }
+        };
+        Ok(Self { field, descending })
+    }
+}
+
+/// Compares present values, keeping missing ones last in either direction
+fn missing_last<T: Ord>(a: Option<T>, b: Option<T>, descending: bool) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) if descending => b.cmp(&a),
+        (Some(a), Some(b)) => a.cmp(&b),
+        (a, b) => b.is_none().cmp(&a.is_none()).reverse(),
+    }
+}
+
+impl SortKey {
+    fn compare(&self, a: &TaskOutput, b: &TaskOutput) -> Ordering {
+        let name = |value: &Option<String>| value.as_deref().map(str::to_lowercase);
+        let ordering = match self.field {
+            SortField::Due => {
+                let due = |t: &TaskOutput| t.due_date.clone().or(t.due_datetime.clone());
+                return missing_last(due(a), due(b), self.descending);
+            }
+            SortField::Project => {
+                return missing_last(
+                    name(&a.project_name),
+                    name(&b.project_name),
+                    self.descending,
+                )
+            }
+            SortField::Section => {
+                return missing_last(
+                    name(&a.section_name),
+                    name(&b.section_name),
+                    self.descending,
+                )
+            }
+            SortField::Priority => b.priority.cmp(&a.priority),
+            SortField::Created => a.created_at.cmp(&b.created_at),
+            SortField::Order => a.order.cmp(&b.order),
+            SortField::Content => a.content.to_lowercase().cmp(&b.content.to_lowercase()),
+        };
+        if self.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
+
+/// Sorts by the first key, then the next one for ties; equal tasks keep
+/// their order
+pub fn sort(tasks: &mut [TaskOutput], keys: &[SortKey]) {
+    tasks.sort_by(|a, b| {
+        keys.iter()
+            .map(|key| key.compare(a, b))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    });
+}
+
+/// What a listing is grouped by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum GroupBy {
+    Project,
+    /// Project and section, e.g. "Work / Doing"
+    Section,
+    /// Tasks with several labels appear in each of their groups
+    Label,
+    /// Due date
+    Due,
+    Priority,
+}
+
+/// Tasks sharing a group key
+#[derive(Debug, Clone)]
+pub struct Group {
+    pub name: String,
+    pub tasks: Vec<TaskOutput>,
+}
+
+/// A grouped listing: the tasks in listing order and the groups they form
+#[derive(Debug, Clone)]
+pub struct Grouped {
+    pub tasks: Vec<TaskOutput>,
+    pub groups: Vec<Group>,
+}
+
+/// Names of the groups `task` belongs to; `None` for tasks without the key
+fn group_names(task: &TaskOutput, by: GroupBy) -> Vec<Option<String>> {
+    match by {
+        GroupBy::Project => vec![task.project_name.clone()],
+        GroupBy::Section => {
+            let project = task.project_name.as_deref().unwrap_or("No project");
+            vec![Some(match &task.section_name {
+                Some(section) => format!("{} / {}", project, section),
+                None => project.to_string(),
+            })]
+        }
+        GroupBy::Label if task.labels.is_empty() => vec![None],
+        GroupBy::Label => task.labels.iter().cloned().map(Some).collect(),
+        GroupBy::Due => vec![task
+            .due_date
+            .as_deref()
+            .map(|d| d.get(..10).unwrap_or(d).to_string())],
+        GroupBy::Priority => vec![Some(task.priority_label.clone())],
+    }
+}
+
+/// Splits `tasks` into groups ordered by name (dates chronologically,
+/// priorities from p1), with tasks lacking the key in a last group. Tasks
+/// keep their order within each group.
+pub fn group(tasks: Vec<TaskOutput>, by: GroupBy) -> Grouped {
+    let missing = match by {
+        GroupBy::Project | GroupBy::Section => "No project",
+        GroupBy::Label => "No label",
+        GroupBy::Due => "No date",
+        GroupBy::Priority => "No priority",
+    };
+    let mut keyed: Vec<(Option<String>, Group)> = Vec::new();
+    for task in &tasks {
+        for name in group_names(task, by) {
+            match keyed.iter_mut().find(|(key, _)| *key == name) {
+                Some((_, group)) => group.tasks.push(task.clone()),
+                None => keyed.push((
+                    name.clone(),
+                    Group {
+                        name: name.unwrap_or_else(|| missing.to_string()),
+                        tasks: vec![task.clone()],
+                    },
+                )),
+            }
+        }
+    }
+    keyed.sort_by(|(a, _), (b, _)| {
+        missing_last(
+            a.as_deref().map(str::to_lowercase),
+            b.as_deref().map(str::to_lowercase),
+            false,
+        )
+    });
+    Grouped {
+        tasks,
+        groups: keyed.into_iter().map(|(_, group)| group).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Task;
+    use serde_json::json;
+
+    fn task(id: &str, project: Option<&str>, due: Option<&str>, priority: u8) -> TaskOutput {
+        let task: Task = serde_json::from_value(json!({
+            "id": id,
+            "content": format!("Task {}", id),
+            "project_id": project,
+            "due": due.map(|d| json!({"date": d})),
+            "is_completed": false,
+            "created_at": format!("2026-01-0{}T00:00:00Z", id),
+            "order": 1,
+            "priority": priority
+        }))
+        .unwrap();
+        TaskOutput::from_task(task, project.map(str::to_string))
+    }
+
+    fn ids(tasks: &[TaskOutput]) -> Vec<&str> {
+        tasks.iter().map(|t| t.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_parse_sort_key() {
+        assert_eq!(
+            "-created".parse::<SortKey>().unwrap(),
+            SortKey {
+                field: SortField::Created,
+                descending: true
+            }
+        );
+        assert_eq!("Due".parse::<SortKey>().unwrap().field, SortField::Due);
+        assert!("size"
+            .parse::<SortKey>()
+            .unwrap_err()
+            .contains("unknown sort key 'size'"));
+    }
+
+    #[test]
+    fn test_sort_by_several_keys() {
+        let mut tasks = vec![
+            task("1", Some("Work"), None, 4),
+            task("2", Some("Home"), Some("2026-02-01"), 1),
+            task("3", Some("Work"), Some("2026-01-15"), 2),
+            task("4", None, Some("2026-01-15"), 4),
+        ];
+        let keys: Vec<SortKey> = ["due", "priority"]
+            .iter()
+            .map(|k| k.parse().unwrap())
+            .collect();
+        sort(&mut tasks, &keys);
+        assert_eq!(ids(&tasks), ["4", "3", "2", "1"]);
+
+        // Reversed dates still leave undated tasks last
+        sort(&mut tasks, &["-due".parse().unwrap()]);
+        assert_eq!(ids(&tasks), ["2", "4", "3", "1"]);
+
+        sort(
+            &mut tasks,
+            &["project".parse().unwrap(), "-created".parse().unwrap()],
+        );
+        assert_eq!(ids(&tasks), ["2", "3", "1", "4"]);
+    }
+
+    #[test]
+    fn test_group_by_label_and_due() {
+        let mut tagged = task("1", Some("Work"), Some("2026-01-15T09:00:00"), 4);
+        tagged.labels = vec!["deep".to_string(), "calls".to_string()];
+        let tasks = vec![
+            tagged,
+            task("2", Some("Home"), None, 1),
+            task("3", Some("Work"), Some("2026-01-10"), 2),
+        ];
+
+        let grouped = group(tasks.clone(), GroupBy::Label);
+        let names: Vec<_> = grouped.groups.iter().map(|g| g.name.as_str()).collect();
+        assert_eq!(names, ["calls", "deep", "No label"]);
+        assert_eq!(ids(&grouped.groups[2].tasks), ["2", "3"]);
+        assert_eq!(grouped.tasks.len(), 3);
+
+        let grouped = group(tasks, GroupBy::Due);
+        let names: Vec<_> = grouped.groups.iter().map(|g| g.name.as_str()).collect();
+        assert_eq!(names, ["2026-01-10", "2026-01-15", "No date"]);
+    }
+}
@@ -2,21 +2,32 @@
 //!
 //! A modern CLI client for Todoist built with Rust.
 
+pub mod agenda;
+pub mod backup;
 pub mod cli;
 pub mod config;
 pub mod error;
 pub mod formatter;
+pub mod ics;
+pub mod import;
+pub mod listing;
+pub mod markdown;
 pub mod models;
+pub mod projection;
+pub mod quick_add;
+pub mod resolver;
 pub mod sync;
+pub mod table;
+pub mod template;
 
 pub use formatter::{Formattable, OutputFormat};
 pub use models::Project;
 pub use sync::{SyncFilter, SyncLabel, SyncProject, SyncSection, SyncTask, TodoistSyncClient};
 
 use crate::cli::{
-    handle_error, AddCommands, CacheCommands, Cli, Commands, CompleteCommands, ConfigCommands,
-    DeleteCommands, EditCommands, GetCommands, MoveCommands, ReopenCommands, ReorderCommands,
-    SyncCommands,
+    handle_error, AddCommands, ArchiveCommands, CacheCommands, Cli, Commands, CompleteCommands,
+    ConfigCommands, DeleteCommands, EditCommands, ExportCommand, ExportCommands, GetCommands,
+    MdCommands, MoveCommands, ReopenCommands, ReorderCommands, SyncCommands, UnarchiveCommands,
 };
 use clap::Parser;
 
@@ -70,6 +81,12 @@ pub async fn run(cli: Cli) -> crate::error::Result<()> {
         return Ok(());
     }
 
+    // The batch schema is static, so it doesn't require config either
+    if let Commands::Batch { schema: true, .. } = &cli.command {
+        cli::handlers::batch_schema();
+        return Ok(());
+    }
+
     // Load config for other commands
     let config = crate::config::load_config()?;
 
@@ -81,18 +98,70 @@ pub async fn run(cli: Cli) -> crate::error::Result<()> {
     };
 
     // Create sync client
-    let client = crate::sync::TodoistSyncClient::new(config.api_token);
+    let client = crate::sync::TodoistSyncClient::new(config.api_token)
+        .with_dry_run(cli.dry_run)
+        .with_journal(crate::sync::Journal::new());
 
     // Determine output format (command-specific override or global)
     let format = match &cli.command {
-        Commands::Get(GetCommands::Tasks { format, .. }) => format.clone().unwrap_or(cli.format),
-        Commands::Get(GetCommands::Projects { format, .. }) => format.clone().unwrap_or(cli.format),
-        Commands::Get(GetCommands::Task { format, .. }) => format.clone().unwrap_or(cli.format),
-        Commands::Get(GetCommands::Sections { format, .. }) => format.clone().unwrap_or(cli.format),
-        _ => cli.format,
+        Commands::Get(GetCommands::Tasks { format, .. }) => {
+            format.clone().unwrap_or(cli.format.clone())
+        }
+        Commands::Get(GetCommands::Projects { format, .. }) => {
+            format.clone().unwrap_or(cli.format.clone())
+        }
+        Commands::Get(GetCommands::Task { format, .. }) => {
+            format.clone().unwrap_or(cli.format.clone())
+        }
+        Commands::Get(GetCommands::Sections { format, .. }) => {
+            format.clone().unwrap_or(cli.format.clone())
+        }
+        Commands::Get(GetCommands::Collaborators { format, .. }) => {
+            format.clone().unwrap_or(cli.format.clone())
+        }
+        Commands::Get(GetCommands::Agenda { format, .. }) => {
+            format.clone().unwrap_or(cli.format.clone())
+        }
+        _ => cli.format.clone(),
     };
+    // An explicit template replaces the format
+    let format = if let Some(path) = &cli.template {
+        OutputFormat::Template(crate::template::load_file(path)?)
+    } else if let Some(source) = &cli.template_string {
+        OutputFormat::Template(source.clone())
+    } else {
+        format
+    };
+
+    // Execute command; in dry-run mode the first write stops and is reported instead
+    // Nothing is sent in dry-run mode, so there is nothing to confirm
+    let confirm =
+        cli::confirm::Confirmation::new(cli.yes || cli.dry_run, config.require_confirmation);
+
+    let default_project = config.default_project.as_deref();
+    match dispatch(
+        &cli,
+        &client,
+        &format,
+        &masked_token,
+        &confirm,
+        default_project,
+    )
+    .await
+    {
+        Err(crate::error::TodoError::DryRun) => cli::handlers::dry_run_report(&client).await,
+        result => result,
+    }
+}
 
-    // Execute command
+async fn dispatch(
+    cli: &Cli,
+    client: &crate::sync::TodoistSyncClient,
+    format: &OutputFormat,
+    masked_token: &str,
+    confirm: &cli::confirm::Confirmation,
+    default_project: Option<&str>,
+) -> crate::error::Result<()> {
     match &cli.command {
         // Config commands
         Commands::Config(ConfigCommands::Get) => {
@@ -107,37 +176,70 @@ pub async fn run(cli: Cli) -> crate::error::Result<()> {
         Commands::Get(GetCommands::Tasks {
             filter,
             fields,
+            sort,
+            group_by,
             limit,
             ..
         }) => {
             cli::handlers::get_tasks(
-                &client,
+                client,
                 filter.as_deref(),
-                &format,
+                format,
                 fields.as_deref(),
+                sort,
+                *group_by,
                 *limit,
             )
             .await?;
         }
-        Commands::Get(GetCommands::Projects { fields, .. }) => {
-            cli::handlers::get_projects(&client, &format, fields.as_deref()).await?;
+        Commands::Get(GetCommands::Projects {
+            fields, archived, ..
+        }) => {
+            cli::handlers::get_projects(client, format, fields.as_deref(), *archived).await?;
         }
         Commands::Get(GetCommands::Task {
             task_id, fields, ..
         }) => {
-            cli::handlers::get_task(&client, task_id, &format, fields.as_deref()).await?;
+            cli::handlers::get_task(client, task_id, format, fields.as_deref()).await?;
         }
         Commands::Get(GetCommands::Sections {
-            project_id, fields, ..
+            project_id,
+            project,
+            fields,
+            ..
         }) => {
-            cli::handlers::get_sections(&client, project_id.as_deref(), &format, fields.as_deref())
-                .await?;
+            cli::handlers::get_sections(
+                client,
+                project_id.as_deref(),
+                project.as_deref(),
+                format,
+                fields.as_deref(),
+            )
+            .await?;
+        }
+        Commands::Get(GetCommands::Agenda { date, fields, .. }) => {
+            cli::handlers::get_agenda(client, date, format, fields.as_deref()).await?;
+        }
+        Commands::Get(GetCommands::Collaborators {
+            project_id,
+            project,
+            fields,
+            ..
+        }) => {
+            cli::handlers::get_collaborators(
+                client,
+                project_id.as_deref(),
+                project.as_deref(),
+                format,
+                fields.as_deref(),
+            )
+            .await?;
         }
         Commands::Get(GetCommands::Filters { fields, .. }) => {
-            cli::handlers::get_filters(&client, &format, fields.as_deref()).await?;
+            cli::handlers::get_filters(client, format, fields.as_deref()).await?;
         }
         Commands::Get(GetCommands::Labels { fields, .. }) => {
-            cli::handlers::get_labels(&client, &format, fields.as_deref()).await?;
+            cli::handlers::get_labels(client, format, fields.as_deref()).await?;
         }
 
         // Add commands
@@ -146,38 +248,65 @@ pub async fn run(cli: Cli) -> crate::error::Result<()> {
             content,
             description,
             project_id,
+            project,
+            section,
             due_date,
             priority,
             labels,
+            assignee,
+            duration,
+            inbox,
             ..
         }) => {
             cli::handlers::add_task(
-                &client,
+                client,
                 title.clone(),
                 content.clone(),
                 description.clone(),
                 project_id.clone(),
+                project.clone(),
+                section.clone(),
                 due_date.clone(),
                 *priority,
                 labels.clone(),
+                assignee.clone(),
+                duration.clone(),
+                *inbox,
+                default_project,
             )
             .await?;
         }
-        Commands::Add(AddCommands::Section { name, project_id }) => {
-            cli::handlers::add_section(&client, name.clone(), project_id.clone()).await?;
+        Commands::Add(AddCommands::Quick { text }) => {
+            cli::handlers::add_quick(client, text, default_project).await?;
+        }
+        Commands::Add(AddCommands::Section {
+            name,
+            project_id,
+            project,
+        }) => {
+            cli::handlers::add_section(client, name.clone(), project_id.clone(), project.clone())
+                .await?;
         }
         Commands::Add(AddCommands::Project {
             name,
+            parent,
             color,
             favorite,
         }) => {
-            cli::handlers::add_project(&client, name.clone(), color.clone(), *favorite).await?;
+            cli::handlers::add_project(
+                client,
+                name.clone(),
+                parent.clone(),
+                color.clone(),
+                *favorite,
+            )
+            .await?;
         }
         Commands::Add(AddCommands::Label { name, color }) => {
-            cli::handlers::add_label(&client, name.clone(), color.clone()).await?;
+            cli::handlers::add_label(client, name.clone(), color.clone()).await?;
         }
         Commands::Add(AddCommands::Filter { name, query, color }) => {
-            cli::handlers::add_filter(&client, name.clone(), query.clone(), color.clone()).await?;
+            cli::handlers::add_filter(client, name.clone(), query.clone(), color.clone()).await?;
         }
 
         // Edit commands
@@ -186,35 +315,93 @@ pub async fn run(cli: Cli) -> crate::error::Result<()> {
             title,
             content,
             project_id,
+            project,
             due_date,
             priority,
             labels,
+            add_label,
+            remove_label,
+            assignee,
+            unassign,
+            duration,
         }) => {
             cli::handlers::edit_task(
-                &client,
+                client,
                 task_id.clone(),
                 title.clone(),
                 content.clone(),
                 project_id.clone(),
+                project.clone(),
                 due_date.clone(),
                 *priority,
                 labels.clone(),
+                add_label.clone(),
+                remove_label.clone(),
+                assignee.clone(),
+                *unassign,
+                duration.clone(),
             )
             .await?;
         }
-        Commands::Edit(EditCommands::Project { project_id, name }) => {
-            cli::handlers::edit_project(&client, project_id.clone(), name.clone()).await?;
+        Commands::Edit(EditCommands::Tasks {
+            filter,
+            add_label,
+            remove_label,
+            priority,
+            limit,
+        }) => {
+            cli::handlers::edit_tasks(
+                client,
+                filter.clone(),
+                add_label.clone(),
+                remove_label.clone(),
+                *priority,
+                *limit,
+                confirm,
+            )
+            .await?;
+        }
+        Commands::Edit(EditCommands::Project {
+            project_id,
+            project,
+            name,
+            color,
+            favorite,
+            view_style,
+        }) => {
+            cli::handlers::edit_project(
+                client,
+                project_id.clone(),
+                project.clone(),
+                name.clone(),
+                color.clone(),
+                *favorite,
+                view_style.clone(),
+            )
+            .await?;
         }
-        Commands::Edit(EditCommands::Section { section_id, name }) => {
-            cli::handlers::edit_section(&client, section_id.clone(), name.clone()).await?;
+        Commands::Edit(EditCommands::Section {
+            section_id,
+            section,
+            name,
+        }) => {
+            cli::handlers::edit_section(client, section_id.clone(), section.clone(), name.clone())
+                .await?;
         }
         Commands::Edit(EditCommands::Label {
             label_id,
+            label,
             name,
             color,
         }) => {
-            cli::handlers::edit_label(&client, label_id.clone(), name.clone(), color.clone())
-                .await?;
+            cli::handlers::edit_label(
+                client,
+                label_id.clone(),
+                label.clone(),
+                name.clone(),
+                color.clone(),
+            )
+            .await?;
         }
         Commands::Edit(EditCommands::Filter {
             filter_id,
@@ -223,7 +410,7 @@ pub async fn run(cli: Cli) -> crate::error::Result<()> {
             color,
         }) => {
             cli::handlers::edit_filter(
-                &client,
+                client,
                 filter_id.clone(),
                 name.clone(),
                 query.clone(),
@@ -233,64 +420,230 @@ pub async fn run(cli: Cli) -> crate::error::Result<()> {
         }
 
         // Complete/Reopen
-        Commands::Complete(CompleteCommands::Task { task_id }) => {
-            cli::handlers::complete_task(&client, task_id.clone()).await?;
+        Commands::Complete(CompleteCommands::Task { task_id, forever }) => {
+            cli::handlers::complete_task(client, task_id.clone(), *forever).await?;
+        }
+        Commands::Complete(CompleteCommands::Tasks {
+            filter,
+            limit,
+            forever,
+        }) => {
+            cli::handlers::complete_tasks(client, filter.clone(), *limit, *forever, confirm)
+                .await?;
         }
         Commands::Reopen(ReopenCommands::Task { task_id }) => {
-            cli::handlers::reopen_task(&client, task_id.clone()).await?;
+            cli::handlers::reopen_task(client, task_id.clone()).await?;
         }
 
         // Delete commands
         Commands::Delete(DeleteCommands::Task { task_id }) => {
-            cli::handlers::delete_task(&client, task_id.clone()).await?;
+            cli::handlers::delete_task(client, task_id.clone(), confirm).await?;
+        }
+        Commands::Delete(DeleteCommands::Tasks { filter, limit }) => {
+            cli::handlers::delete_tasks(client, filter.clone(), *limit, confirm).await?;
+        }
+        Commands::Delete(DeleteCommands::Project {
+            project_id,
+            project,
+        }) => {
+            cli::handlers::delete_project(client, project_id.clone(), project.clone(), confirm)
+                .await?;
         }
-        Commands::Delete(DeleteCommands::Project { project_id }) => {
-            cli::handlers::delete_project(&client, project_id.clone()).await?;
+        Commands::Delete(DeleteCommands::Section {
+            section_id,
+            section,
+        }) => {
+            cli::handlers::delete_section(client, section_id.clone(), section.clone(), confirm)
+                .await?;
         }
-        Commands::Delete(DeleteCommands::Section { section_id }) => {
-            cli::handlers::delete_section(&client, section_id.clone()).await?;
+        Commands::Delete(DeleteCommands::Label { label_id, label }) => {
+            cli::handlers::delete_label(client, label_id.clone(), label.clone(), confirm).await?;
+        }
+        Commands::Delete(DeleteCommands::Filter { filter_id }) => {
+            cli::handlers::delete_filter(client, filter_id.clone(), confirm).await?;
         }
 
         // Move commands
         Commands::Move(MoveCommands::Task {
             task_id,
             project_id,
+            project,
             section_id,
+            section,
+            inbox,
         }) => {
             cli::handlers::move_task(
-                &client,
+                client,
                 task_id.clone(),
                 project_id.clone(),
+                project.clone(),
                 section_id.clone(),
+                section.clone(),
+                *inbox,
+            )
+            .await?;
+        }
+        Commands::Move(MoveCommands::Tasks {
+            filter,
+            project_id,
+            project,
+            section_id,
+            section,
+            limit,
+        }) => {
+            cli::handlers::move_tasks(
+                client,
+                filter.clone(),
+                project_id.clone(),
+                project.clone(),
+                section_id.clone(),
+                section.clone(),
+                *limit,
+                confirm,
             )
             .await?;
         }
 
+        Commands::Move(MoveCommands::Project {
+            project_id,
+            project,
+            parent,
+            ..
+        }) => {
+            cli::handlers::move_project(
+                client,
+                project_id.clone(),
+                project.clone(),
+                parent.clone(),
+            )
+            .await?;
+        }
+        Commands::Move(MoveCommands::Section {
+            section_id,
+            section,
+            project_id,
+            project,
+        }) => {
+            cli::handlers::move_section(
+                client,
+                section_id.clone(),
+                section.clone(),
+                project_id.clone(),
+                project.clone(),
+            )
+            .await?;
+        }
+
+        // Archive commands
+        Commands::Archive(ArchiveCommands::Project {
+            project_id,
+            project,
+        }) => {
+            cli::handlers::archive_project(client, project_id.clone(), project.clone(), true)
+                .await?;
+        }
+        Commands::Archive(ArchiveCommands::Section {
+            section_id,
+            section,
+        }) => {
+            cli::handlers::archive_section(client, section_id.clone(), section.clone(), true)
+                .await?;
+        }
+        Commands::Unarchive(UnarchiveCommands::Project {
+            project_id,
+            project,
+        }) => {
+            cli::handlers::archive_project(client, project_id.clone(), project.clone(), false)
+                .await?;
+        }
+        Commands::Unarchive(UnarchiveCommands::Section {
+            section_id,
+            section,
+        }) => {
+            cli::handlers::archive_section(client, section_id.clone(), section.clone(), false)
+                .await?;
+        }
+
         // Reorder commands
         Commands::Reorder(ReorderCommands::Sections { section_ids }) => {
-            cli::handlers::reorder_sections(&client, section_ids.clone()).await?;
+            cli::handlers::reorder_sections(client, section_ids.clone()).await?;
+        }
+        Commands::Reorder(ReorderCommands::Projects { project_ids }) => {
+            cli::handlers::reorder_projects(client, project_ids.clone()).await?;
         }
 
         // Sync commands
         Commands::Sync(SyncCommands::Sync { force }) => {
-            cli::handlers::sync(&client, *force).await?;
+            cli::handlers::sync(client, *force).await?;
         }
 
         // Cache commands
         Commands::Cache(CacheCommands::Status) => {
-            cli::handlers::cache_status(&client)?;
+            cli::handlers::cache_status(client)?;
         }
         Commands::Cache(CacheCommands::Clear) => {
-            cli::handlers::cache_clear(&client)?;
+            cli::handlers::cache_clear(client)?;
+        }
+
+        // Markdown sync
+        Commands::Md(MdCommands::Sync { file }) => {
+            let state = crate::markdown::SyncState::new()?;
+            cli::handlers::md_sync(client, &state, file, default_project).await?;
+        }
+
+        // Export
+        Commands::Export(ExportCommand {
+            command:
+                Some(ExportCommands::Ics {
+                    filter,
+                    component,
+                    output,
+                }),
+            ..
+        }) => {
+            cli::handlers::export_ics(client, filter.as_deref(), *component, output.as_deref())
+                .await?;
+        }
+
+        Commands::Export(ExportCommand { command: None, .. }) => {
+            cli::handlers::export_all(client).await?;
+        }
+        Commands::Import {
+            file,
+            from: None,
+            into,
+        } => {
+            cli::handlers::import_backup(client, file, into.as_deref()).await?;
+        }
+        Commands::Import {
+            file,
+            from: Some(source),
+            into,
+        } => {
+            cli::handlers::import_tasks(client, file, *source, into.as_deref()).await?;
         }
 
         // Batch command
-        Commands::Batch { commands } => {
-            cli::handlers::batch(&client, commands.clone()).await?;
+        Commands::Batch {
+            commands: Some(commands),
+            allow_unknown,
+            ..
+        } => {
+            cli::handlers::batch(client, commands.clone(), *allow_unknown, confirm).await?;
         }
 
-        // Completion and Init were handled above
-        Commands::Completion { .. } | Commands::Init(_) => unreachable!(),
+        // Undo journal
+        Commands::Undo { steps } => {
+            cli::handlers::undo(client, &crate::sync::Journal::new(), *steps).await?;
+        }
+        Commands::History { limit } => {
+            cli::handlers::history(&crate::sync::Journal::new(), *limit)?;
+        }
+
+        // Completion, Init and the batch schema were handled above
+        Commands::Completion { .. } | Commands::Init(_) | Commands::Batch { .. } => {
+            unreachable!()
+        }
     }
 
     Ok(())
@@ -312,6 +665,53 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_cli_parsing_priority_forms() {
+        for (value, expected) in [
+            ("p1", crate::models::Priority::URGENT),
+            ("high", crate::models::Priority::HIGH),
+            ("P3", crate::models::Priority::MEDIUM),
+        ] {
+            let cli = Cli::try_parse_from([
+                "todorust",
+                "add",
+                "task",
+                "--title",
+                "t",
+                "--priority",
+                value,
+            ])
+            .unwrap();
+            match cli.command {
+                Commands::Add(AddCommands::Task { priority, .. }) => {
+                    assert_eq!(priority, Some(expected))
+                }
+                _ => panic!("unexpected command"),
+            }
+        }
+        assert!(Cli::try_parse_from([
+            "todorust",
+            "add",
+            "task",
+            "--title",
+            "t",
+            "--priority",
+            "p5"
+        ])
+        .is_err());
+        // A bare number is ambiguous between p1 and the API value 1 (p4)
+        assert!(Cli::try_parse_from([
+            "todorust",
+            "add",
+            "task",
+            "--title",
+            "t",
+            "--priority",
+            "1"
+        ])
+        .is_err());
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_run_init_with_token() {
@@ -322,6 +722,14 @@ mod tests {
 
         let cli = Cli {
             format: OutputFormat::Json,
+
+            template: None,
+
+            template_string: None,
+
+            dry_run: false,
+
+            yes: false,
             command: Commands::Init(InitCommand {
                 api_token: Some("test_token".to_string()),
             }),
@@ -345,6 +753,14 @@ mod tests {
 
         let cli = Cli {
             format: OutputFormat::Json,
+
+            template: None,
+
+            template_string: None,
+
+            dry_run: false,
+
+            yes: false,
             command: Commands::Config(ConfigCommands::Get),
         };
 
@@ -376,10 +792,20 @@ mod tests {
 
         let cli = Cli {
             format: OutputFormat::Json,
+
+            template: None,
+
+            template_string: None,
+
+            dry_run: false,
+
+            yes: false,
             command: Commands::Get(GetCommands::Tasks {
                 filter: None,
                 format: None,
                 fields: None,
+                sort: Vec::new(),
+                group_by: None,
                 limit: None,
             }),
         };
@@ -408,14 +834,27 @@ mod tests {
 
         let cli = Cli {
             format: OutputFormat::Json,
+
+            template: None,
+
+            template_string: None,
+
+            dry_run: false,
+
+            yes: false,
             command: Commands::Add(AddCommands::Task {
                 title: Some("New Task".to_string()),
                 content: None,
                 description: None,
                 project_id: None,
+                project: None,
+                section: None,
                 due_date: None,
-                priority: Some(4),
+                priority: Some(crate::models::Priority::URGENT),
                 labels: None,
+                assignee: None,
+                duration: None,
+                inbox: false,
                 format: None,
             }),
         };
@@ -461,9 +900,21 @@ mod tests {
         let cli = Cli {
             format: OutputFormat::Json,
 
+            template: None,
+
+            template_string: None,
+
+            dry_run: false,
+
+            yes: false,
+
             command: Commands::Batch {
-                commands: r#"[{"type": "item_complete", "uuid": "uuid1", "args": {"id": "123"}}]"#
-                    .to_string(),
+                commands: Some(
+                    r#"[{"type": "item_complete", "uuid": "uuid1", "args": {"id": "123"}}]"#
+                        .to_string(),
+                ),
+                allow_unknown: false,
+                schema: false,
             },
         };
 
@@ -499,6 +950,14 @@ mod tests {
         let cli = Cli {
             format: OutputFormat::Json,
 
+            template: None,
+
+            template_string: None,
+
+            dry_run: false,
+
+            yes: false,
+
             command: Commands::Delete(DeleteCommands::Task {
                 task_id: "123".to_string(),
             }),
@@ -536,6 +995,14 @@ mod tests {
         let cli = Cli {
             format: OutputFormat::Json,
 
+            template: None,
+
+            template_string: None,
+
+            dry_run: false,
+
+            yes: false,
+
             command: Commands::Edit(EditCommands::Task {
                 task_id: "123".to_string(),
 
@@ -545,11 +1012,23 @@ mod tests {
 
                 project_id: None,
 
+                project: None,
+
                 due_date: None,
 
                 priority: None,
 
                 labels: None,
+
+                add_label: vec![],
+
+                remove_label: vec![],
+
+                assignee: None,
+
+                unassign: false,
+
+                duration: None,
             }),
         };
 
@@ -591,10 +1070,20 @@ mod tests {
         let cli = Cli {
             format: OutputFormat::Json,
 
+            template: None,
+
+            template_string: None,
+
+            dry_run: false,
+
+            yes: false,
+
             command: Commands::Get(GetCommands::Projects {
                 format: None,
 
                 fields: None,
+
+                archived: false,
             }),
         };
 
@@ -620,6 +1109,14 @@ mod tests {
         let cli = Cli {
             format: OutputFormat::Json,
 
+            template: None,
+
+            template_string: None,
+
+            dry_run: false,
+
+            yes: false,
+
             command: Commands::Config(ConfigCommands::Get),
         };
 
@@ -655,9 +1152,19 @@ mod tests {
         let cli = Cli {
             format: OutputFormat::Json,
 
+            template: None,
+
+            template_string: None,
+
+            dry_run: false,
+
+            yes: false,
+
             command: Commands::Get(GetCommands::Sections {
                 project_id: None,
 
+                project: None,
+
                 format: None,
 
                 fields: None,
@@ -696,12 +1203,26 @@ mod tests {
         let cli = Cli {
             format: OutputFormat::Json,
 
+            template: None,
+
+            template_string: None,
+
+            dry_run: false,
+
+            yes: false,
+
             command: Commands::Move(MoveCommands::Task {
                 task_id: "123".to_string(),
 
-                project_id: "proj1".to_string(),
+                project_id: Some("proj1".to_string()),
+
+                project: None,
 
                 section_id: None,
+
+                section: None,
+
+                inbox: false,
             }),
         };
 
@@ -737,6 +1258,14 @@ mod tests {
         let cli = Cli {
             format: OutputFormat::Json,
 
+            template: None,
+
+            template_string: None,
+
+            dry_run: false,
+
+            yes: false,
+
             command: Commands::Reorder(ReorderCommands::Sections {
                 section_ids: "s1,s2".to_string(),
             }),
@@ -776,10 +1305,20 @@ mod tests {
         let cli = Cli {
             format: OutputFormat::Json,
 
+            template: None,
+
+            template_string: None,
+
+            dry_run: false,
+
+            yes: false,
+
             command: Commands::Add(AddCommands::Section {
                 name: "New Section".to_string(),
 
-                project_id: "proj1".to_string(),
+                project_id: Some("proj1".to_string()),
+
+                project: None,
             }),
         };
 
@@ -795,6 +1334,14 @@ mod tests {
         let cli = Cli {
             format: OutputFormat::Json,
 
+            template: None,
+
+            template_string: None,
+
+            dry_run: false,
+
+            yes: false,
+
             command: Commands::Completion {
                 shell: clap_complete::Shell::Bash,
             },
@@ -809,6 +1356,80 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_run_dry_run_does_not_send_commands() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+
+        std::env::set_var("TODORUST_API_TOKEN", "mock_token");
+
+        std::env::set_var("TODORUST_SYNC_URL", server.url("/sync"));
+
+        let write = server.mock(|when, then| {
+            when.method(POST).path("/sync").body_includes("commands");
+
+            then.status(500);
+        });
+
+        server.mock(|when, then| {
+            when.method(POST).path("/sync");
+
+            then.status(200).json_body(serde_json::json!({
+                "sync_token": "token123",
+                "items": [{"id": "123", "content": "Existing"}]
+            }));
+        });
+
+        let cli = Cli {
+            format: OutputFormat::Json,
+
+            template: None,
+
+            template_string: None,
+
+            dry_run: true,
+
+            yes: false,
+
+            command: Commands::Batch {
+                commands: Some(r#"[{"type": "item_delete", "args": {"id": "123"}}]"#.to_string()),
+                allow_unknown: false,
+                schema: false,
+            },
+        };
+
+        let result = run(cli).await;
+
+        assert!(result.is_ok());
+        write.assert_calls(0);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_run_batch_schema_without_config() {
+        let cli = Cli {
+            format: OutputFormat::Json,
+
+            template: None,
+
+            template_string: None,
+
+            dry_run: false,
+
+            yes: false,
+
+            command: Commands::Batch {
+                commands: None,
+                allow_unknown: false,
+                schema: true,
+            },
+        };
+
+        assert!(run(cli).await.is_ok());
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_run_cache_status_flow() {
@@ -820,6 +1441,14 @@ mod tests {
 
         let cli = Cli {
             format: OutputFormat::Json,
+
+            template: None,
+
+            template_string: None,
+
+            dry_run: false,
+
+            yes: false,
             command: Commands::Cache(CacheCommands::Status),
         };
 
@@ -839,6 +1468,14 @@ mod tests {
 
         let cli = Cli {
             format: OutputFormat::Json,
+
+            template: None,
+
+            template_string: None,
+
+            dry_run: false,
+
+            yes: false,
             command: Commands::Cache(CacheCommands::Clear),
         };
 
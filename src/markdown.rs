@@ -0,0 +1,535 @@
+//! # Markdown Checklists
+//!
+//! Parses `- [ ]` / `- [x]` lines of a notes file, as written by the obsidian
+//! format, for `todorust md sync`:
+//!
+//! ```text
+//! ## Work
+//! - [ ] Write report <!-- todoist-id:123 --> #deep-work ⏫ 📅 2026-01-20
+//! ```
+//!
+//! Besides the content, a line carries `#tags` (labels, spaces written as `-`),
+//! a priority (🔺 p1, ⏫ p2, 🔼 p3), `🔁 recurrence`, `📅 due` and `✅ done` dates.
+//! Lines without an id are new tasks, created in the project named by the
+//! nearest heading above them. Content words that would read as one of these
+//! markers are escaped with a backslash, e.g. `Fix \#bug in parser`.
+//!
+//! Both sides are merged field by field against the state recorded at the
+//! last sync: a change on one side wins, a change on both is a conflict that
+//! is reported and left alone until one side gives way.
+
+use crate::error::{Result, TodoError};
+use crate::models::{Priority, TaskOutput};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const ID_PREFIX: &str = "<!-- todoist-id:";
+const ID_SUFFIX: &str = "-->";
+const RECURRENCE: &str = "🔁";
+const DUE: &str = "📅";
+const DONE: &str = "✅";
+
+/// The synced fields of a task, as written in the file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Fields {
+    pub content: String,
+    pub checked: bool,
+    pub priority: Priority,
+    /// Label names, sorted
+    pub labels: Vec<String>,
+    /// Due date as `YYYY-MM-DD`
+    pub due: Option<String>,
+}
+
+impl Fields {
+    /// Fields of a Todoist task
+    pub fn from_task(task: &TaskOutput) -> Self {
+        let mut labels = task.labels.clone();
+        labels.sort();
+        Self {
+            content: task.content.clone(),
+            checked: task.is_completed,
+            priority: task.priority,
+            labels,
+            due: task.due_date.as_deref().map(date_part),
+        }
+    }
+
+    /// The fields as read back from an unescaped line: tags in the content
+    /// become labels and whitespace collapses
+    fn as_written(&self) -> Fields {
+        let labels: Vec<&str> = self.labels.iter().map(String::as_str).collect();
+        let Some(parsed) = parse_line(&format!("- [ ] {}", self.content), &labels) else {
+            return self.clone();
+        };
+        let mut written = self.clone();
+        written.content = parsed.fields.content;
+        written.labels.extend(parsed.fields.labels);
+        written.labels.sort();
+        written.labels.dedup();
+        written
+    }
+}
+
+/// A checklist line of the file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Item {
+    /// Line number, starting at 0
+    pub line: usize,
+    /// Indentation and bullet, e.g. `  - `
+    pub prefix: String,
+    pub id: Option<String>,
+    pub fields: Fields,
+    /// Recurrence as written after 🔁, e.g. `every monday`
+    pub recurrence: Option<String>,
+    /// Completion date as written after ✅
+    pub done: Option<String>,
+    /// Nearest heading above the line
+    pub heading: Option<String>,
+}
+
+impl Item {
+    /// Renders the line in the format of the obsidian output
+    pub fn render(&self) -> String {
+        let mut line = format!(
+            "{}[{}] {}",
+            self.prefix,
+            if self.fields.checked { "x" } else { " " },
+            escape_content(&self.fields.content)
+        );
+        if let Some(id) = &self.id {
+            line.push_str(&format!(" {}{} {}", ID_PREFIX, id, ID_SUFFIX));
+        }
+        for label in &self.fields.labels {
+            line.push_str(&format!(" #{}", label.replace(' ', "-")));
+        }
+        match self.fields.priority.display_level() {
+            1 => line.push_str(" 🔺"),
+            2 => line.push_str(" ⏫"),
+            3 => line.push_str(" 🔼"),
+            _ => {}
+        }
+        if let Some(recurrence) = &self.recurrence {
+            line.push_str(&format!(" {} {}", RECURRENCE, recurrence));
+        }
+        if let Some(due) = &self.fields.due {
+            line.push_str(&format!(" {} {}", DUE, due));
+        }
+        if let Some(done) = self.done.as_ref().filter(|_| self.fields.checked) {
+            line.push_str(&format!(" {} {}", DONE, done));
+        }
+        line
+    }
+}
+
+/// Parses the checklist lines of `text`.
+///
+/// Tags are matched to `labels` with spaces written as `-`; unknown tags are
+/// kept as label names.
+pub fn parse(text: &str, labels: &[&str]) -> Vec<Item> {
+    let mut heading = None;
+    let mut items = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        if let Some(title) = parse_heading(line) {
+            heading = Some(title);
+        } else if let Some(mut item) = parse_line(line, labels) {
+            item.line = number;
+            item.heading = heading.clone();
+            items.push(item);
+        }
+    }
+    items
+}
+
+fn parse_heading(line: &str) -> Option<String> {
+    let title = line.trim_start_matches('#');
+    let level = line.len() - title.len();
+    ((1..=6).contains(&level) && title.starts_with(' ')).then(|| title.trim().to_string())
+}
+
+/// Parses one `- [ ] ...` line
+fn parse_line(line: &str, labels: &[&str]) -> Option<Item> {
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+    let bullet = trimmed.chars().next().filter(|c| "-*+".contains(*c))?;
+    let rest = trimmed[1..].strip_prefix(' ')?.strip_prefix('[')?;
+    let mut chars = rest.chars();
+    let checked = match chars.next()? {
+        ' ' => false,
+        'x' | 'X' => true,
+        _ => return None,
+    };
+    let rest = chars.as_str().strip_prefix(']')?;
+    if !(rest.is_empty() || rest.starts_with(' ')) {
+        return None;
+    }
+
+    let (id, rest) = extract_id(rest);
+    let mut item = Item {
+        line: 0,
+        prefix: format!("{}{} ", indent, bullet),
+        id,
+        fields: Fields {
+            checked,
+            ..Fields::default()
+        },
+        recurrence: None,
+        done: None,
+        heading: None,
+    };
+
+    let mut content = Vec::new();
+    let mut tokens = rest.split_whitespace().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            "🔺" => item.fields.priority = Priority::URGENT,
+            "⏫" => item.fields.priority = Priority::HIGH,
+            "🔼" => item.fields.priority = Priority::MEDIUM,
+            "🔽" | "⏬" => item.fields.priority = Priority::LOW,
+            DUE => item.fields.due = tokens.next().map(str::to_string),
+            DONE => item.done = tokens.next().map(str::to_string),
+            RECURRENCE => {
+                let mut words = Vec::new();
+                while let Some(word) = tokens.next_if(|t| !is_signifier(t)) {
+                    words.push(word);
+                }
+                item.recurrence = (!words.is_empty()).then(|| words.join(" "));
+            }
+            tag if is_tag(tag) => item.fields.labels.push(label_name(&tag[1..], labels)),
+            escaped if escaped.strip_prefix('\\').is_some_and(needs_escape) => {
+                content.push(&escaped[1..])
+            }
+            word => content.push(word),
+        }
+    }
+    item.fields.content = content.join(" ");
+    item.fields.labels.sort();
+    item.fields.labels.dedup();
+    Some(item)
+}
+
+/// Splits off the `<!-- todoist-id:ID -->` marker
+fn extract_id(text: &str) -> (Option<String>, String) {
+    let Some(start) = text.find(ID_PREFIX) else {
+        return (None, text.to_string());
+    };
+    let after = &text[start + ID_PREFIX.len()..];
+    let Some(end) = after.find(ID_SUFFIX) else {
+        return (None, text.to_string());
+    };
+    let id = after[..end].trim();
+    let rest = format!("{} {}", &text[..start], &after[end + ID_SUFFIX.len()..]);
+    ((!id.is_empty()).then(|| id.to_string()), rest)
+}
+
+fn is_signifier(token: &str) -> bool {
+    matches!(token, "🔺" | "⏫" | "🔼" | "🔽" | "⏬" | DUE | DONE) || is_tag(token)
+}
+
+/// Words that would be read as a marker, or as an escaped one
+fn needs_escape(word: &str) -> bool {
+    is_signifier(word) || word == RECURRENCE || word.strip_prefix('\\').is_some_and(needs_escape)
+}
+
+/// Escapes content words that would be read back as tags or markers, e.g.
+/// `#bug` becomes `\#bug`
+pub fn escape_content(content: &str) -> String {
+    content
+        .split(' ')
+        .map(|word| {
+            if needs_escape(word) {
+                format!("\\{}", word)
+            } else {
+                word.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `#tag`, but not a heading-like `#` or a number such as `#1`
+fn is_tag(token: &str) -> bool {
+    token
+        .strip_prefix('#')
+        .is_some_and(|t| !t.is_empty() && !t.chars().all(|c| c.is_ascii_digit() || c == '#'))
+}
+
+fn label_name(tag: &str, labels: &[&str]) -> String {
+    labels
+        .iter()
+        .find(|l| l.replace(' ', "-") == tag)
+        .map(|l| l.to_string())
+        .unwrap_or_else(|| tag.to_string())
+}
+
+/// `2026-01-20T09:00:00` -> `2026-01-20`
+pub fn date_part(value: &str) -> String {
+    value.chars().take(10).collect()
+}
+
+/// A field of [`Fields`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Field {
+    Checked,
+    Content,
+    Priority,
+    Labels,
+    Due,
+}
+
+/// A field changed both in the file and in Todoist
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Conflict {
+    pub id: String,
+    pub field: Field,
+    pub local: serde_json::Value,
+    pub remote: serde_json::Value,
+}
+
+/// Outcome of merging a line with its task
+#[derive(Debug, Clone, PartialEq)]
+pub struct Merge {
+    /// Fields changed in the file, to send to Todoist
+    pub push: Vec<Field>,
+    /// Fields to write to the file
+    pub merged: Fields,
+    /// State to record for the next sync
+    pub base: Fields,
+    pub conflicts: Vec<Conflict>,
+}
+
+/// Merges `local` and `remote` field by field against `base`, the state of the
+/// last sync. Without a base, differences count as changes in the file.
+///
+/// A local value equal to the remote one as read from an unescaped line (tags
+/// in the content taken as labels, whitespace collapsed) is no change.
+pub fn merge(id: &str, local: &Fields, remote: &Fields, base: Option<&Fields>) -> Merge {
+    let base = base.unwrap_or(remote);
+    let (remote_written, base_written) = (remote.as_written(), base.as_written());
+    let mut out = Merge {
+        push: Vec::new(),
+        merged: local.clone(),
+        base: base.clone(),
+        conflicts: Vec::new(),
+    };
+
+    macro_rules! field {
+        ($field:ident, $kind:expr) => {
+            let (l, r, b) = (&local.$field, &remote.$field, &base.$field);
+            if l == r {
+                out.base.$field = l.clone();
+            } else if *l == remote_written.$field {
+                out.base.$field = r.clone();
+            } else if r == b {
+                out.push.push($kind);
+                out.base.$field = l.clone();
+            } else if l == b || *l == base_written.$field {
+                out.merged.$field = r.clone();
+                out.base.$field = r.clone();
+            } else {
+                out.conflicts.push(Conflict {
+                    id: id.to_string(),
+                    field: $kind,
+                    local: serde_json::json!(l),
+                    remote: serde_json::json!(r),
+                });
+            }
+        };
+    }
+    field!(checked, Field::Checked);
+    field!(content, Field::Content);
+    field!(priority, Field::Priority);
+    field!(labels, Field::Labels);
+    field!(due, Field::Due);
+    out
+}
+
+/// Fields of each task id at the last sync of each file, stored as JSON next
+/// to the configuration
+pub struct SyncState {
+    path: PathBuf,
+}
+
+type Files = HashMap<String, HashMap<String, Fields>>;
+
+impl SyncState {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            path: crate::config::config_dir()?.join("md_sync.json"),
+        })
+    }
+
+    /// Create a SyncState with a custom path (for testing)
+    #[cfg(test)]
+    pub fn with_path(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    fn load_all(&self) -> Result<Files> {
+        if !self.path.exists() {
+            return Ok(Files::new());
+        }
+        let content = std::fs::read_to_string(&self.path)?;
+        serde_json::from_str(&content)
+            .map_err(|e| TodoError::Config(format!("Invalid markdown sync state: {}", e)))
+    }
+
+    /// Recorded fields of the tasks of `file`
+    pub fn load(&self, file: &Path) -> Result<HashMap<String, Fields>> {
+        Ok(self.load_all()?.remove(&file_key(file)).unwrap_or_default())
+    }
+
+    pub fn save(&self, file: &Path, tasks: HashMap<String, Fields>) -> Result<()> {
+        let mut files = self.load_all()?;
+        files.insert(file_key(file), tasks);
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&files).unwrap())?;
+        Ok(())
+    }
+}
+
+fn file_key(file: &Path) -> String {
+    file.canonicalize()
+        .unwrap_or_else(|_| file.to_path_buf())
+        .display()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(content: &str, checked: bool) -> Fields {
+        Fields {
+            content: content.to_string(),
+            checked,
+            ..Fields::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_obsidian_line() {
+        let text = "## Work\n\n\
+                    - [ ] Write report <!-- todoist-id:123 --> #deep-work #home ⏫ 🔁 every monday 📅 2026-01-20\n\
+                    Some notes\n  * [x] Call Bea ✅ 2026-01-18\n- [-] Cancelled\n";
+        let items = parse(text, &["deep work"]);
+        assert_eq!(items.len(), 2);
+
+        let first = &items[0];
+        assert_eq!(first.line, 2);
+        assert_eq!(first.id.as_deref(), Some("123"));
+        assert_eq!(first.heading.as_deref(), Some("Work"));
+        assert_eq!(first.fields.content, "Write report");
+        assert_eq!(first.fields.labels, vec!["deep work", "home"]);
+        assert_eq!(first.fields.priority, Priority::HIGH);
+        assert_eq!(first.fields.due.as_deref(), Some("2026-01-20"));
+        assert_eq!(first.recurrence.as_deref(), Some("every monday"));
+        assert_eq!(first.render(), text.lines().nth(2).unwrap());
+
+        let second = &items[1];
+        assert_eq!(second.prefix, "  * ");
+        assert!(second.fields.checked);
+        assert_eq!(second.id, None);
+        assert_eq!(second.done.as_deref(), Some("2026-01-18"));
+        assert_eq!(second.render(), "  * [x] Call Bea ✅ 2026-01-18");
+    }
+
+    #[test]
+    fn test_parse_keeps_plain_text() {
+        let items = parse("- [ ] Fix #1 issue\n-[ ] not a task\n- [ ]\n", &[]);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].fields.content, "Fix #1 issue");
+        assert!(items[0].fields.labels.is_empty());
+        assert_eq!(items[1].fields.content, "");
+    }
+
+    #[test]
+    fn test_content_markers_are_escaped() {
+        let mut item = parse("- [ ] x <!-- todoist-id:1 -->", &[]).remove(0);
+        item.fields.content = "Fix #bug in \\#parser ⏫ now".to_string();
+        item.fields.labels = vec!["bug".to_string()];
+        let line = item.render();
+        assert_eq!(
+            line,
+            "- [ ] Fix \\#bug in \\\\#parser \\⏫ now <!-- todoist-id:1 --> #bug"
+        );
+        let parsed = parse(&line, &[]).remove(0);
+        assert_eq!(parsed.fields, item.fields);
+    }
+
+    #[test]
+    fn test_merge_ignores_unescaped_export() {
+        // Written by an export that did not escape the tag, and with a double space
+        let mut remote = fields("Fix #bug  in parser", false);
+        remote.labels = vec!["urgent".to_string()];
+        let local = parse("- [ ] Fix #bug  in parser #urgent", &[])
+            .remove(0)
+            .fields;
+
+        let merge = merge("1", &local, &remote, None);
+        assert!(merge.push.is_empty());
+        assert!(merge.conflicts.is_empty());
+        assert_eq!(merge.base, remote);
+
+        // A later change in Todoist is pulled without a conflict
+        let mut changed = remote.clone();
+        changed.content = "Fix #bug in the parser".to_string();
+        let next = super::merge("1", &local, &changed, Some(&merge.base));
+        assert!(next.conflicts.is_empty());
+        assert_eq!(next.merged.content, "Fix #bug in the parser");
+    }
+
+    #[test]
+    fn test_merge_one_side_changed() {
+        let base = fields("Buy milk", false);
+        let local = fields("Buy oat milk", false);
+        let remote = fields("Buy milk", true);
+
+        let merge = merge("1", &local, &remote, Some(&base));
+        assert_eq!(merge.push, vec![Field::Content]);
+        assert_eq!(merge.merged, fields("Buy oat milk", true));
+        assert_eq!(merge.base, fields("Buy oat milk", true));
+        assert!(merge.conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_merge_conflict() {
+        let base = fields("Buy milk", false);
+        let local = fields("Buy oat milk", false);
+        let remote = fields("Buy soy milk", false);
+
+        let merge = merge("1", &local, &remote, Some(&base));
+        assert!(merge.push.is_empty());
+        assert_eq!(merge.merged, local);
+        assert_eq!(merge.base, base);
+        assert_eq!(merge.conflicts.len(), 1);
+        assert_eq!(merge.conflicts[0].field, Field::Content);
+        assert_eq!(merge.conflicts[0].remote, "Buy soy milk");
+    }
+
+    #[test]
+    fn test_merge_without_base_pushes_local() {
+        let local = fields("Buy milk", true);
+        let remote = fields("Buy milk", false);
+        let merge = merge("1", &local, &remote, None);
+        assert_eq!(merge.push, vec![Field::Checked]);
+        assert_eq!(merge.base, local);
+    }
+
+    #[test]
+    fn test_sync_state_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = SyncState::with_path(dir.path().join("md_sync.json"));
+        let file = dir.path().join("notes.md");
+        assert!(state.load(&file).unwrap().is_empty());
+
+        let tasks = HashMap::from([("1".to_string(), fields("Buy milk", false))]);
+        state.save(&file, tasks.clone()).unwrap();
+        assert_eq!(state.load(&file).unwrap(), tasks);
+        assert!(state.load(&dir.path().join("other.md")).unwrap().is_empty());
+    }
+}
@@ -4,14 +4,209 @@
 //! Models are shared between the REST API and Sync API clients.
 
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Task priority.
+///
+/// Stored as the API value, where 4 is the most urgent. The Todoist UI shows the
+/// same scale inverted: API 4 is "p1" and API 1 is "p4". Serialized as the
+/// API value; other values than 1-4 are rejected when deserializing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "u8", into = "u8")]
+pub struct Priority(u8);
+
+impl TryFrom<u8> for Priority {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Self::from_api(value).ok_or_else(|| {
+            format!(
+                "invalid priority {}, expected an API value 1-4 (4 = p1)",
+                value
+            )
+        })
+    }
+}
+
+impl From<Priority> for u8 {
+    fn from(priority: Priority) -> Self {
+        priority.0
+    }
+}
+
+impl Priority {
+    pub const URGENT: Priority = Priority(4);
+    pub const HIGH: Priority = Priority(3);
+    pub const MEDIUM: Priority = Priority(2);
+    pub const LOW: Priority = Priority(1);
+
+    /// Creates a priority from an API value (1-4)
+    pub fn from_api(value: u8) -> Option<Self> {
+        (1..=4).contains(&value).then_some(Self(value))
+    }
+
+    /// Creates a priority from a UI level, where 1 means "p1"
+    pub fn from_display(level: u8) -> Option<Self> {
+        (1..=4).contains(&level).then(|| Self(5 - level))
+    }
+
+    /// Value sent to and received from the Todoist API
+    pub fn api_value(self) -> u8 {
+        self.0
+    }
+
+    /// Level shown in the Todoist UI (1 for "p1")
+    pub fn display_level(self) -> u8 {
+        5 - self.0
+    }
+
+    pub fn name(self) -> &'static str {
+        match self.0 {
+            4 => "urgent",
+            3 => "high",
+            2 => "medium",
+            _ => "low",
+        }
+    }
+}
+
+impl Default for Priority {
+    fn default() -> Self {
+        Self::LOW
+    }
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "p{}", self.display_level())
+    }
+}
+
+/// Accepts `p1`..`p4` or `urgent`/`high`/`medium`/`low`. Bare numbers are
+/// rejected, as `1` would mean p1 to some and the API value for p4 to others.
+impl FromStr for Priority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.trim().to_lowercase();
+        let priority = match lower.as_str() {
+            "urgent" => Some(Self::URGENT),
+            "high" => Some(Self::HIGH),
+            "medium" => Some(Self::MEDIUM),
+            "low" => Some(Self::LOW),
+            _ => lower
+                .strip_prefix('p')
+                .and_then(|level| level.parse().ok())
+                .and_then(Self::from_display),
+        };
+        priority.ok_or_else(|| {
+            format!(
+                "Invalid priority '{}'. Use p1-p4 (p1 = urgent) or urgent/high/medium/low.",
+                s
+            )
+        })
+    }
+}
+
+/// Task duration, as Todoist stores it: an amount of `minute`s or `day`s
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Duration {
+    pub amount: u32,
+    pub unit: String,
+}
+
+impl Duration {
+    pub fn minutes(amount: u32) -> Self {
+        Self {
+            amount,
+            unit: "minute".to_string(),
+        }
+    }
+
+    pub fn days(amount: u32) -> Self {
+        Self {
+            amount,
+            unit: "day".to_string(),
+        }
+    }
+
+    /// Length in minutes (a day counts as 24 hours)
+    pub fn as_minutes(&self) -> i64 {
+        match self.unit.as_str() {
+            "day" => self.amount as i64 * 24 * 60,
+            _ => self.amount as i64,
+        }
+    }
+}
+
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.unit.as_str(), self.amount / 60, self.amount % 60) {
+            ("day", _, _) => write!(f, "{}d", self.amount),
+            (_, 0, m) => write!(f, "{}m", m),
+            (_, h, 0) => write!(f, "{}h", h),
+            (_, h, m) => write!(f, "{}h{}m", h, m),
+        }
+    }
+}
+
+/// Accepts `45m`, `2h`, `1h30m`, `90` (minutes) or `2d`. After hours, the
+/// minutes must be below 60.
+impl FromStr for Duration {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            format!(
+                "Invalid duration '{}'. Use minutes or hours like 45m, 2h, 1h30m, or days like 2d.",
+                s
+            )
+        };
+        let lower = s.trim().to_lowercase();
+        if let Some(days) = lower.strip_suffix('d') {
+            return match days.parse() {
+                Ok(n) if n > 0 => Ok(Self::days(n)),
+                _ => Err(invalid()),
+            };
+        }
+        let (hours, rest) = match lower.split_once('h') {
+            Some((h, rest)) => (h.parse::<u32>().map_err(|_| invalid())?, rest),
+            None => (0, lower.as_str()),
+        };
+        let rest = rest
+            .strip_suffix("min")
+            .or(rest.strip_suffix('m'))
+            .unwrap_or(rest);
+        let minutes = match rest {
+            "" => 0,
+            m => m.parse::<u32>().map_err(|_| invalid())?,
+        };
+        if lower.contains('h') && minutes >= 60 {
+            return Err(invalid());
+        }
+        match hours.checked_mul(60).and_then(|h| h.checked_add(minutes)) {
+            None | Some(0) => Err(invalid()),
+            Some(total) => Ok(Self::minutes(total)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Project {
     pub id: String,
     pub name: String,
+    /// Parent project for nested projects
+    #[serde(default)]
+    pub parent_id: Option<String>,
     pub color: String,
     pub is_shared: bool,
     pub is_favorite: bool,
+    /// "list", "board" or "calendar"
+    #[serde(default)]
+    pub view_style: String,
+    #[serde(default)]
+    pub is_inbox_project: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,9 +217,13 @@ pub struct Task {
     pub description: Option<String>,
     pub project_id: Option<String>,
     #[serde(default)]
+    pub section_id: Option<String>,
+    #[serde(default)]
     pub due: Option<Due>,
     #[serde(alias = "checked")]
     pub is_completed: bool,
+    #[serde(default)]
+    pub completed_at: Option<String>,
     #[serde(alias = "added_at")]
     pub created_at: String,
     #[serde(alias = "child_order")]
@@ -32,6 +231,10 @@ pub struct Task {
     pub priority: u8,
     #[serde(default)]
     pub labels: Vec<String>,
+    #[serde(default)]
+    pub responsible_uid: Option<String>,
+    #[serde(default)]
+    pub duration: Option<Duration>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,21 +242,73 @@ pub struct Due {
     pub date: Option<String>,
     pub is_recurring: Option<bool>,
     pub datetime: Option<String>,
+    /// Human-readable due date, e.g. "every monday"
+    #[serde(default)]
+    pub string: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct TaskOutput {
     pub id: String,
     pub content: String,
     pub description: Option<String>,
     pub project_id: Option<String>,
     pub project_name: Option<String>,
+    pub section_id: Option<String>,
+    pub section_name: Option<String>,
     pub due_date: Option<String>,
+    /// Due date and time for timed tasks
+    pub due_datetime: Option<String>,
+    /// Due date as entered, e.g. "every monday"
+    pub due_string: Option<String>,
+    pub is_recurring: bool,
     pub is_completed: bool,
+    pub completed_at: Option<String>,
     pub created_at: String,
     pub order: i32,
-    pub priority: u8,
+    /// API priority value (4 = most urgent)
+    pub priority: Priority,
+    /// Priority as shown in the Todoist UI ("p1" = most urgent)
+    pub priority_label: String,
     pub labels: Vec<String>,
+    /// Collaborator the task is assigned to
+    pub responsible_uid: Option<String>,
+    /// Assignee's name (or email when the name is unknown)
+    pub assignee: Option<String>,
+    pub duration: Option<Duration>,
+}
+
+impl TaskOutput {
+    pub fn from_task(task: Task, project_name: Option<String>) -> Self {
+        let priority = Priority::from_api(task.priority).unwrap_or_default();
+        Self {
+            id: task.id,
+            content: task.content,
+            description: task.description,
+            project_id: task.project_id,
+            project_name,
+            section_id: task.section_id,
+            section_name: None,
+            due_date: task.due.as_ref().and_then(|d| d.date.clone()),
+            due_datetime: task.due.as_ref().and_then(|d| d.datetime.clone()),
+            due_string: task.due.as_ref().and_then(|d| d.string.clone()),
+            is_recurring: task
+                .due
+                .as_ref()
+                .and_then(|d| d.is_recurring)
+                .unwrap_or(false),
+            is_completed: task.is_completed,
+            completed_at: task.completed_at,
+            created_at: task.created_at,
+            order: task.order,
+            priority,
+            priority_label: priority.to_string(),
+            labels: task.labels,
+            responsible_uid: task.responsible_uid,
+            assignee: None,
+            duration: task.duration,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -63,6 +318,17 @@ pub struct Filter {
     pub query: String,
 }
 
+/// A user who shares projects with the current user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Collaborator {
+    pub id: String,
+    pub name: String,
+    pub email: String,
+    /// Membership state in the requested project ("active" or "invited")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct SyncResponse {
     pub filters: Vec<Filter>,
@@ -77,3 +343,71 @@ pub struct ProjectsResponse {
 pub struct TasksResponse {
     pub results: Vec<Task>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_priority_api_and_display_are_inverted() {
+        assert_eq!(Priority::URGENT.api_value(), 4);
+        assert_eq!(Priority::URGENT.display_level(), 1);
+        assert_eq!(Priority::URGENT.to_string(), "p1");
+        assert_eq!(Priority::LOW.to_string(), "p4");
+        assert_eq!(Priority::from_display(2), Some(Priority::HIGH));
+    }
+
+    #[test]
+    fn test_priority_from_api_range() {
+        assert!(Priority::from_api(1).is_some());
+        assert!(Priority::from_api(4).is_some());
+        assert!(Priority::from_api(0).is_none());
+        assert!(Priority::from_api(5).is_none());
+    }
+
+    #[test]
+    fn test_priority_from_str() {
+        assert_eq!("p1".parse::<Priority>(), Ok(Priority::URGENT));
+        assert_eq!("P3".parse::<Priority>(), Ok(Priority::MEDIUM));
+        assert_eq!("high".parse::<Priority>(), Ok(Priority::HIGH));
+        assert_eq!("Low".parse::<Priority>(), Ok(Priority::LOW));
+        // Bare numbers are ambiguous
+        let err = "1".parse::<Priority>().unwrap_err();
+        assert!(err.contains("Use p1-p4"), "{}", err);
+        assert!("4".parse::<Priority>().is_err());
+        assert!("p5".parse::<Priority>().is_err());
+        assert!("0".parse::<Priority>().is_err());
+        assert!("soon".parse::<Priority>().is_err());
+    }
+
+    #[test]
+    fn test_duration_from_str_and_display() {
+        assert_eq!("45m".parse::<Duration>(), Ok(Duration::minutes(45)));
+        assert_eq!("90".parse::<Duration>(), Ok(Duration::minutes(90)));
+        assert_eq!("1h30m".parse::<Duration>(), Ok(Duration::minutes(90)));
+        assert_eq!("2H".parse::<Duration>(), Ok(Duration::minutes(120)));
+        assert_eq!("2d".parse::<Duration>(), Ok(Duration::days(2)));
+        assert!("0m".parse::<Duration>().is_err());
+        assert!("soon".parse::<Duration>().is_err());
+        assert!("1h90m".parse::<Duration>().is_err());
+        assert!("99999999h".parse::<Duration>().is_err());
+        assert!("71582789h".parse::<Duration>().is_err());
+        assert!("71582788h16m".parse::<Duration>().is_err());
+        assert_eq!("1h59m".parse::<Duration>(), Ok(Duration::minutes(119)));
+        assert_eq!(Duration::minutes(90).to_string(), "1h30m");
+        assert_eq!(Duration::minutes(45).to_string(), "45m");
+        assert_eq!(Duration::days(1).as_minutes(), 1440);
+    }
+
+    #[test]
+    fn test_priority_serializes_as_api_value() {
+        assert_eq!(serde_json::to_string(&Priority::URGENT).unwrap(), "4");
+        let p: Priority = serde_json::from_str("2").unwrap();
+        assert_eq!(p, Priority::MEDIUM);
+        let err = serde_json::from_str::<Priority>("7")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("invalid priority 7"), "{}", err);
+        assert!(serde_json::from_str::<Priority>("0").is_err());
+    }
+}
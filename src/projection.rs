@@ -0,0 +1,288 @@
+//! # Field Projection
+//!
+//! Parses `--fields` expressions and applies them to JSON rows:
+//!
+//! - `id,content`: keeps these fields, in this order
+//! - `duration.amount`, `labels[0]`: dotted paths and array indexes (`[-1]`
+//!   is the last item), written under the path as given; a path also reaches
+//!   flattened fields, so `due.date` reads `due_date`
+//! - `title:content`: writes `content` as `title`
+//! - `-description`: leaves a field out; with only exclusions, every other
+//!   field is kept
+//!
+//! Names that match no field of any row are reported by [`Projection::unknown`],
+//! so typos are not dropped silently.
+
+use serde_json::{Map, Value};
+
+/// One `--fields` item: the output name and the path it reads
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub name: String,
+    path: Vec<String>,
+}
+
+impl Field {
+    fn parse(name: &str, path: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            path: path
+                .replace('[', ".")
+                .replace(']', "")
+                .split('.')
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Projection {
+    include: Vec<Field>,
+    exclude: Vec<Field>,
+}
+
+impl Projection {
+    pub fn parse(spec: &str) -> Self {
+        let mut projection = Self::default();
+        for item in spec.split(',').map(str::trim).filter(|i| !i.is_empty()) {
+            if let Some(path) = item.strip_prefix('-') {
+                projection.exclude.push(Field::parse(path, path));
+            } else if let Some((name, path)) = item.split_once(':') {
+                projection
+                    .include
+                    .push(Field::parse(name.trim(), path.trim()));
+            } else {
+                projection.include.push(Field::parse(item, item));
+            }
+        }
+        projection
+    }
+
+    /// Output names of the kept fields, in order; `None` when only
+    /// exclusions were given
+    pub fn columns(&self) -> Option<Vec<&str>> {
+        (!self.include.is_empty()).then(|| self.include.iter().map(|f| f.name.as_str()).collect())
+    }
+
+    /// Whether `name` is an excluded top-level field
+    pub fn excludes(&self, name: &str) -> bool {
+        self.exclude.iter().any(|f| f.path == [name])
+    }
+
+    /// Projects an object, or each object of an array; other values are kept
+    pub fn apply(&self, value: Value) -> Value {
+        match value {
+            Value::Array(items) => Value::Array(items.into_iter().map(|i| self.apply(i)).collect()),
+            Value::Object(map) => {
+                let mut value = if self.include.is_empty() {
+                    Value::Object(map)
+                } else {
+                    let source = Value::Object(map);
+                    let mut projected = Map::new();
+                    for field in &self.include {
+                        if let Some(found) = get(&source, &field.path) {
+                            projected.insert(field.name.clone(), found.clone());
+                        }
+                    }
+                    Value::Object(projected)
+                };
+                for field in &self.exclude {
+                    remove(&mut value, &field.path);
+                }
+                value
+            }
+            other => other,
+        }
+    }
+
+    /// Names matching no field of `rows` (an array or a single object).
+    /// Nothing is reported without rows to compare with.
+    pub fn unknown(&self, rows: &Value) -> Vec<&str> {
+        let rows = match rows {
+            Value::Array(rows) => rows.as_slice(),
+            row => std::slice::from_ref(row),
+        };
+        if rows.is_empty() {
+            return Vec::new();
+        }
+        let found = |path: &[String]| rows.iter().any(|row| get(row, path).is_some());
+        let mut unknown = Vec::new();
+        for field in &self.include {
+            if !found(&field.path) {
+                unknown.push(field.name.as_str());
+            }
+        }
+        for field in &self.exclude {
+            // Exclusions may also refer to renamed fields
+            let renamed = self
+                .include
+                .iter()
+                .any(|f| field.path.first() == Some(&f.name));
+            if !renamed && !found(&field.path) {
+                unknown.push(field.name.as_str());
+            }
+        }
+        unknown
+    }
+}
+
+/// Index into an array; negative indexes count from the end
+fn index(items: &[Value], segment: &str) -> Option<usize> {
+    let index: i64 = segment.parse().ok()?;
+    let index = if index < 0 {
+        items.len() as i64 + index
+    } else {
+        index
+    };
+    usize::try_from(index).ok().filter(|&i| i < items.len())
+}
+
+fn get<'a>(value: &'a Value, path: &[String]) -> Option<&'a Value> {
+    let (segment, rest) = path.split_first()?;
+    let child = match value {
+        Value::Object(map) => map.get(segment),
+        Value::Array(items) => index(items, segment).map(|i| &items[i]),
+        _ => None,
+    };
+    match child {
+        Some(child) if rest.is_empty() => Some(child),
+        Some(child) => get(child, rest),
+        // `due.date` falls back to a flattened `due_date`
+        None => match (value, rest.split_first()) {
+            (Value::Object(map), Some((next, rest))) => {
+                let flattened = format!("{}_{}", segment, next);
+                let child = map.get(&flattened)?;
+                if rest.is_empty() {
+                    Some(child)
+                } else {
+                    get(child, rest)
+                }
+            }
+            _ => None,
+        },
+    }
+}
+
+fn remove(value: &mut Value, path: &[String]) {
+    let Some((segment, rest)) = path.split_first() else {
+        return;
+    };
+    match value {
+        Value::Object(map) if rest.is_empty() => {
+            map.shift_remove(segment);
+        }
+        Value::Object(map) if map.contains_key(segment) => {
+            remove(&mut map[segment], rest);
+        }
+        // `due.date` falls back to a flattened `due_date`
+        Value::Object(map) => {
+            let flattened = format!("{}_{}", segment, rest[0]);
+            if rest.len() == 1 {
+                map.shift_remove(&flattened);
+            } else if let Some(child) = map.get_mut(&flattened) {
+                remove(child, &rest[1..]);
+            }
+        }
+        Value::Array(items) => {
+            if let Some(i) = index(items, segment) {
+                if rest.is_empty() {
+                    items.remove(i);
+                } else {
+                    remove(&mut items[i], rest);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn task() -> Value {
+        json!({
+            "id": "1",
+            "content": "Write report",
+            "description": "Q1 numbers",
+            "labels": ["deep", "work"],
+            "duration": {"amount": 45, "unit": "minute"}
+        })
+    }
+
+    #[test]
+    fn test_paths_indexes_and_aliases() {
+        let projection = Projection::parse("id, title:content, duration.amount, labels[-1]");
+        assert_eq!(
+            projection.apply(task()),
+            json!({"id": "1", "title": "Write report", "duration.amount": 45, "labels[-1]": "work"})
+        );
+        assert_eq!(
+            projection.columns().unwrap(),
+            ["id", "title", "duration.amount", "labels[-1]"]
+        );
+        assert_eq!(
+            Projection::parse("labels.0").apply(task()),
+            json!({"labels.0": "deep"})
+        );
+    }
+
+    #[test]
+    fn test_exclusions() {
+        let projection = Projection::parse("-description,-duration.unit,-labels[0]");
+        assert_eq!(projection.columns(), None);
+        assert!(projection.excludes("description"));
+        assert_eq!(
+            projection.apply(json!([task()])),
+            json!([{"id": "1", "content": "Write report", "labels": ["work"],
+                    "duration": {"amount": 45}}])
+        );
+
+        // Exclusions apply to the kept fields
+        let projection = Projection::parse("id,d:duration,-d.unit");
+        assert_eq!(
+            projection.apply(task()),
+            json!({"id": "1", "d": {"amount": 45}})
+        );
+        assert!(projection.unknown(&task()).is_empty());
+    }
+
+    #[test]
+    fn test_flattened_paths() {
+        let task: crate::models::Task = serde_json::from_value(json!({
+            "id": "1",
+            "content": "Write report",
+            "due": {"date": "2026-01-15", "string": "jan 15"},
+            "is_completed": false,
+            "created_at": "2026-01-01T00:00:00Z",
+            "order": 1,
+            "priority": 1
+        }))
+        .unwrap();
+        let row = serde_json::to_value(crate::models::TaskOutput::from_task(task, None)).unwrap();
+
+        let projection = Projection::parse("id,title:content,due.date,due.string");
+        assert!(projection.unknown(&row).is_empty());
+        assert_eq!(
+            projection.apply(row.clone()),
+            json!({"id": "1", "title": "Write report", "due.date": "2026-01-15",
+                   "due.string": "jan 15"})
+        );
+        let projected = Projection::parse("-due.date").apply(row);
+        assert!(projected.get("due_date").is_none());
+        assert_eq!(projected["due_string"], "jan 15");
+    }
+
+    #[test]
+    fn test_unknown_fields() {
+        let projection = Projection::parse("id,titel,due.date,-descripton,labels[5]");
+        assert_eq!(
+            projection.unknown(&json!([task()])),
+            ["titel", "due.date", "labels[5]", "descripton"]
+        );
+        assert!(projection.unknown(&json!([])).is_empty());
+    }
+}
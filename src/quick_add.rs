@@ -0,0 +1,338 @@
+//! # Quick Add
+//!
+//! Parses Todoist-style quick-add text such as
+//! `Buy milk tomorrow #Errands @store p1 /Groceries` into its parts:
+//!
+//! - `#Project` - project name
+//! - `/Section` - section name
+//! - `@label` - labels (repeatable)
+//! - `p1`..`p4` - priority as shown in the Todoist UI (`p1` is most urgent)
+//! - a trailing date phrase (`today`, `next monday 9am`, `every day`, `2026-01-20`, ...) as
+//!   due string; the phrase must run to the end of the text, so date-like words
+//!   inside the content ("Write weekly report") stay content
+//!
+//! Everything else becomes the task content.
+
+use crate::error::{Result, TodoError};
+use crate::models::Priority;
+use crate::sync::ItemAddArgs;
+use serde::Serialize;
+
+/// Result of parsing a quick-add string
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct QuickAdd {
+    pub content: String,
+    pub project: Option<String>,
+    pub section: Option<String>,
+    pub labels: Vec<String>,
+    pub priority: Option<Priority>,
+    pub due_string: Option<String>,
+}
+
+impl QuickAdd {
+    /// Converts the parsed text into `item_add` arguments using resolved IDs
+    pub fn into_args(self, project_id: Option<String>, section_id: Option<String>) -> ItemAddArgs {
+        ItemAddArgs::new(self.content)
+            .project_id(project_id)
+            .section_id(section_id)
+            .due_string(self.due_string)
+            .priority(self.priority.map(Priority::api_value))
+            .labels(if self.labels.is_empty() {
+                None
+            } else {
+                Some(self.labels)
+            })
+    }
+}
+
+/// Full names only: abbreviations like "sun" or "sat" are common words
+const WEEKDAYS: [&str; 7] = [
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+];
+
+const MONTHS: [&str; 23] = [
+    "jan",
+    "feb",
+    "mar",
+    "apr",
+    "may",
+    "jun",
+    "jul",
+    "aug",
+    "sep",
+    "oct",
+    "nov",
+    "dec",
+    "january",
+    "february",
+    "march",
+    "april",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+/// Units of `in 3 weeks` and `every 2 days`
+const UNITS: [&str; 12] = [
+    "day", "days", "week", "weeks", "month", "months", "year", "years", "hour", "hours", "weekday",
+    "workday",
+];
+
+/// Parses quick-add text
+pub fn parse(text: &str) -> Result<QuickAdd> {
+    let mut parsed = QuickAdd::default();
+    let mut words: Vec<&str> = Vec::new();
+
+    for token in text.split_whitespace() {
+        if let Some(project) = sigil(token, '#') {
+            parsed.project = Some(project.to_string());
+        } else if let Some(label) = sigil(token, '@') {
+            parsed.labels.push(label.to_string());
+        } else if let Some(section) = sigil(token, '/') {
+            parsed.section = Some(section.to_string());
+        } else if let Some(priority) = parse_priority(token) {
+            parsed.priority = Some(priority);
+        } else {
+            words.push(token);
+        }
+    }
+
+    let due_start = (1..words.len()).find(|&i| is_due_phrase(&words[i..]));
+    let (content, due) = match due_start {
+        Some(i) => (&words[..i], Some(words[i..].join(" "))),
+        None => (&words[..], None),
+    };
+
+    parsed.content = content.join(" ");
+    parsed.due_string = due;
+
+    if parsed.content.is_empty() {
+        return Err(TodoError::InvalidInput(
+            "Quick add text has no task content".to_string(),
+        ));
+    }
+    Ok(parsed)
+}
+
+fn sigil(token: &str, prefix: char) -> Option<&str> {
+    token.strip_prefix(prefix).filter(|rest| !rest.is_empty())
+}
+
+/// Accepts only the UI form `p1`..`p4`, so bare numbers stay in the content
+fn parse_priority(token: &str) -> Option<Priority> {
+    token
+        .strip_prefix('p')
+        .or_else(|| token.strip_prefix('P'))?
+        .parse::<u8>()
+        .ok()
+        .and_then(Priority::from_display)
+}
+
+/// Whether `words` are exactly one date expression, optionally followed by a
+/// time (`9am`, `at 17:30`)
+fn is_due_phrase(words: &[&str]) -> bool {
+    let words: Vec<String> = words.iter().map(|w| w.to_lowercase()).collect();
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+    let rest = match words.as_slice() {
+        ["every", rest @ ..] => {
+            return !rest.is_empty() && rest.iter().all(|w| is_recurrence_word(w));
+        }
+        ["daily" | "weekly" | "monthly" | "yearly"] => return true,
+        ["today" | "tod" | "tomorrow" | "tom" | "tonight", rest @ ..] => rest,
+        ["next", "week" | "month" | "year", rest @ ..] => rest,
+        ["next", day, rest @ ..] if is_weekday(day) => rest,
+        ["in", count, unit] => return is_number(count) && UNITS.contains(unit),
+        [day, rest @ ..] if is_weekday(day) || is_iso_date(day) => rest,
+        [month, day, rest @ ..] if is_month(month) && is_day(day) => match rest {
+            [year, rest @ ..] if is_year(year) => rest,
+            rest => rest,
+        },
+        _ => return false,
+    };
+    match rest {
+        [] => true,
+        ["at", time] | [time] => is_time(time),
+        _ => false,
+    }
+}
+
+/// Words that can follow `every`: "every other week", "every 2 days at 9am",
+/// "every monday and friday", "every jan 5"
+fn is_recurrence_word(word: &str) -> bool {
+    let word = word.trim_end_matches(',');
+    matches!(word, "other" | "and" | "at")
+        || UNITS.contains(&word)
+        || is_weekday(word)
+        || is_month(word)
+        || is_number(word)
+        || is_time(word)
+}
+
+fn is_weekday(word: &str) -> bool {
+    WEEKDAYS.contains(&word)
+}
+
+fn is_month(word: &str) -> bool {
+    MONTHS.contains(&word)
+}
+
+fn is_number(word: &str) -> bool {
+    !word.is_empty() && word.chars().all(|c| c.is_ascii_digit())
+}
+
+fn is_day(word: &str) -> bool {
+    let day = word.trim_end_matches(',');
+    let day = ["st", "nd", "rd", "th"]
+        .iter()
+        .find_map(|s| day.strip_suffix(s))
+        .unwrap_or(day);
+    day.parse::<u8>().is_ok_and(|d| (1..=31).contains(&d))
+}
+
+fn is_year(word: &str) -> bool {
+    word.len() == 4 && is_number(word)
+}
+
+/// `9am`, `9:30pm`, `17:30`; a bare number is not a time
+fn is_time(word: &str) -> bool {
+    let (clock, meridiem) = match word.strip_suffix("am").or_else(|| word.strip_suffix("pm")) {
+        Some(clock) => (clock, true),
+        None => (word, false),
+    };
+    match clock.split_once(':') {
+        Some((hours, minutes)) => {
+            is_number(hours) && hours.len() <= 2 && is_number(minutes) && minutes.len() == 2
+        }
+        None => meridiem && is_number(clock) && clock.len() <= 2,
+    }
+}
+
+fn is_iso_date(word: &str) -> bool {
+    chrono::NaiveDate::parse_from_str(word, "%Y-%m-%d").is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_full_example() {
+        let parsed = parse("Buy milk tomorrow #Errands @store p1 /Groceries").unwrap();
+        assert_eq!(parsed.content, "Buy milk");
+        assert_eq!(parsed.due_string.as_deref(), Some("tomorrow"));
+        assert_eq!(parsed.project.as_deref(), Some("Errands"));
+        assert_eq!(parsed.section.as_deref(), Some("Groceries"));
+        assert_eq!(parsed.labels, vec!["store"]);
+        assert_eq!(parsed.priority, Some(Priority::URGENT));
+    }
+
+    #[test]
+    fn test_parse_plain_content() {
+        let parsed = parse("Call mom").unwrap();
+        assert_eq!(parsed.content, "Call mom");
+        assert!(parsed.due_string.is_none());
+        assert!(parsed.project.is_none());
+        assert!(parsed.priority.is_none());
+    }
+
+    #[test]
+    fn test_parse_priority_mapping() {
+        assert_eq!(parse("a p1").unwrap().priority, Some(Priority::URGENT));
+        assert_eq!(parse("a p2").unwrap().priority, Some(Priority::HIGH));
+        assert_eq!(parse("a P3").unwrap().priority, Some(Priority::MEDIUM));
+        assert_eq!(parse("a p4").unwrap().priority, Some(Priority::LOW));
+        // Out of range is kept as content
+        assert_eq!(parse("a p5").unwrap().content, "a p5");
+    }
+
+    #[test]
+    fn test_parse_multiple_labels() {
+        let parsed = parse("Review PR @work @urgent").unwrap();
+        assert_eq!(parsed.labels, vec!["work", "urgent"]);
+    }
+
+    #[test]
+    fn test_parse_due_phrases() {
+        let cases = [
+            ("Pay rent every month", "Pay rent", "every month"),
+            ("Standup next monday 9am", "Standup", "next monday 9am"),
+            ("Renew passport in 3 weeks", "Renew passport", "in 3 weeks"),
+            ("Dentist 2026-03-01", "Dentist", "2026-03-01"),
+            ("Birthday jan 5", "Birthday", "jan 5"),
+            ("Gym friday", "Gym", "friday"),
+            ("Call Bea tomorrow at 5pm", "Call Bea", "tomorrow at 5pm"),
+            (
+                "Water plants every other day",
+                "Water plants",
+                "every other day",
+            ),
+            ("Write report weekly", "Write report", "weekly"),
+            ("Conference march 3rd 2027", "Conference", "march 3rd 2027"),
+        ];
+        for (text, content, due) in cases {
+            let parsed = parse(text).unwrap();
+            assert_eq!(parsed.content, content, "content of {:?}", text);
+            assert_eq!(parsed.due_string.as_deref(), Some(due), "due of {:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_parse_ignores_ambiguous_words() {
+        let parsed = parse("Plan next steps in detail").unwrap();
+        assert_eq!(parsed.content, "Plan next steps in detail");
+        assert!(parsed.due_string.is_none());
+
+        let parsed = parse("Read and/or write").unwrap();
+        assert_eq!(parsed.content, "Read and/or write");
+        assert!(parsed.section.is_none());
+    }
+
+    #[test]
+    fn test_parse_date_words_inside_content() {
+        for text in [
+            "Write weekly report",
+            "Watch the sun set",
+            "Pay every bill",
+            "Fix the mars rover",
+            "Decide what may 3 people do",
+            "Go to the gym on sat",
+        ] {
+            let parsed = parse(text).unwrap();
+            assert_eq!(parsed.content, text);
+            assert!(parsed.due_string.is_none(), "due of {:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_parse_leading_date_word_stays_content() {
+        let parsed = parse("Today show tickets").unwrap();
+        assert_eq!(parsed.content, "Today show tickets");
+    }
+
+    #[test]
+    fn test_parse_empty_content_is_error() {
+        assert!(parse("#Work @home p2").is_err());
+        assert!(parse("   ").is_err());
+    }
+
+    #[test]
+    fn test_into_args() {
+        let args = parse("Buy milk @store p2")
+            .unwrap()
+            .into_args(Some("p1".to_string()), None);
+        assert_eq!(args.content, "Buy milk");
+        assert_eq!(args.project_id.as_deref(), Some("p1"));
+        assert_eq!(args.priority, Some(3));
+        assert_eq!(args.labels, Some(vec!["store".to_string()]));
+    }
+}
@@ -0,0 +1,408 @@
+//! # Name Resolution
+//!
+//! Resolves human-friendly project, section and label references to Todoist IDs
+//! using the synced resources, so commands can accept `--project "Work"` instead
+//! of raw IDs.
+//!
+//! A reference matches, in order of preference:
+//!
+//! 1. an exact ID
+//! 2. a case-insensitive exact name
+//! 3. an unambiguous case-insensitive name prefix
+//!
+//! Sections may be qualified with their project as `Project/Section`.
+//! Collaborators additionally match by exact (case-insensitive) email.
+
+use crate::error::{Result, TodoError};
+use crate::sync::{SyncCollaborator, SyncLabel, SyncProject, SyncSection, TodoistSyncClient};
+
+/// Lookup tables for resolving names to resources
+pub struct Resolver {
+    projects: Vec<SyncProject>,
+    sections: Vec<SyncSection>,
+    labels: Vec<SyncLabel>,
+}
+
+impl Resolver {
+    pub fn new(
+        projects: Vec<SyncProject>,
+        sections: Vec<SyncSection>,
+        labels: Vec<SyncLabel>,
+    ) -> Self {
+        Self {
+            projects: projects.into_iter().filter(|p| !p.is_deleted).collect(),
+            sections: sections.into_iter().filter(|s| !s.is_deleted).collect(),
+            labels: labels.into_iter().filter(|l| !l.is_deleted).collect(),
+        }
+    }
+
+    /// Builds a resolver from the cached projects, sections and labels
+    pub async fn load(client: &TodoistSyncClient) -> Result<Self> {
+        let response = client
+            .sync_with_cache(&["projects", "sections", "labels"])
+            .await?;
+        Ok(Self::new(
+            response.projects,
+            response.sections,
+            response.labels,
+        ))
+    }
+
+    pub fn projects(&self) -> &[SyncProject] {
+        &self.projects
+    }
+
+    pub fn sections(&self) -> &[SyncSection] {
+        &self.sections
+    }
+
+    pub fn labels(&self) -> &[SyncLabel] {
+        &self.labels
+    }
+
+    /// Resolves a project by ID or name
+    pub fn project(&self, query: &str) -> Result<&SyncProject> {
+        let candidates: Vec<&SyncProject> = self.projects.iter().collect();
+        find_match(
+            "project",
+            query,
+            &candidates,
+            |p| &p.id,
+            |p| &p.name,
+            |p| format!("{} ({})", p.name, p.id),
+        )
+    }
+
+    /// Resolves a section by ID, name or `Project/Section` path.
+    ///
+    /// When `project_id` is given, only sections of that project are considered,
+    /// also when matching by ID or by path. A section whose name contains `/`
+    /// is still found by its full name.
+    pub fn section(&self, query: &str, project_id: Option<&str>) -> Result<&SyncSection> {
+        if let Some(section) = self.sections.iter().find(|s| s.id == query) {
+            return match project_id {
+                Some(pid) if section.project_id != pid => Err(TodoError::InvalidInput(format!(
+                    "Section '{}' is not in project '{}'",
+                    query,
+                    self.project_name(pid)
+                ))),
+                _ => Ok(section),
+            };
+        }
+
+        let in_scope = |s: &SyncSection| project_id.is_none_or(|pid| s.project_id == pid);
+        let literal = self
+            .sections
+            .iter()
+            .any(|s| in_scope(s) && s.name.eq_ignore_ascii_case(query));
+        let (scope, name) = match query.split_once('/') {
+            Some((project, section)) if !literal => {
+                let project = self.project(project.trim())?;
+                if let Some(pid) = project_id.filter(|pid| *pid != project.id) {
+                    return Err(TodoError::InvalidInput(format!(
+                        "Section '{}' is not in project '{}'",
+                        query,
+                        self.project_name(pid)
+                    )));
+                }
+                (Some(project.id.as_str()), section.trim())
+            }
+            _ => (project_id, query),
+        };
+
+        let candidates: Vec<&SyncSection> = self
+            .sections
+            .iter()
+            .filter(|s| scope.map(|pid| s.project_id == pid).unwrap_or(true))
+            .collect();
+        find_match(
+            "section",
+            name,
+            &candidates,
+            |s| &s.id,
+            |s| &s.name,
+            |s| format!("{}/{} ({})", self.project_name(&s.project_id), s.name, s.id),
+        )
+    }
+
+    /// Resolves a label by ID or name
+    pub fn label(&self, query: &str) -> Result<&SyncLabel> {
+        let candidates: Vec<&SyncLabel> = self.labels.iter().collect();
+        find_match(
+            "label",
+            query,
+            &candidates,
+            |l| &l.id,
+            |l| &l.name,
+            |l| format!("{} ({})", l.name, l.id),
+        )
+    }
+
+    /// The user's Inbox, flagged by `inbox_project` in the sync data
+    pub fn inbox(&self) -> Result<&SyncProject> {
+        self.projects
+            .iter()
+            .find(|p| p.inbox_project)
+            .ok_or_else(|| TodoError::InvalidInput("No Inbox project found".to_string()))
+    }
+
+    fn project_name<'a>(&'a self, project_id: &'a str) -> &'a str {
+        self.projects
+            .iter()
+            .find(|p| p.id == project_id)
+            .map(|p| p.name.as_str())
+            .unwrap_or(project_id)
+    }
+}
+
+/// Resolves a collaborator by ID, email or full name
+pub fn collaborator<'a>(
+    collaborators: &'a [SyncCollaborator],
+    query: &str,
+) -> Result<&'a SyncCollaborator> {
+    if let Some(found) = collaborators
+        .iter()
+        .find(|c| !c.email.is_empty() && c.email.eq_ignore_ascii_case(query))
+    {
+        return Ok(found);
+    }
+    let candidates: Vec<&SyncCollaborator> = collaborators.iter().collect();
+    find_match(
+        "collaborator",
+        query,
+        &candidates,
+        |c| &c.id,
+        |c| &c.full_name,
+        |c| format!("{} <{}>", c.full_name, c.email),
+    )
+}
+
+fn find_match<'a, T>(
+    kind: &str,
+    query: &str,
+    candidates: &[&'a T],
+    id: impl Fn(&T) -> &str,
+    name: impl Fn(&T) -> &str,
+    describe: impl Fn(&T) -> String,
+) -> Result<&'a T> {
+    if let Some(item) = candidates.iter().find(|c| id(c) == query) {
+        return Ok(item);
+    }
+
+    let query_lower = query.to_lowercase();
+
+    let exact: Vec<&'a T> = candidates
+        .iter()
+        .copied()
+        .filter(|c| name(c).to_lowercase() == query_lower)
+        .collect();
+    let matches = if exact.is_empty() {
+        candidates
+            .iter()
+            .copied()
+            .filter(|c| name(c).to_lowercase().starts_with(&query_lower))
+            .collect()
+    } else {
+        exact
+    };
+
+    match matches.as_slice() {
+        [item] => Ok(item),
+        [] => Err(TodoError::InvalidInput(format!(
+            "No {} matches '{}'",
+            kind, query
+        ))),
+        _ => Err(TodoError::InvalidInput(format!(
+            "Ambiguous {} '{}'. Candidates: {}",
+            kind,
+            query,
+            matches
+                .iter()
+                .map(|c| describe(c))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(id: &str, name: &str) -> SyncProject {
+        serde_json::from_value(serde_json::json!({"id": id, "name": name})).unwrap()
+    }
+
+    fn section(id: &str, project_id: &str, name: &str) -> SyncSection {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "project_id": project_id,
+            "name": name,
+            "order": 1,
+            "created_at": "2024-01-01T00:00:00Z"
+        }))
+        .unwrap()
+    }
+
+    fn label(id: &str, name: &str) -> SyncLabel {
+        serde_json::from_value(serde_json::json!({"id": id, "name": name, "color": "red"})).unwrap()
+    }
+
+    fn resolver() -> Resolver {
+        Resolver::new(
+            vec![
+                project("p1", "Work"),
+                project("p2", "Workout"),
+                project("p3", "Personal"),
+            ],
+            vec![
+                section("s1", "p1", "Backlog"),
+                section("s2", "p3", "Backlog"),
+                section("s3", "p3", "Errands"),
+            ],
+            vec![label("l1", "urgent"), label("l2", "someday")],
+        )
+    }
+
+    #[test]
+    fn test_resolve_project_by_id() {
+        assert_eq!(resolver().project("p2").unwrap().name, "Workout");
+    }
+
+    #[test]
+    fn test_resolve_project_exact_name_beats_prefix() {
+        assert_eq!(resolver().project("work").unwrap().id, "p1");
+    }
+
+    #[test]
+    fn test_resolve_project_unique_prefix() {
+        assert_eq!(resolver().project("pers").unwrap().id, "p3");
+    }
+
+    #[test]
+    fn test_resolve_project_ambiguous_prefix() {
+        let err = resolver().project("wo").unwrap_err().to_string();
+        assert!(err.contains("Ambiguous project 'wo'"));
+        assert!(err.contains("Work (p1)"));
+        assert!(err.contains("Workout (p2)"));
+    }
+
+    #[test]
+    fn test_resolve_project_not_found() {
+        let err = resolver().project("Garden").unwrap_err().to_string();
+        assert!(err.contains("No project matches 'Garden'"));
+    }
+
+    #[test]
+    fn test_resolve_section_with_project_path() {
+        assert_eq!(resolver().section("Work/Backlog", None).unwrap().id, "s1");
+        assert_eq!(
+            resolver().section("personal/backlog", None).unwrap().id,
+            "s2"
+        );
+    }
+
+    #[test]
+    fn test_resolve_section_ambiguous_lists_projects() {
+        let err = resolver().section("Backlog", None).unwrap_err().to_string();
+        assert!(err.contains("Work/Backlog (s1)"));
+        assert!(err.contains("Personal/Backlog (s2)"));
+    }
+
+    #[test]
+    fn test_resolve_section_scoped_to_project() {
+        assert_eq!(resolver().section("back", Some("p3")).unwrap().id, "s2");
+        assert_eq!(
+            resolver().section("s3", Some("p3")).unwrap().name,
+            "Errands"
+        );
+        assert_eq!(
+            resolver()
+                .section("s3", Some("p1"))
+                .unwrap_err()
+                .to_string(),
+            "Invalid input: Section 's3' is not in project 'Work'"
+        );
+    }
+
+    #[test]
+    fn test_resolve_section_path_within_project() {
+        assert_eq!(
+            resolver()
+                .section("Personal/Errands", Some("p3"))
+                .unwrap()
+                .id,
+            "s3"
+        );
+        assert_eq!(
+            resolver()
+                .section("Personal/Errands", Some("p1"))
+                .unwrap_err()
+                .to_string(),
+            "Invalid input: Section 'Personal/Errands' is not in project 'Work'"
+        );
+
+        // A name containing the separator is matched as a whole
+        let resolver = Resolver::new(
+            vec![project("p1", "Work")],
+            vec![section("s1", "p1", "Q1/Doing")],
+            vec![],
+        );
+        assert_eq!(resolver.section("Q1/Doing", Some("p1")).unwrap().id, "s1");
+        assert_eq!(resolver.section("q1/doing", None).unwrap().id, "s1");
+    }
+
+    #[test]
+    fn test_resolve_label() {
+        assert_eq!(resolver().label("URG").unwrap().id, "l1");
+        assert!(resolver().label("missing").is_err());
+    }
+
+    #[test]
+    fn test_deleted_labels_are_ignored() {
+        let mut deleted = label("l9", "urgent-old");
+        deleted.is_deleted = true;
+        let resolver = Resolver::new(vec![], vec![], vec![label("l1", "urgent"), deleted]);
+        assert_eq!(
+            resolver.label("urgent-").unwrap_err().to_string(),
+            "Invalid input: No label matches 'urgent-'"
+        );
+        assert!(resolver.label("l9").is_err());
+    }
+
+    #[test]
+    fn test_deleted_projects_are_ignored() {
+        let mut deleted = project("p9", "Workshop");
+        deleted.is_deleted = true;
+        let resolver = Resolver::new(vec![project("p1", "Work"), deleted], vec![], vec![]);
+        assert_eq!(
+            resolver.project("works").unwrap_err().to_string(),
+            "Invalid input: No project matches 'works'"
+        );
+    }
+
+    #[test]
+    fn test_inbox_from_sync_flag() {
+        assert!(resolver().inbox().is_err());
+
+        let mut inbox = project("p0", "Eingang");
+        inbox.inbox_project = true;
+        let resolver = Resolver::new(vec![project("p1", "Work"), inbox], vec![], vec![]);
+        assert_eq!(resolver.inbox().unwrap().name, "Eingang");
+    }
+
+    #[test]
+    fn test_resolve_collaborator_by_email_or_name() {
+        let people: Vec<SyncCollaborator> = serde_json::from_value(serde_json::json!([
+            {"id": "u1", "email": "ana@example.com", "full_name": "Ana Lima"},
+            {"id": "u2", "email": "andre@example.com", "full_name": "Andre Costa"}
+        ]))
+        .unwrap();
+        assert_eq!(collaborator(&people, "ANDRE@example.com").unwrap().id, "u2");
+        assert_eq!(collaborator(&people, "ana lima").unwrap().id, "u1");
+        assert!(collaborator(&people, "An")
+            .unwrap_err()
+            .to_string()
+            .contains("Ambiguous collaborator"));
+    }
+}
@@ -15,6 +15,14 @@ pub struct CacheData {
     pub sections: Vec<super::models::SyncSection>,
     pub labels: Vec<super::models::SyncLabel>,
     pub filters: Vec<super::models::SyncFilter>,
+    #[serde(default)]
+    pub collaborators: Vec<super::models::SyncCollaborator>,
+    #[serde(default)]
+    pub collaborator_states: Vec<super::models::SyncCollaboratorState>,
+    #[serde(default)]
+    pub user: Option<super::models::SyncUser>,
+    #[serde(default)]
+    pub notes: Vec<super::models::SyncNote>,
 }
 
 pub struct CacheManager {
@@ -106,6 +114,7 @@ mod tests {
                 sections: vec![],
                 labels: vec![],
                 filters: vec![],
+                ..Default::default()
             },
         };
 
@@ -161,10 +170,13 @@ mod tests {
                 projects: vec![crate::sync::models::SyncProject {
                     id: "p1".to_string(),
                     name: "Test Project".to_string(),
+                    parent_id: None,
                     color: "red".to_string(),
                     shared: false,
                     favorite: true,
                     sort_order: 1,
+                    view_style: "list".to_string(),
+                    inbox_project: false,
                     is_archived: false,
                     is_deleted: false,
                     created_at: "2024-01-01T00:00:00Z".to_string(),
@@ -174,6 +186,7 @@ mod tests {
                 sections: vec![],
                 labels: vec![],
                 filters: vec![],
+                ..Default::default()
             },
         };
 
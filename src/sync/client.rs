@@ -1,4 +1,5 @@
 use std::cell::RefCell;
+use std::collections::HashMap;
 
 use reqwest::Client as HttpClient;
 
@@ -6,7 +7,13 @@ use crate::error::TodoError;
 
 use super::cache::{Cache, CacheData, CacheManager};
 use super::commands::{Command, CommandBuilder};
-use super::models::{SyncReadResponse, SyncWriteResponse};
+use super::journal::{inverse_commands, snapshot_covers, snapshot_resource_types, Journal};
+use super::models::{
+    Page, SyncCollaborator, SyncCollaboratorState, SyncProject, SyncReadResponse, SyncWriteResponse,
+};
+
+/// Todoist 单个 sync 请求最多接受的命令数
+pub const MAX_COMMANDS_PER_REQUEST: usize = 100;
 
 /// Cache status information
 #[derive(Debug, Clone, Default)]
@@ -52,6 +59,24 @@ pub struct TodoistSyncClient {
     cache_manager: CacheManager,
     cache: RefCell<Option<Cache>>,
     cache_ttl: u64,
+    dry_run: bool,
+    planned: RefCell<Vec<Command>>,
+    journal: Option<Journal>,
+}
+
+/// 分块执行的结果
+#[derive(Debug, Default)]
+pub struct ChunkedOutcome {
+    /// 已发送块的响应
+    pub responses: Vec<SyncWriteResponse>,
+    /// 已发送块中临时 ID 到真实 ID 的映射
+    pub temp_id_mapping: HashMap<String, String>,
+    /// 失败的命令 (uuid, 状态)
+    pub failures: Vec<(String, serde_json::Value)>,
+    /// 未发送命令的 uuid
+    pub unsent: Vec<String>,
+    /// 中断执行的请求错误 (包括 dry run)
+    pub error: Option<TodoError>,
 }
 
 impl TodoistSyncClient {
@@ -87,9 +112,37 @@ impl TodoistSyncClient {
             cache_manager: CacheManager::new(),
             cache: RefCell::new(None),
             cache_ttl,
+            dry_run: false,
+            planned: RefCell::new(Vec::new()),
+            journal: None,
         }
     }
 
+    /// Enables dry-run mode: write commands are recorded instead of sent.
+    ///
+    /// In this mode [`execute_commands`](Self::execute_commands) returns
+    /// [`TodoError::DryRun`]; the recorded commands are available from
+    /// [`take_planned_commands`](Self::take_planned_commands).
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Records every successful write and its inverse in `journal`
+    pub fn with_journal(mut self, journal: Journal) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// 取出 dry-run 模式下记录的命令
+    pub fn take_planned_commands(&self) -> Vec<Command> {
+        std::mem::take(&mut *self.planned.borrow_mut())
+    }
+
     #[cfg(test)]
     pub fn new_with_url(token: String, sync_url: String) -> Self {
         Self {
@@ -100,6 +153,9 @@ impl TodoistSyncClient {
             cache_manager: CacheManager::new(),
             cache: RefCell::new(None),
             cache_ttl: 300,
+            dry_run: false,
+            planned: RefCell::new(Vec::new()),
+            journal: None,
         }
     }
 
@@ -118,7 +174,9 @@ impl TodoistSyncClient {
                 .unwrap_or(0),
             data,
         };
-        self.cache_manager.save(&cache)
+        self.cache_manager.save(&cache)?;
+        *self.cache.borrow_mut() = Some(cache);
+        Ok(())
     }
 
     /// 检查缓存是否过期 (默认 5 分钟 = 300 秒)
@@ -234,6 +292,49 @@ impl TodoistSyncClient {
             .clone()
             .unwrap_or_else(|| "*".to_string());
 
+        let parsed = self.fetch(sync_token, resource_types).await?;
+
+        // Update sync token
+        self.set_sync_token(parsed.sync_token.clone());
+
+        // 保存到缓存
+        let data = CacheData {
+            projects: parsed.projects.clone(),
+            items: parsed.items.clone(),
+            sections: parsed.sections.clone(),
+            labels: parsed.labels.clone(),
+            filters: parsed.filters.clone(),
+            collaborators: parsed.collaborators.clone(),
+            collaborator_states: parsed.collaborator_states.clone(),
+            user: parsed.user.clone(),
+            notes: parsed.notes.clone(),
+        };
+        self.save_cache(&parsed.sync_token, data)?;
+
+        Ok(parsed)
+    }
+
+    /// 全量读取资源，不影响 sync_token 和缓存
+    pub async fn snapshot(&self, resource_types: &[&str]) -> Result<CacheData, TodoError> {
+        let parsed = self.fetch("*".to_string(), resource_types).await?;
+        Ok(CacheData {
+            projects: parsed.projects,
+            items: parsed.items,
+            sections: parsed.sections,
+            labels: parsed.labels,
+            filters: parsed.filters,
+            collaborators: parsed.collaborators,
+            collaborator_states: parsed.collaborator_states,
+            user: parsed.user,
+            notes: parsed.notes,
+        })
+    }
+
+    async fn fetch(
+        &self,
+        sync_token: String,
+        resource_types: &[&str],
+    ) -> Result<SyncReadResponse, TodoError> {
         tracing::debug!(sync_token = %sync_token, "Performing sync request");
 
         let response = self
@@ -260,23 +361,8 @@ impl TodoistSyncClient {
             return Err(TodoError::Http(status.as_u16()));
         }
 
-        let parsed: SyncReadResponse = serde_json::from_str(&body)
-            .map_err(|e| TodoError::Api(format!("Failed to parse sync response: {}", e)))?;
-
-        // Update sync token
-        self.set_sync_token(parsed.sync_token.clone());
-
-        // 保存到缓存
-        let data = CacheData {
-            projects: parsed.projects.clone(),
-            items: parsed.items.clone(),
-            sections: parsed.sections.clone(),
-            labels: parsed.labels.clone(),
-            filters: parsed.filters.clone(),
-        };
-        self.save_cache(&parsed.sync_token, data)?;
-
-        Ok(parsed)
+        serde_json::from_str(&body)
+            .map_err(|e| TodoError::Api(format!("Failed to parse sync response: {}", e)))
     }
 
     /// 执行命令（写入资源）
@@ -293,6 +379,62 @@ impl TodoistSyncClient {
         &self,
         commands: &[Command],
     ) -> Result<SyncWriteResponse, TodoError> {
+        let journal = match &self.journal {
+            Some(journal) if !self.dry_run => journal,
+            _ => return self.send_commands(commands).await,
+        };
+
+        let before = self.state_before(commands).await?;
+        let response = self.send_commands(commands).await?;
+
+        let succeeded: Vec<Command> = commands
+            .iter()
+            .filter(|c| response.sync_status.get(&c.uuid) == Some(&serde_json::json!("ok")))
+            .cloned()
+            .collect();
+        if !succeeded.is_empty() {
+            let (inverse, warnings) =
+                inverse_commands(&succeeded, &before, &response.temp_id_mapping);
+            if let Err(e) = journal.record(&succeeded, inverse, warnings) {
+                tracing::warn!(error = %e, "Failed to record operation in undo journal");
+            }
+        }
+
+        Ok(response)
+    }
+
+    /// 写入前的资源状态：优先使用缓存，缓存中缺少目标资源时才读取快照
+    async fn state_before(&self, commands: &[Command]) -> Result<CacheData, TodoError> {
+        let resource_types = snapshot_resource_types(commands);
+        if resource_types.is_empty() {
+            return Ok(CacheData::default());
+        }
+        match self.get_cached_data() {
+            Some(data) if snapshot_covers(commands, &data) => Ok(data),
+            _ => self.snapshot(&resource_types).await,
+        }
+    }
+
+    /// 执行命令但不写入撤销日志（用于 undo 本身）
+    pub async fn execute_untracked(
+        &self,
+        commands: &[Command],
+    ) -> Result<SyncWriteResponse, TodoError> {
+        let response = self.send_commands(commands).await?;
+        self.check_sync_status(&response)?;
+        Ok(response)
+    }
+
+    async fn send_commands(&self, commands: &[Command]) -> Result<SyncWriteResponse, TodoError> {
+        if self.dry_run {
+            tracing::debug!(
+                command_count = commands.len(),
+                "Dry run, not sending commands"
+            );
+            self.planned.borrow_mut().extend(commands.iter().cloned());
+            return Err(TodoError::DryRun);
+        }
+
         let sync_token = self
             .sync_token
             .borrow()
@@ -379,12 +521,120 @@ impl TodoistSyncClient {
         self.execute_commands_with_status(&commands).await
     }
 
+    /// 分块执行命令，每块最多 [`MAX_COMMANDS_PER_REQUEST`] 条
+    ///
+    /// Dry run 时一次性记录全部命令，以便预览完整的批次。
+    /// 前面块中创建的临时 ID 会在后续块中替换为真实 ID。
+    pub async fn execute_chunked(
+        &self,
+        builder: CommandBuilder,
+    ) -> Result<Vec<SyncWriteResponse>, TodoError> {
+        let outcome = self.run_chunks(builder, true).await;
+        if let Some(error) = outcome.error {
+            return Err(error);
+        }
+        if let Some((uuid, status)) = outcome.failures.first() {
+            return Err(TodoError::Api(format!(
+                "Command {} failed: {}",
+                uuid, status
+            )));
+        }
+        Ok(outcome.responses)
+    }
+
+    /// 分块执行命令，返回已完成块的响应与 ID 映射，调用方可据此保存部分结果
+    ///
+    /// `stop_on_failure` 为 false 时，失败的命令不会中断后续块。
+    pub async fn execute_chunked_partial(
+        &self,
+        builder: CommandBuilder,
+        stop_on_failure: bool,
+    ) -> ChunkedOutcome {
+        self.run_chunks(builder, stop_on_failure).await
+    }
+
+    async fn run_chunks(&self, builder: CommandBuilder, stop_on_failure: bool) -> ChunkedOutcome {
+        let mut commands = builder.build();
+        let mut outcome = ChunkedOutcome::default();
+        if self.dry_run {
+            outcome.error = self.execute_commands(&commands).await.err();
+            return outcome;
+        }
+        let mut sent = 0;
+        for chunk in commands.chunks_mut(MAX_COMMANDS_PER_REQUEST) {
+            for command in chunk.iter_mut() {
+                command.resolve_temp_ids(&outcome.temp_id_mapping);
+            }
+            let response = match self.execute_commands(chunk).await {
+                Ok(response) => response,
+                Err(e) => {
+                    outcome.error = Some(e);
+                    break;
+                }
+            };
+            let failures: Vec<_> = chunk
+                .iter()
+                .filter_map(|c| Some((c.uuid.clone(), response.sync_status.get(&c.uuid)?.clone())))
+                .filter(|(_, status)| status != "ok")
+                .collect();
+            outcome
+                .temp_id_mapping
+                .extend(response.temp_id_mapping.clone());
+            outcome.responses.push(response);
+            outcome.failures.extend(failures);
+            sent += chunk.len();
+            if stop_on_failure && !outcome.failures.is_empty() {
+                break;
+            }
+        }
+        outcome.unsent = commands[sent..].iter().map(|c| c.uuid.clone()).collect();
+        outcome
+    }
+
     // Resources: Read Methods
 
     /// 获取所有项目 (使用混合同步)
     pub async fn get_projects(&self) -> Result<Vec<crate::models::Project>, TodoError> {
-        let response = self.sync_with_cache(&["projects"]).await?;
-        Ok(response.projects.into_iter().map(Into::into).collect())
+        let mut projects = self.sync_with_cache(&["projects"]).await?.projects;
+        projects.retain(|p| !p.is_archived);
+        projects.sort_by_key(|p| p.sort_order);
+        Ok(projects.into_iter().map(Into::into).collect())
+    }
+
+    /// 获取已归档项目
+    ///
+    /// Sync 不返回已归档项目，需分页读取 `/projects/archived`。
+    pub async fn get_archived_projects(&self) -> Result<Vec<SyncProject>, TodoError> {
+        let url = format!(
+            "{}/projects/archived",
+            self.sync_url.trim_end_matches("/sync")
+        );
+        let mut projects = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut request = self
+                .http
+                .get(&url)
+                .header("Authorization", self.get_auth_header());
+            if let Some(cursor) = &cursor {
+                request = request.query(&[("cursor", cursor)]);
+            }
+            let response = request.send().await?;
+            let status = response.status();
+            let body = response.text().await?;
+            if !status.is_success() {
+                tracing::error!(status = %status, body = %body, "Archived projects request failed");
+                return Err(TodoError::Http(status.as_u16()));
+            }
+            let page: Page<SyncProject> = serde_json::from_str(&body)
+                .map_err(|e| TodoError::Api(format!("Failed to parse archived projects: {}", e)))?;
+            projects.extend(page.results);
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        Ok(projects)
     }
 
     /// 获取所有任务/项目 (使用混合同步)
@@ -411,6 +661,14 @@ impl TodoistSyncClient {
         Ok(response.filters)
     }
 
+    /// 获取协作者及其在各共享项目中的状态 (使用混合同步)
+    pub async fn get_collaborators(
+        &self,
+    ) -> Result<(Vec<SyncCollaborator>, Vec<SyncCollaboratorState>), TodoError> {
+        let response = self.sync_with_cache(&["collaborators"]).await?;
+        Ok((response.collaborators, response.collaborator_states))
+    }
+
     /// 获取项目和任务 (用于需要两者的场景，如 get_tasks handler)
     pub async fn get_projects_and_tasks(
         &self,
@@ -427,10 +685,12 @@ impl TodoistSyncClient {
     pub async fn add_project(
         &self,
         name: &str,
+        parent_id: Option<&str>,
         color: Option<&str>,
         favorite: Option<bool>,
     ) -> Result<String, TodoError> {
         let args = super::commands::ProjectAddArgs::new(name.to_string())
+            .parent_id(parent_id.map(|p| p.to_string()))
             .color(color.map(|c| c.to_string()))
             .favorite(favorite);
 
@@ -458,6 +718,8 @@ impl TodoistSyncClient {
         due_string: Option<&str>,
         priority: Option<u8>,
         labels: Option<Vec<&str>>,
+        responsible_uid: Option<&str>,
+        duration: Option<crate::models::Duration>,
     ) -> Result<String, TodoError> {
         let args = super::commands::ItemAddArgs::new(content.to_string())
             .description(description.map(|d| d.to_string()))
@@ -465,7 +727,9 @@ impl TodoistSyncClient {
             .section_id(section_id.map(|s| s.to_string()))
             .due_string(due_string.map(|d| d.to_string()))
             .priority(priority)
-            .labels(labels.map(|ls| ls.iter().map(|&s| s.to_string()).collect()));
+            .labels(labels.map(|ls| ls.iter().map(|&s| s.to_string()).collect()))
+            .responsible_uid(responsible_uid.map(|r| r.to_string()))
+            .duration(duration.map(Into::into));
 
         let builder = CommandBuilder::new().item_add(args);
 
@@ -480,7 +744,8 @@ impl TodoistSyncClient {
             .cloned()
     }
 
-    /// 更新任务 (使用 Sync API)
+    /// 更新任务 (使用 Sync API)，`responsible_uid` 为 `Some(None)` 时取消指派
+    #[allow(clippy::too_many_arguments)]
     pub async fn update_task(
         &self,
         id: &str,
@@ -489,13 +754,17 @@ impl TodoistSyncClient {
         priority: Option<u8>,
         due_string: Option<&str>,
         labels: Option<Vec<&str>>,
+        responsible_uid: Option<Option<&str>>,
+        duration: Option<crate::models::Duration>,
     ) -> Result<(), TodoError> {
         let args = super::commands::ItemUpdateArgs::new(id.to_string())
             .content(content.map(|c| c.to_string()))
             .description(description.map(|d| d.to_string()))
             .priority(priority)
             .due_string(due_string.map(|d| d.to_string()))
-            .labels(labels.map(|ls| ls.iter().map(|&s| s.to_string()).collect()));
+            .labels(labels.map(|ls| ls.iter().map(|&s| s.to_string()).collect()))
+            .responsible_uid(responsible_uid.map(|r| r.map(str::to_string)))
+            .duration(duration.map(|d| Some(d.into())));
 
         let builder = CommandBuilder::new().item_update(args);
 
@@ -573,6 +842,19 @@ impl TodoistSyncClient {
         Ok(())
     }
 
+    /// 重新排序同级项目 (使用 Sync API)
+    pub async fn reorder_projects(&self, projects: &[(&str, i64)]) -> Result<(), TodoError> {
+        let project_args: Vec<super::commands::ProjectOrderArgs> = projects
+            .iter()
+            .map(|(id, order)| super::commands::ProjectOrderArgs::new(id.to_string(), *order))
+            .collect();
+
+        let builder = CommandBuilder::new().project_reorder(&project_args);
+
+        self.execute(builder).await?;
+        Ok(())
+    }
+
     /// 添加标签 (使用 Sync API)
     pub async fn add_label(&self, name: &str, color: Option<&str>) -> Result<String, TodoError> {
         let args = super::commands::LabelAddArgs::new(name.to_string())
@@ -674,6 +956,7 @@ impl TodoistSyncClient {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sync::{CommandKind, ItemAddArgs};
     use httpmock::{HttpMockResponse, Method, MockServer};
     use serial_test::serial;
     use tokio::test;
@@ -915,9 +1198,8 @@ mod tests {
             TodoistSyncClient::new_with_url("test_token".to_string(), server.url("/api/v1/sync"));
 
         let commands = vec![Command {
-            type_: "item_add".to_string(),
+            kind: CommandKind::ItemAdd(ItemAddArgs::new("Test task".to_string())),
             uuid: "temp_123".to_string(),
-            args: serde_json::json!({"content": "Test task"}),
             temp_id: Some("temp_123".to_string()),
         }];
 
@@ -1011,7 +1293,7 @@ mod tests {
         let client =
             TodoistSyncClient::new_with_url("test".to_string(), server.url("/api/v1/sync"));
         let id = client
-            .add_task("New task", None, None, None, None, None, None)
+            .add_task("New task", None, None, None, None, None, None, None, None)
             .await
             .unwrap();
         assert_eq!(id, "real_1");
@@ -1110,11 +1392,114 @@ mod tests {
         let client =
             TodoistSyncClient::new_with_url("test".to_string(), server.url("/api/v1/sync"));
         let result = client
-            .update_task("task_1", Some("new content"), None, None, None, None)
+            .update_task(
+                "task_1",
+                Some("new content"),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
             .await;
         assert!(result.is_ok(), "Update task should succeed");
     }
 
+    #[tokio::test]
+    async fn test_execute_commands_records_inverse_in_journal() {
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method(Method::POST)
+                .path("/api/v1/sync")
+                .body_includes("commands");
+            then.status(200).json_body(serde_json::json!({
+                "sync_token": "token",
+                "sync_status": {"uuid1": "ok"}
+            }));
+        });
+        let snapshot = server.mock(|when, then| {
+            when.method(Method::POST)
+                .path("/api/v1/sync")
+                .body_includes("resource_types");
+            then.status(200).json_body(serde_json::json!({
+                "sync_token": "token",
+                "items": [{"id": "t1", "content": "Keep me", "priority": 2}]
+            }));
+        });
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let client =
+            TodoistSyncClient::new_with_url("test".to_string(), server.url("/api/v1/sync"))
+                .with_journal(Journal::with_path(temp_dir.path().join("journal.json")));
+        let commands = vec![Command {
+            kind: CommandKind::ItemDelete(crate::sync::IdArgs::new("t1")),
+            uuid: "uuid1".to_string(),
+            temp_id: None,
+        }];
+        client
+            .execute_commands_with_status(&commands)
+            .await
+            .unwrap();
+        snapshot.assert_calls(1);
+
+        let entries = Journal::with_path(temp_dir.path().join("journal.json"))
+            .load()
+            .unwrap();
+        assert_eq!(entries.len(), 1);
+        match &entries[0].inverse[0].kind {
+            CommandKind::ItemAdd(args) => assert_eq!(args.content, "Keep me"),
+            other => panic!("unexpected inverse {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_commands_uses_cache_for_inverse() {
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method(Method::POST)
+                .path("/api/v1/sync")
+                .body_includes("commands");
+            then.status(200).json_body(serde_json::json!({
+                "sync_token": "token",
+                "sync_status": {"uuid1": "ok"}
+            }));
+        });
+        let read = server.mock(|when, then| {
+            when.method(Method::POST)
+                .path("/api/v1/sync")
+                .body_includes("resource_types");
+            then.status(200).json_body(serde_json::json!({
+                "sync_token": "token",
+                "items": [{"id": "t1", "content": "Keep me", "priority": 2}]
+            }));
+        });
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let client =
+            TodoistSyncClient::new_with_url("test".to_string(), server.url("/api/v1/sync"))
+                .with_journal(Journal::with_path(temp_dir.path().join("journal.json")));
+        client.sync(&["items"]).await.unwrap();
+        let commands = vec![Command {
+            kind: CommandKind::ItemDelete(crate::sync::IdArgs::new("t1")),
+            uuid: "uuid1".to_string(),
+            temp_id: None,
+        }];
+        client
+            .execute_commands_with_status(&commands)
+            .await
+            .unwrap();
+        // Only the initial sync reads resources
+        read.assert_calls(1);
+
+        let entries = Journal::with_path(temp_dir.path().join("journal.json"))
+            .load()
+            .unwrap();
+        assert!(
+            matches!(&entries[0].inverse[0].kind, CommandKind::ItemAdd(args) if args.content == "Keep me")
+        );
+    }
+
     #[tokio::test]
     async fn test_execute_commands_with_status() {
         let server = MockServer::start_async().await;
@@ -1136,9 +1521,8 @@ mod tests {
         let client =
             TodoistSyncClient::new_with_url("test".to_string(), server.url("/api/v1/sync"));
         let commands = vec![Command {
-            type_: "item_add".to_string(),
+            kind: CommandKind::ItemAdd(ItemAddArgs::new("test".to_string())),
             uuid: "uuid1".to_string(),
-            args: serde_json::json!({"content": "test"}),
             temp_id: None,
         }];
         let result = client.execute_commands_with_status(&commands).await;
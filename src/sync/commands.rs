@@ -6,7 +6,12 @@
  * ## Commands
  *
  * Commands are used to perform write operations through the Sync API.
- * The [`CommandBuilder`] provides a fluent API for constructing commands.
+ * Every command is a [`CommandKind`] variant with typed arguments, serialized to
+ * the wire format `{"type": ..., "uuid": ..., "temp_id": ..., "args": {...}}`.
+ * The [`CommandBuilder`] provides a fluent API for constructing commands, and
+ * [`parse_batch`] validates user supplied JSON before anything is sent and
+ * rejects unknown command types and fields. [`parse_batch_allowing_unknown`]
+ * keeps such commands as [`RawCommand`]s and sends them unchanged.
  *
  * ## Example
  *
@@ -20,24 +25,38 @@
  * ```
  */
 
-use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
 use uuid::Uuid;
 
 use crate::error::TodoError;
 
 /// Sync API 命令结构
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct Command {
-    #[serde(rename = "type")]
-    pub type_: String,
-    #[serde(default = "Command::generate_uuid")]
+    #[serde(flatten)]
+    pub kind: CommandKind,
+    /// Generated when omitted
+    #[schemars(with = "Option<String>")]
     pub uuid: String,
+    /// Temporary ID that later commands in the same batch can reference
     #[serde(skip_serializing_if = "Option::is_none")]
     pub temp_id: Option<String>,
-    pub args: serde_json::Value,
 }
 
 impl Command {
+    /// 创建命令，创建类命令自动分配临时 ID
+    pub fn new(kind: CommandKind) -> Self {
+        let temp_id = kind.creates_resource().then(Self::generate_temp_id);
+        Self {
+            kind,
+            uuid: Self::generate_uuid(),
+            temp_id,
+        }
+    }
+
     /// 生成命令 UUID
     pub fn generate_uuid() -> String {
         Uuid::new_v4().to_string()
@@ -47,6 +66,353 @@ impl Command {
     pub fn generate_temp_id() -> String {
         Uuid::new_v4().to_string()
     }
+
+    /// 将参数中的临时 ID 替换为已分配的真实 ID
+    pub fn resolve_temp_ids(&mut self, temp_id_mapping: &HashMap<String, String>) {
+        if temp_id_mapping.is_empty() {
+            return;
+        }
+        let Ok(mut value) = serde_json::to_value(&self.kind) else {
+            return;
+        };
+        if let Some(args) = value.get_mut("args") {
+            replace_ids(args, temp_id_mapping);
+        }
+        if let Ok(kind) = serde_json::from_value(value) {
+            self.kind = kind;
+        }
+    }
+
+    /// Wire name of the command, e.g. `item_add`
+    pub fn command_type(&self) -> &str {
+        self.kind.command_type()
+    }
+}
+
+/// Wire representation used to give precise errors for batch input
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WireCommand {
+    #[serde(rename = "type")]
+    type_: String,
+    #[serde(default = "Command::generate_uuid")]
+    uuid: String,
+    #[serde(default)]
+    temp_id: Option<String>,
+    args: serde_json::Value,
+}
+
+impl WireCommand {
+    fn into_command(self, allow_unknown: bool) -> Result<Command, String> {
+        let kind = if allow_unknown {
+            CommandKind::from_wire_allowing_unknown(&self.type_, self.args)?
+        } else {
+            CommandKind::from_wire(&self.type_, self.args)?
+        };
+        Ok(Command {
+            kind,
+            uuid: self.uuid,
+            temp_id: self.temp_id,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Command {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        WireCommand::deserialize(deserializer)?
+            .into_command(false)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// A batch command that may have an unknown type or unknown fields
+struct UncheckedCommand(Command);
+
+impl<'de> Deserialize<'de> for UncheckedCommand {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        WireCommand::deserialize(deserializer)?
+            .into_command(true)
+            .map(Self)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Declares [`CommandKind`] with one typed variant per wire name, so the
+/// list of types, [`CommandKind::command_type`] and
+/// [`CommandKind::from_wire`] cannot drift apart.
+macro_rules! command_kinds {
+    ($($variant:ident($args:ty) => $name:literal,)*) => {
+        /// 所有支持的 Sync API 命令
+        #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+        #[serde(tag = "type", content = "args")]
+        pub enum CommandKind {
+            $(
+                #[serde(rename = $name)]
+                $variant($args),
+            )*
+            /// 未知类型或含未知字段的命令，原样发送
+            #[serde(untagged)]
+            #[schemars(skip)]
+            Other(RawCommand),
+        }
+
+        impl CommandKind {
+            /// All command types with typed arguments
+            pub const TYPES: &'static [&'static str] = &[$($name),*];
+
+            pub fn command_type(&self) -> &str {
+                match self {
+                    $(Self::$variant(_) => $name,)*
+                    Self::Other(raw) => &raw.type_,
+                }
+            }
+
+            /// Parses typed arguments; `None` for unknown types
+            fn parse_typed(type_: &str, args: serde_json::Value) -> Option<Result<Self, String>> {
+                match type_ {
+                    $($name => Some(parse_args(args).map(Self::$variant)),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+command_kinds! {
+    ItemAdd(ItemAddArgs) => "item_add",
+    ItemUpdate(ItemUpdateArgs) => "item_update",
+    ItemMove(ItemMoveArgs) => "item_move",
+    ItemClose(IdArgs) => "item_close",
+    ItemComplete(IdArgs) => "item_complete",
+    ItemUncomplete(IdArgs) => "item_uncomplete",
+    ItemDelete(IdArgs) => "item_delete",
+    ProjectAdd(ProjectAddArgs) => "project_add",
+    ProjectUpdate(ProjectUpdateArgs) => "project_update",
+    ProjectDelete(IdArgs) => "project_delete",
+    ProjectArchive(IdArgs) => "project_archive",
+    ProjectUnarchive(IdArgs) => "project_unarchive",
+    ProjectMove(ProjectMoveArgs) => "project_move",
+    ProjectReorder(ProjectReorderArgs) => "project_reorder",
+    SectionAdd(SectionAddArgs) => "section_add",
+    SectionUpdate(SectionUpdateArgs) => "section_update",
+    SectionDelete(IdArgs) => "section_delete",
+    SectionArchive(IdArgs) => "section_archive",
+    SectionUnarchive(IdArgs) => "section_unarchive",
+    SectionMove(SectionMoveArgs) => "section_move",
+    SectionReorder(SectionReorderArgs) => "section_reorder",
+    LabelAdd(LabelAddArgs) => "label_add",
+    LabelUpdate(LabelUpdateArgs) => "label_update",
+    LabelDelete(IdArgs) => "label_delete",
+    FilterAdd(FilterAddArgs) => "filter_add",
+    FilterUpdate(FilterUpdateArgs) => "filter_update",
+    FilterDelete(IdArgs) => "filter_delete",
+    FilterUpdateOrders(FilterUpdateOrdersArgs) => "filter_update_orders",
+    NoteAdd(NoteAddArgs) => "note_add",
+}
+
+/// A command todorust has no typed arguments for, sent as given
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RawCommand {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub args: serde_json::Value,
+}
+
+impl CommandKind {
+    /// Whether the command creates a resource and therefore takes a `temp_id`
+    pub fn creates_resource(&self) -> bool {
+        self.command_type().ends_with("_add")
+    }
+
+    /// Whether the command is sent without local checks: its type is
+    /// unknown, or its arguments have fields todorust does not know
+    pub fn is_unchecked(&self) -> bool {
+        matches!(self, Self::Other(_))
+    }
+
+    /// Builds a command from its wire `type` and `args`.
+    ///
+    /// Errors name the offending field, e.g. `args.priority: ...`. Unknown
+    /// types and unknown fields are rejected.
+    pub fn from_wire(type_: &str, args: serde_json::Value) -> Result<Self, String> {
+        let kind = Self::parse_typed(type_, args).unwrap_or_else(|| {
+            Err(format!(
+                "type: unknown command type `{}` (pass --allow-unknown to send it unchecked)",
+                type_
+            ))
+        })?;
+        kind.validate()?;
+        Ok(kind)
+    }
+
+    /// Like [`CommandKind::from_wire`], but unknown types, and known types
+    /// whose arguments have unknown fields, are kept as [`RawCommand`]s
+    /// after checking the known fields.
+    pub fn from_wire_allowing_unknown(
+        type_: &str,
+        args: serde_json::Value,
+    ) -> Result<Self, String> {
+        let mut known = args.clone();
+        let mut unknown = Vec::new();
+        loop {
+            let kind = match Self::parse_typed(type_, known.clone()) {
+                None => break,
+                Some(Ok(kind)) => kind,
+                Some(Err(e)) => {
+                    // Drop unknown fields and check the rest
+                    match unknown_field(&e) {
+                        Some(path) if remove_path(&mut known, &path) => {
+                            unknown.push(format!("args.{}", path.join(".")));
+                            continue;
+                        }
+                        _ if unknown.is_empty() => return Err(e),
+                        _ => return Err(format!("{} (unknown: {})", e, unknown.join(", "))),
+                    }
+                }
+            };
+            kind.validate()?;
+            if unknown.is_empty() {
+                return Ok(kind);
+            }
+            break;
+        }
+        Ok(Self::Other(RawCommand {
+            type_: type_.to_string(),
+            args,
+        }))
+    }
+
+    /// Checks value ranges that the type system does not capture
+    fn validate(&self) -> Result<(), String> {
+        if let Self::ProjectAdd(ProjectAddArgs { view_style, .. })
+        | Self::ProjectUpdate(ProjectUpdateArgs { view_style, .. }) = self
+        {
+            match view_style.as_deref() {
+                None | Some("list" | "board" | "calendar") => {}
+                Some(other) => {
+                    return Err(format!(
+                        "args.view_style: '{}' is not one of: {}",
+                        other,
+                        VIEW_STYLES.join(", ")
+                    ))
+                }
+            }
+        }
+        let duration = match self {
+            Self::ItemAdd(args) => args.duration.as_ref(),
+            Self::ItemUpdate(args) => args.duration.as_ref().and_then(Option::as_ref),
+            _ => None,
+        };
+        if duration.is_some_and(|d| d.amount == 0) {
+            return Err("args.duration.amount: must be at least 1".to_string());
+        }
+        let priority = match self {
+            Self::ItemAdd(args) => args.priority,
+            Self::ItemUpdate(args) => args.priority,
+            _ => None,
+        };
+        match priority {
+            Some(p) if !(1..=4).contains(&p) => Err(format!(
+                "args.priority: {} is out of range, expected 1-4 (API value, 4 = p1)",
+                p
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn replace_ids(value: &mut serde_json::Value, temp_id_mapping: &HashMap<String, String>) {
+    match value {
+        serde_json::Value::String(s) => {
+            if let Some(id) = temp_id_mapping.get(s.as_str()) {
+                *s = id.clone();
+            }
+        }
+        serde_json::Value::Array(items) => {
+            items
+                .iter_mut()
+                .for_each(|v| replace_ids(v, temp_id_mapping));
+        }
+        serde_json::Value::Object(map) => {
+            map.values_mut()
+                .for_each(|v| replace_ids(v, temp_id_mapping));
+        }
+        _ => {}
+    }
+}
+
+/// Project layouts accepted by `view_style`
+pub const VIEW_STYLES: [&str; 3] = ["list", "board", "calendar"];
+
+fn parse_args<T: DeserializeOwned>(args: serde_json::Value) -> Result<T, String> {
+    serde_path_to_error::deserialize(args).map_err(|e| match e.path().to_string().as_str() {
+        "." => format!("args: {}", e.inner()),
+        path => format!("args.{}: {}", path, e.inner()),
+    })
+}
+
+/// Path within `args` of the field an "unknown field" error from
+/// [`parse_args`] names, e.g. `["duration", "anchor"]`
+fn unknown_field(error: &str) -> Option<Vec<String>> {
+    let (path, _) = error.split_once(": unknown field `")?;
+    let path = path.strip_prefix("args.")?;
+    Some(
+        path.replace('[', ".")
+            .replace(']', "")
+            .split('.')
+            .map(String::from)
+            .collect(),
+    )
+}
+
+/// Removes the field at `path`; false when there is none
+fn remove_path(value: &mut serde_json::Value, path: &[String]) -> bool {
+    let Some((last, parents)) = path.split_last() else {
+        return false;
+    };
+    let parent = parents
+        .iter()
+        .try_fold(value, |value, segment| match value {
+            serde_json::Value::Object(map) => map.get_mut(segment),
+            serde_json::Value::Array(items) => items.get_mut(segment.parse::<usize>().ok()?),
+            _ => None,
+        });
+    match parent {
+        Some(serde_json::Value::Object(map)) => map.shift_remove(last).is_some(),
+        _ => false,
+    }
+}
+
+/// Parses and validates a JSON array of commands.
+///
+/// The error names the failing command by index, e.g.
+/// `Invalid batch command [1]: args.content: missing field`.
+pub fn parse_batch(json: &str) -> Result<Vec<Command>, TodoError> {
+    parse_commands(json)
+}
+
+/// Like [`parse_batch`], but commands with unknown types or fields are kept
+/// as [`RawCommand`]s instead of rejected
+pub fn parse_batch_allowing_unknown(json: &str) -> Result<Vec<Command>, TodoError> {
+    let commands: Vec<UncheckedCommand> = parse_commands(json)?;
+    Ok(commands.into_iter().map(|c| c.0).collect())
+}
+
+fn parse_commands<T: DeserializeOwned>(json: &str) -> Result<Vec<T>, TodoError> {
+    let deserializer = &mut serde_json::Deserializer::from_str(json);
+    serde_path_to_error::deserialize(deserializer).map_err(|e| {
+        match e.path().to_string().as_str() {
+            "." => TodoError::InvalidInput(format!("Invalid batch JSON: {}", e.inner())),
+            path => {
+                TodoError::InvalidInput(format!("Invalid batch command {}: {}", path, e.inner()))
+            }
+        }
+    })
+}
+
+/// JSON Schema describing the input accepted by `batch`
+pub fn batch_schema() -> serde_json::Value {
+    serde_json::to_value(schemars::schema_for!(Vec<Command>)).expect("schema serializes to JSON")
 }
 
 /// Command 构建器
@@ -61,329 +427,204 @@ impl CommandBuilder {
         }
     }
 
-    /// 添加 item_add 命令 - 创建任务
-    pub fn item_add(mut self, args: ItemAddArgs) -> Self {
-        self.commands.push(Command {
-            type_: "item_add".to_string(),
-            uuid: Command::generate_uuid(),
-            temp_id: Some(Command::generate_temp_id()),
-            args: serde_json::to_value(args)
-                .map_err(|e| TodoError::Serialize(e.to_string()))
-                .unwrap(),
-        });
+    /// 添加任意命令
+    pub fn push(mut self, kind: CommandKind) -> Self {
+        self.commands.push(Command::new(kind));
         self
     }
 
+    /// 添加 item_add 命令 - 创建任务
+    pub fn item_add(self, args: ItemAddArgs) -> Self {
+        self.push(CommandKind::ItemAdd(args))
+    }
+
     /// 添加 item_close 命令 - 完成任务
-    pub fn item_close(mut self, id: &str) -> Self {
-        self.commands.push(Command {
-            type_: "item_close".to_string(),
-            uuid: Command::generate_uuid(),
-            temp_id: None,
-            args: serde_json::json!({ "id": id }),
-        });
-        self
+    pub fn item_close(self, id: &str) -> Self {
+        self.push(CommandKind::ItemClose(IdArgs::new(id)))
     }
 
     /// 添加 item_uncomplete 命令 - 取消完成任务（重新打开）
-    pub fn item_uncomplete(mut self, id: &str) -> Self {
-        self.commands.push(Command {
-            type_: "item_uncomplete".to_string(),
-            uuid: Command::generate_uuid(),
-            temp_id: None,
-            args: serde_json::json!({ "id": id }),
-        });
-        self
+    pub fn item_uncomplete(self, id: &str) -> Self {
+        self.push(CommandKind::ItemUncomplete(IdArgs::new(id)))
     }
 
     /// 添加 item_delete 命令 - 删除任务
-    pub fn item_delete(mut self, id: &str) -> Self {
-        self.commands.push(Command {
-            type_: "item_delete".to_string(),
-            uuid: Command::generate_uuid(),
-            temp_id: None,
-            args: serde_json::json!({ "id": id }),
-        });
-        self
+    pub fn item_delete(self, id: &str) -> Self {
+        self.push(CommandKind::ItemDelete(IdArgs::new(id)))
     }
 
     /// 添加 item_move 命令 - 移动任务
-    pub fn item_move(mut self, id: &str, project_id: &str, section_id: Option<&str>) -> Self {
-        let mut args = serde_json::json!({
-            "id": id,
-            "project_id": project_id
-        });
-        if let Some(sid) = section_id {
-            args["section_id"] = serde_json::json!(sid);
-        }
-        self.commands.push(Command {
-            type_: "item_move".to_string(),
-            uuid: Command::generate_uuid(),
-            temp_id: None,
-            args,
-        });
-        self
+    pub fn item_move(self, id: &str, project_id: &str, section_id: Option<&str>) -> Self {
+        self.push(CommandKind::ItemMove(ItemMoveArgs {
+            id: id.to_string(),
+            project_id: Some(project_id.to_string()),
+            section_id: section_id.map(str::to_string),
+            parent_id: None,
+        }))
     }
 
     /// 添加 project_add 命令 - 创建项目
-    pub fn project_add(mut self, args: ProjectAddArgs) -> Self {
-        self.commands.push(Command {
-            type_: "project_add".to_string(),
-            uuid: Command::generate_uuid(),
-            temp_id: Some(Command::generate_temp_id()),
-            args: serde_json::to_value(args)
-                .map_err(|e| TodoError::Serialize(e.to_string()))
-                .unwrap(),
-        });
-        self
+    pub fn project_add(self, args: ProjectAddArgs) -> Self {
+        self.push(CommandKind::ProjectAdd(args))
     }
 
     /// 添加 project_update 命令 - 更新项目
     pub fn project_update(
-        mut self,
+        self,
         id: &str,
         name: Option<&str>,
         color: Option<&str>,
         favorite: Option<bool>,
     ) -> Self {
-        let mut args = serde_json::json!({ "id": id });
-        if let Some(n) = name {
-            args["name"] = serde_json::json!(n);
-        }
-        if let Some(c) = color {
-            args["color"] = serde_json::json!(c);
-        }
-        if let Some(f) = favorite {
-            args["favorite"] = serde_json::json!(f);
-        }
-        self.commands.push(Command {
-            type_: "project_update".to_string(),
-            uuid: Command::generate_uuid(),
-            temp_id: None,
-            args,
-        });
-        self
+        self.push(CommandKind::ProjectUpdate(
+            ProjectUpdateArgs::new(id.to_string())
+                .name(name.map(str::to_string))
+                .color(color.map(str::to_string))
+                .favorite(favorite),
+        ))
+    }
+
+    /// 添加 project_move 命令 - 移动项目到父项目 (None 为顶层)
+    pub fn project_move(self, id: &str, parent_id: Option<&str>) -> Self {
+        self.push(CommandKind::ProjectMove(ProjectMoveArgs {
+            id: id.to_string(),
+            parent_id: parent_id.map(str::to_string),
+        }))
+    }
+
+    /// 添加 project_reorder 命令 - 批量重新排序同级项目
+    pub fn project_reorder(self, projects: &[ProjectOrderArgs]) -> Self {
+        self.push(CommandKind::ProjectReorder(ProjectReorderArgs {
+            projects: projects.to_vec(),
+        }))
     }
 
     /// 添加 project_delete 命令 - 删除项目
-    pub fn project_delete(mut self, id: &str) -> Self {
-        self.commands.push(Command {
-            type_: "project_delete".to_string(),
-            uuid: Command::generate_uuid(),
-            temp_id: None,
-            args: serde_json::json!({ "id": id }),
-        });
-        self
+    pub fn project_delete(self, id: &str) -> Self {
+        self.push(CommandKind::ProjectDelete(IdArgs::new(id)))
+    }
+
+    /// 添加 project_archive 命令 - 归档项目
+    pub fn project_archive(self, id: &str) -> Self {
+        self.push(CommandKind::ProjectArchive(IdArgs::new(id)))
+    }
+
+    /// 添加 project_unarchive 命令 - 取消归档项目
+    pub fn project_unarchive(self, id: &str) -> Self {
+        self.push(CommandKind::ProjectUnarchive(IdArgs::new(id)))
     }
 
     /// 添加 section_add 命令 - 创建分区
-    pub fn section_add(mut self, args: SectionAddArgs) -> Self {
-        self.commands.push(Command {
-            type_: "section_add".to_string(),
-            uuid: Command::generate_uuid(),
-            temp_id: Some(Command::generate_temp_id()),
-            args: serde_json::to_value(args)
-                .map_err(|e| TodoError::Serialize(e.to_string()))
-                .unwrap(),
-        });
-        self
+    pub fn section_add(self, args: SectionAddArgs) -> Self {
+        self.push(CommandKind::SectionAdd(args))
     }
 
     /// 添加 section_update 命令 - 更新分区
-    pub fn section_update(mut self, id: &str, name: &str) -> Self {
-        self.commands.push(Command {
-            type_: "section_update".to_string(),
-            uuid: Command::generate_uuid(),
-            temp_id: None,
-            args: serde_json::json!({ "id": id, "name": name }),
-        });
-        self
+    pub fn section_update(self, id: &str, name: &str) -> Self {
+        self.push(CommandKind::SectionUpdate(SectionUpdateArgs {
+            id: id.to_string(),
+            name: name.to_string(),
+        }))
     }
 
     /// 添加 section_delete 命令 - 删除分区
-    pub fn section_delete(mut self, id: &str) -> Self {
-        self.commands.push(Command {
-            type_: "section_delete".to_string(),
-            uuid: Command::generate_uuid(),
-            temp_id: None,
-            args: serde_json::json!({ "id": id }),
-        });
-        self
+    pub fn section_delete(self, id: &str) -> Self {
+        self.push(CommandKind::SectionDelete(IdArgs::new(id)))
     }
 
     /// 添加 section_archive 命令 - 归档分区
-    pub fn section_archive(mut self, id: &str) -> Self {
-        self.commands.push(Command {
-            type_: "section_archive".to_string(),
-            uuid: Command::generate_uuid(),
-            temp_id: None,
-            args: serde_json::json!({ "id": id }),
-        });
-        self
+    pub fn section_archive(self, id: &str) -> Self {
+        self.push(CommandKind::SectionArchive(IdArgs::new(id)))
     }
 
     /// 添加 section_unarchive 命令 - 取消归档分区
-    pub fn section_unarchive(mut self, id: &str) -> Self {
-        self.commands.push(Command {
-            type_: "section_unarchive".to_string(),
-            uuid: Command::generate_uuid(),
-            temp_id: None,
-            args: serde_json::json!({ "id": id }),
-        });
-        self
+    pub fn section_unarchive(self, id: &str) -> Self {
+        self.push(CommandKind::SectionUnarchive(IdArgs::new(id)))
     }
 
     /// 添加 section_move 命令 - 移动分区到项目
-    pub fn section_move(mut self, id: &str, project_id: &str) -> Self {
-        self.commands.push(Command {
-            type_: "section_move".to_string(),
-            uuid: Command::generate_uuid(),
-            temp_id: None,
-            args: serde_json::json!({
-                "id": id,
-                "project_id": project_id
-            }),
-        });
-        self
+    pub fn section_move(self, id: &str, project_id: &str) -> Self {
+        self.push(CommandKind::SectionMove(SectionMoveArgs {
+            id: id.to_string(),
+            project_id: project_id.to_string(),
+        }))
     }
 
     /// 添加 section_reorder 命令 - 批量重新排序分区
-    pub fn section_reorder(mut self, sections: &[SectionOrderArgs]) -> Self {
-        self.commands.push(Command {
-            type_: "section_reorder".to_string(),
-            uuid: Command::generate_uuid(),
-            temp_id: None,
-            args: serde_json::json!({ "sections": sections }),
-        });
-        self
+    pub fn section_reorder(self, sections: &[SectionOrderArgs]) -> Self {
+        self.push(CommandKind::SectionReorder(SectionReorderArgs {
+            sections: sections.to_vec(),
+        }))
     }
 
     /// 添加 item_complete 命令 - 完成任务（标记为已完成）
-    pub fn item_complete(mut self, id: &str) -> Self {
-        self.commands.push(Command {
-            type_: "item_complete".to_string(),
-            uuid: Command::generate_uuid(),
-            temp_id: None,
-            args: serde_json::json!({ "id": id }),
-        });
-        self
+    pub fn item_complete(self, id: &str) -> Self {
+        self.push(CommandKind::ItemComplete(IdArgs::new(id)))
     }
 
     /// 添加 item_update 命令 - 更新任务
-    pub fn item_update(mut self, args: ItemUpdateArgs) -> Self {
-        self.commands.push(Command {
-            type_: "item_update".to_string(),
-            uuid: Command::generate_uuid(),
-            temp_id: None,
-            args: serde_json::to_value(args)
-                .map_err(|e| TodoError::Serialize(e.to_string()))
-                .unwrap(),
-        });
-        self
+    pub fn item_update(self, args: ItemUpdateArgs) -> Self {
+        self.push(CommandKind::ItemUpdate(args))
     }
 
     /// 添加 label_add 命令 - 创建标签
-    pub fn label_add(mut self, args: LabelAddArgs) -> Self {
-        self.commands.push(Command {
-            type_: "label_add".to_string(),
-            uuid: Command::generate_uuid(),
-            temp_id: Some(Command::generate_temp_id()),
-            args: serde_json::to_value(args)
-                .map_err(|e| TodoError::Serialize(e.to_string()))
-                .unwrap(),
-        });
-        self
+    pub fn label_add(self, args: LabelAddArgs) -> Self {
+        self.push(CommandKind::LabelAdd(args))
     }
 
     /// 添加 label_update 命令 - 更新标签
-    pub fn label_update(mut self, id: &str, name: Option<&str>, color: Option<&str>) -> Self {
-        let mut args = serde_json::json!({ "id": id });
-        if let Some(n) = name {
-            args["name"] = serde_json::json!(n);
-        }
-        if let Some(c) = color {
-            args["color"] = serde_json::json!(c);
-        }
-        self.commands.push(Command {
-            type_: "label_update".to_string(),
-            uuid: Command::generate_uuid(),
-            temp_id: None,
-            args,
-        });
-        self
+    pub fn label_update(self, id: &str, name: Option<&str>, color: Option<&str>) -> Self {
+        self.push(CommandKind::LabelUpdate(LabelUpdateArgs {
+            id: id.to_string(),
+            name: name.map(str::to_string),
+            color: color.map(str::to_string),
+        }))
     }
 
     /// 添加 label_delete 命令 - 删除标签
-    pub fn label_delete(mut self, id: &str) -> Self {
-        self.commands.push(Command {
-            type_: "label_delete".to_string(),
-            uuid: Command::generate_uuid(),
-            temp_id: None,
-            args: serde_json::json!({ "id": id }),
-        });
-        self
+    pub fn label_delete(self, id: &str) -> Self {
+        self.push(CommandKind::LabelDelete(IdArgs::new(id)))
     }
 
     /// 添加 filter_update_orders 命令 - 更新过滤器顺序
-    pub fn filter_update_orders(mut self, filters: &[FilterOrderArgs]) -> Self {
-        self.commands.push(Command {
-            type_: "filter_update_orders".to_string(),
-            uuid: Command::generate_uuid(),
-            temp_id: None,
-            args: serde_json::json!({ "filters": filters }),
-        });
-        self
+    pub fn filter_update_orders(self, filters: &[FilterOrderArgs]) -> Self {
+        self.push(CommandKind::FilterUpdateOrders(FilterUpdateOrdersArgs {
+            filters: filters.to_vec(),
+        }))
     }
 
     /// 添加 filter_add 命令 - 创建过滤器
-    pub fn filter_add(mut self, args: FilterAddArgs) -> Self {
-        self.commands.push(Command {
-            type_: "filter_add".to_string(),
-            uuid: Command::generate_uuid(),
-            temp_id: Some(Command::generate_temp_id()),
-            args: serde_json::to_value(args)
-                .map_err(|e| TodoError::Serialize(e.to_string()))
-                .unwrap(),
-        });
-        self
+    pub fn filter_add(self, args: FilterAddArgs) -> Self {
+        self.push(CommandKind::FilterAdd(args))
     }
 
     /// 添加 filter_update 命令 - 更新过滤器
     pub fn filter_update(
-        mut self,
+        self,
         id: &str,
         name: Option<&str>,
         query: Option<&str>,
         color: Option<&str>,
     ) -> Self {
-        let mut args = serde_json::json!({ "id": id });
-        if let Some(n) = name {
-            args["name"] = serde_json::json!(n);
-        }
-        if let Some(q) = query {
-            args["query"] = serde_json::json!(q);
-        }
-        if let Some(c) = color {
-            args["color"] = serde_json::json!(c);
-        }
-        self.commands.push(Command {
-            type_: "filter_update".to_string(),
-            uuid: Command::generate_uuid(),
-            temp_id: None,
-            args,
-        });
-        self
+        self.push(CommandKind::FilterUpdate(FilterUpdateArgs {
+            id: id.to_string(),
+            name: name.map(str::to_string),
+            query: query.map(str::to_string),
+            color: color.map(str::to_string),
+        }))
     }
 
     /// 添加 filter_delete 命令 - 删除过滤器
-    pub fn filter_delete(mut self, id: &str) -> Self {
-        self.commands.push(Command {
-            type_: "filter_delete".to_string(),
-            uuid: Command::generate_uuid(),
-            temp_id: None,
-            args: serde_json::json!({ "id": id }),
-        });
-        self
+    pub fn filter_delete(self, id: &str) -> Self {
+        self.push(CommandKind::FilterDelete(IdArgs::new(id)))
+    }
+
+    /// 添加 note_add 命令 - 添加任务评论
+    pub fn note_add(self, item_id: &str, content: &str) -> Self {
+        self.push(CommandKind::NoteAdd(NoteAddArgs {
+            item_id: item_id.to_string(),
+            content: content.to_string(),
+        }))
     }
 
     /// 构建命令列表
@@ -392,28 +633,90 @@ impl CommandBuilder {
     }
 }
 
+impl From<Vec<Command>> for CommandBuilder {
+    fn from(commands: Vec<Command>) -> Self {
+        Self { commands }
+    }
+}
+
 impl Default for CommandBuilder {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// 仅包含 ID 的命令参数（删除、完成、归档等）
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct IdArgs {
+    pub id: String,
+}
+
+impl IdArgs {
+    pub fn new(id: &str) -> Self {
+        Self { id: id.to_string() }
+    }
+}
+
 /// item_add 命令参数
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct ItemAddArgs {
     pub content: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub project_id: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub section_id: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub child_order: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub due_string: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due_lang: Option<String>,
+    /// API priority 1-4 (4 = p1)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(range(min = 1, max = 4))]
     pub priority: Option<u8>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub labels: Option<Vec<String>>,
+    /// 被指派的协作者 ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub responsible_uid: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<DurationArgs>,
+}
+
+/// 任务时长
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct DurationArgs {
+    #[schemars(range(min = 1))]
+    pub amount: u32,
+    pub unit: DurationUnit,
+}
+
+/// 时长单位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DurationUnit {
+    Minute,
+    Day,
+}
+
+impl From<crate::models::Duration> for DurationArgs {
+    fn from(duration: crate::models::Duration) -> Self {
+        Self {
+            amount: duration.amount,
+            unit: match duration.unit.as_str() {
+                "day" => DurationUnit::Day,
+                _ => DurationUnit::Minute,
+            },
+        }
+    }
 }
 
 impl ItemAddArgs {
@@ -423,9 +726,14 @@ impl ItemAddArgs {
             description: None,
             project_id: None,
             section_id: None,
+            parent_id: None,
+            child_order: None,
             due_string: None,
+            due_lang: None,
             priority: None,
             labels: None,
+            responsible_uid: None,
+            duration: None,
         }
     }
 
@@ -444,6 +752,11 @@ impl ItemAddArgs {
         self
     }
 
+    pub fn parent_id(mut self, parent_id: Option<String>) -> Self {
+        self.parent_id = parent_id;
+        self
+    }
+
     pub fn due_string(mut self, due_string: Option<String>) -> Self {
         self.due_string = due_string;
         self
@@ -458,27 +771,63 @@ impl ItemAddArgs {
         self.labels = labels;
         self
     }
+
+    pub fn responsible_uid(mut self, responsible_uid: Option<String>) -> Self {
+        self.responsible_uid = responsible_uid;
+        self
+    }
+
+    pub fn duration(mut self, duration: Option<DurationArgs>) -> Self {
+        self.duration = duration;
+        self
+    }
+}
+
+/// item_move 命令参数，`project_id`、`section_id`、`parent_id` 三选一
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ItemMoveArgs {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub section_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
 }
 
 /// project_add 命令参数
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct ProjectAddArgs {
     pub name: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub color: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub favorite: Option<bool>,
+    /// "list", "board" or "calendar"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub view_style: Option<String>,
 }
 
 impl ProjectAddArgs {
     pub fn new(name: String) -> Self {
         Self {
             name,
+            parent_id: None,
             color: None,
             favorite: None,
+            view_style: None,
         }
     }
 
+    pub fn parent_id(mut self, parent_id: Option<String>) -> Self {
+        self.parent_id = parent_id;
+        self
+    }
+
     pub fn color(mut self, color: Option<String>) -> Self {
         self.color = color;
         self
@@ -488,10 +837,94 @@ impl ProjectAddArgs {
         self.favorite = favorite;
         self
     }
+
+    pub fn view_style(mut self, view_style: Option<String>) -> Self {
+        self.view_style = view_style;
+        self
+    }
+}
+
+/// project_update 命令参数
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectUpdateArgs {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub favorite: Option<bool>,
+    /// "list", "board" or "calendar"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub view_style: Option<String>,
+}
+
+impl ProjectUpdateArgs {
+    pub fn new(id: String) -> Self {
+        Self {
+            id,
+            name: None,
+            color: None,
+            favorite: None,
+            view_style: None,
+        }
+    }
+
+    pub fn name(mut self, name: Option<String>) -> Self {
+        self.name = name;
+        self
+    }
+
+    pub fn color(mut self, color: Option<String>) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn favorite(mut self, favorite: Option<bool>) -> Self {
+        self.favorite = favorite;
+        self
+    }
+
+    pub fn view_style(mut self, view_style: Option<String>) -> Self {
+        self.view_style = view_style;
+        self
+    }
+}
+
+/// project_move 命令参数 (`parent_id` 为 null 时移到顶层)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectMoveArgs {
+    pub id: String,
+    #[serde(default)]
+    pub parent_id: Option<String>,
+}
+
+/// project_reorder 命令参数
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectReorderArgs {
+    pub projects: Vec<ProjectOrderArgs>,
+}
+
+/// project_reorder 中的单个项目顺序
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectOrderArgs {
+    pub id: String,
+    pub child_order: i64,
+}
+
+impl ProjectOrderArgs {
+    pub fn new(id: String, child_order: i64) -> Self {
+        Self { id, child_order }
+    }
 }
 
 /// section_add 命令参数
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct SectionAddArgs {
     pub name: String,
     pub project_id: String,
@@ -503,24 +936,68 @@ impl SectionAddArgs {
     }
 }
 
+/// section_update 命令参数
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SectionUpdateArgs {
+    pub id: String,
+    pub name: String,
+}
+
+/// section_move 命令参数
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SectionMoveArgs {
+    pub id: String,
+    pub project_id: String,
+}
+
 /// item_update 命令参数
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct ItemUpdateArgs {
     pub id: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// API priority 1-4 (4 = p1)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(range(min = 1, max = 4))]
     pub priority: Option<u8>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub due_string: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub due_datetime: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub due_lang: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub labels: Option<Vec<String>>,
+    /// 被指派的协作者 ID，`Some(None)` 发送 null 取消指派
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    #[schemars(with = "Option<String>")]
+    pub responsible_uid: Option<Option<String>>,
+    /// `Some(None)` 发送 null 清除时长
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    #[schemars(with = "Option<DurationArgs>")]
+    pub duration: Option<Option<DurationArgs>>,
+}
+
+/// 字段存在即为 `Some`，用于区分缺省与 null
+fn present<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
 }
 
 impl ItemUpdateArgs {
@@ -534,6 +1011,8 @@ impl ItemUpdateArgs {
             due_datetime: None,
             due_lang: None,
             labels: None,
+            responsible_uid: None,
+            duration: None,
         }
     }
 
@@ -571,13 +1050,24 @@ impl ItemUpdateArgs {
         self.labels = labels;
         self
     }
+
+    pub fn responsible_uid(mut self, responsible_uid: Option<Option<String>>) -> Self {
+        self.responsible_uid = responsible_uid;
+        self
+    }
+
+    pub fn duration(mut self, duration: Option<Option<DurationArgs>>) -> Self {
+        self.duration = duration;
+        self
+    }
 }
 
 /// label_add 命令参数
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct LabelAddArgs {
     pub name: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub color: Option<String>,
 }
 
@@ -592,8 +1082,27 @@ impl LabelAddArgs {
     }
 }
 
+/// label_update 命令参数
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct LabelUpdateArgs {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
 /// filter_update_orders 命令参数
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FilterUpdateOrdersArgs {
+    pub filters: Vec<FilterOrderArgs>,
+}
+
+/// filter_update_orders 中的单个过滤器顺序
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct FilterOrderArgs {
     pub id: String,
     pub order: i64,
@@ -606,11 +1115,12 @@ impl FilterOrderArgs {
 }
 
 /// filter_add 命令参数
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct FilterAddArgs {
     pub name: String,
     pub query: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub color: Option<String>,
 }
 
@@ -629,8 +1139,29 @@ impl FilterAddArgs {
     }
 }
 
+/// filter_update 命令参数
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FilterUpdateArgs {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
 /// section_reorder 命令参数
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SectionReorderArgs {
+    pub sections: Vec<SectionOrderArgs>,
+}
+
+/// section_reorder 中的单个分区顺序
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct SectionOrderArgs {
     pub id: String,
     pub order: i64,
@@ -642,6 +1173,14 @@ impl SectionOrderArgs {
     }
 }
 
+/// note_add 命令参数
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct NoteAddArgs {
+    pub item_id: String,
+    pub content: String,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -660,7 +1199,7 @@ mod tests {
         assert_eq!(commands.len(), 1);
 
         let cmd = &commands[0];
-        assert_eq!(cmd.type_, "item_add");
+        assert_eq!(cmd.command_type(), "item_add");
         assert!(!cmd.uuid.is_empty());
         assert!(cmd.temp_id.is_some());
     }
@@ -671,7 +1210,7 @@ mod tests {
         assert_eq!(commands.len(), 1);
 
         let cmd = &commands[0];
-        assert_eq!(cmd.type_, "item_close");
+        assert_eq!(cmd.command_type(), "item_close");
         assert!(cmd.temp_id.is_none());
     }
 
@@ -703,13 +1242,36 @@ mod tests {
         assert_ne!(commands[0].temp_id, commands[1].temp_id);
     }
 
+    #[test]
+    fn test_resolve_temp_ids() {
+        let mut commands = CommandBuilder::new()
+            .item_add(ItemAddArgs::new("Task".to_string()).project_id(Some("tmp".to_string())))
+            .note_add("tmp", "tmp stays in text")
+            .build();
+        let mapping = HashMap::from([("tmp".to_string(), "123".to_string())]);
+        for command in &mut commands {
+            command.resolve_temp_ids(&mapping);
+        }
+        match &commands[0].kind {
+            CommandKind::ItemAdd(args) => assert_eq!(args.project_id.as_deref(), Some("123")),
+            other => panic!("unexpected {:?}", other),
+        }
+        match &commands[1].kind {
+            CommandKind::NoteAdd(args) => {
+                assert_eq!(args.item_id, "123");
+                assert_eq!(args.content, "tmp stays in text");
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
     #[test]
     fn test_item_complete_command() {
         let commands = CommandBuilder::new().item_complete("123").build();
         assert_eq!(commands.len(), 1);
 
         let cmd = &commands[0];
-        assert_eq!(cmd.type_, "item_complete");
+        assert_eq!(cmd.command_type(), "item_complete");
         assert!(cmd.temp_id.is_none());
     }
 
@@ -723,7 +1285,7 @@ mod tests {
         assert_eq!(commands.len(), 1);
 
         let cmd = &commands[0];
-        assert_eq!(cmd.type_, "item_update");
+        assert_eq!(cmd.command_type(), "item_update");
         assert!(cmd.temp_id.is_none());
     }
 
@@ -735,7 +1297,7 @@ mod tests {
         assert_eq!(commands.len(), 1);
 
         let cmd = &commands[0];
-        assert_eq!(cmd.type_, "label_add");
+        assert_eq!(cmd.command_type(), "label_add");
         assert!(cmd.temp_id.is_some());
     }
 
@@ -745,7 +1307,7 @@ mod tests {
         assert_eq!(commands.len(), 1);
 
         let cmd = &commands[0];
-        assert_eq!(cmd.type_, "label_delete");
+        assert_eq!(cmd.command_type(), "label_delete");
         assert!(cmd.temp_id.is_none());
     }
 
@@ -760,7 +1322,7 @@ mod tests {
         assert_eq!(commands.len(), 1);
 
         let cmd = &commands[0];
-        assert_eq!(cmd.type_, "filter_update_orders");
+        assert_eq!(cmd.command_type(), "filter_update_orders");
         assert!(cmd.temp_id.is_none());
     }
 
@@ -770,7 +1332,7 @@ mod tests {
         assert_eq!(commands.len(), 1);
 
         let cmd = &commands[0];
-        assert_eq!(cmd.type_, "section_archive");
+        assert_eq!(cmd.command_type(), "section_archive");
         assert!(cmd.temp_id.is_none());
     }
 
@@ -780,7 +1342,7 @@ mod tests {
         assert_eq!(commands.len(), 1);
 
         let cmd = &commands[0];
-        assert_eq!(cmd.type_, "section_unarchive");
+        assert_eq!(cmd.command_type(), "section_unarchive");
         assert!(cmd.temp_id.is_none());
     }
 
@@ -790,7 +1352,7 @@ mod tests {
         assert_eq!(commands.len(), 1);
 
         let cmd = &commands[0];
-        assert_eq!(cmd.type_, "section_move");
+        assert_eq!(cmd.command_type(), "section_move");
         assert!(cmd.temp_id.is_none());
     }
 
@@ -805,7 +1367,7 @@ mod tests {
         assert_eq!(commands.len(), 1);
 
         let cmd = &commands[0];
-        assert_eq!(cmd.type_, "section_reorder");
+        assert_eq!(cmd.command_type(), "section_reorder");
         assert!(cmd.temp_id.is_none());
     }
 
@@ -817,7 +1379,7 @@ mod tests {
         assert_eq!(commands.len(), 1);
 
         let cmd = &commands[0];
-        assert_eq!(cmd.type_, "project_add");
+        assert_eq!(cmd.command_type(), "project_add");
         assert!(cmd.temp_id.is_some());
     }
 
@@ -833,7 +1395,7 @@ mod tests {
         assert_eq!(commands.len(), 1);
 
         let cmd = &commands[0];
-        assert_eq!(cmd.type_, "project_add");
+        assert_eq!(cmd.command_type(), "project_add");
         assert!(cmd.temp_id.is_some());
     }
 
@@ -845,7 +1407,7 @@ mod tests {
         assert_eq!(commands.len(), 1);
 
         let cmd = &commands[0];
-        assert_eq!(cmd.type_, "project_update");
+        assert_eq!(cmd.command_type(), "project_update");
         assert!(cmd.temp_id.is_none());
     }
 
@@ -855,7 +1417,7 @@ mod tests {
         assert_eq!(commands.len(), 1);
 
         let cmd = &commands[0];
-        assert_eq!(cmd.type_, "project_delete");
+        assert_eq!(cmd.command_type(), "project_delete");
         assert!(cmd.temp_id.is_none());
     }
 
@@ -870,7 +1432,7 @@ mod tests {
         assert_eq!(commands.len(), 1);
 
         let cmd = &commands[0];
-        assert_eq!(cmd.type_, "section_add");
+        assert_eq!(cmd.command_type(), "section_add");
         assert!(cmd.temp_id.is_some());
     }
 
@@ -882,7 +1444,7 @@ mod tests {
         assert_eq!(commands.len(), 1);
 
         let cmd = &commands[0];
-        assert_eq!(cmd.type_, "section_update");
+        assert_eq!(cmd.command_type(), "section_update");
         assert!(cmd.temp_id.is_none());
     }
 
@@ -892,7 +1454,7 @@ mod tests {
         assert_eq!(commands.len(), 1);
 
         let cmd = &commands[0];
-        assert_eq!(cmd.type_, "section_delete");
+        assert_eq!(cmd.command_type(), "section_delete");
         assert!(cmd.temp_id.is_none());
     }
 
@@ -902,7 +1464,7 @@ mod tests {
         assert_eq!(commands.len(), 1);
 
         let cmd = &commands[0];
-        assert_eq!(cmd.type_, "item_uncomplete");
+        assert_eq!(cmd.command_type(), "item_uncomplete");
         assert!(cmd.temp_id.is_none());
     }
 
@@ -912,7 +1474,7 @@ mod tests {
         assert_eq!(commands.len(), 1);
 
         let cmd = &commands[0];
-        assert_eq!(cmd.type_, "item_move");
+        assert_eq!(cmd.command_type(), "item_move");
         assert!(cmd.temp_id.is_none());
     }
 
@@ -924,7 +1486,7 @@ mod tests {
         assert_eq!(commands.len(), 1);
 
         let cmd = &commands[0];
-        assert_eq!(cmd.type_, "item_move");
+        assert_eq!(cmd.command_type(), "item_move");
         assert!(cmd.temp_id.is_none());
     }
 
@@ -934,7 +1496,7 @@ mod tests {
         assert_eq!(commands.len(), 1);
 
         let cmd = &commands[0];
-        assert_eq!(cmd.type_, "item_delete");
+        assert_eq!(cmd.command_type(), "item_delete");
         assert!(cmd.temp_id.is_none());
     }
 
@@ -949,7 +1511,7 @@ mod tests {
         assert_eq!(commands.len(), 1);
 
         let cmd = &commands[0];
-        assert_eq!(cmd.type_, "filter_add");
+        assert_eq!(cmd.command_type(), "filter_add");
         assert!(cmd.temp_id.is_some());
     }
 
@@ -964,7 +1526,7 @@ mod tests {
         assert_eq!(commands.len(), 1);
 
         let cmd = &commands[0];
-        assert_eq!(cmd.type_, "filter_add");
+        assert_eq!(cmd.command_type(), "filter_add");
         assert!(cmd.temp_id.is_some());
     }
 
@@ -981,7 +1543,7 @@ mod tests {
         assert_eq!(commands.len(), 1);
 
         let cmd = &commands[0];
-        assert_eq!(cmd.type_, "filter_update");
+        assert_eq!(cmd.command_type(), "filter_update");
         assert!(cmd.temp_id.is_none());
     }
 
@@ -991,7 +1553,7 @@ mod tests {
         assert_eq!(commands.len(), 1);
 
         let cmd = &commands[0];
-        assert_eq!(cmd.type_, "filter_delete");
+        assert_eq!(cmd.command_type(), "filter_delete");
         assert!(cmd.temp_id.is_none());
     }
 
@@ -1015,7 +1577,7 @@ mod tests {
         assert_eq!(commands.len(), 1);
 
         let cmd = &commands[0];
-        assert_eq!(cmd.type_, "item_add");
+        assert_eq!(cmd.command_type(), "item_add");
         assert!(cmd.temp_id.is_some());
     }
 
@@ -1034,7 +1596,7 @@ mod tests {
         assert_eq!(commands.len(), 1);
 
         let cmd = &commands[0];
-        assert_eq!(cmd.type_, "item_update");
+        assert_eq!(cmd.command_type(), "item_update");
     }
 
     #[test]
@@ -1084,15 +1646,15 @@ mod tests {
         let item_add = CommandBuilder::new()
             .item_add(ItemAddArgs::new("Task".to_string()))
             .build();
-        assert_eq!(item_add[0].type_, "item_add");
+        assert_eq!(item_add[0].command_type(), "item_add");
 
         let item_close = CommandBuilder::new().item_close("123").build();
-        assert_eq!(item_close[0].type_, "item_close");
+        assert_eq!(item_close[0].command_type(), "item_close");
 
         let project_add = CommandBuilder::new()
             .project_add(ProjectAddArgs::new("Project".to_string()))
             .build();
-        assert_eq!(project_add[0].type_, "project_add");
+        assert_eq!(project_add[0].command_type(), "project_add");
     }
 
     #[test]
@@ -1108,8 +1670,11 @@ mod tests {
             "args": {"content": "New Task"}
         }"#;
         let cmd: Command = serde_json::from_str(json).unwrap();
-        assert_eq!(cmd.type_, "item_add");
-        assert_eq!(cmd.args["content"], "New Task");
+        assert_eq!(cmd.command_type(), "item_add");
+        match &cmd.kind {
+            CommandKind::ItemAdd(args) => assert_eq!(args.content, "New Task"),
+            other => panic!("unexpected command {:?}", other),
+        }
         // Check that UUID was generated by default
         assert!(!cmd.uuid.is_empty());
     }
@@ -1122,7 +1687,216 @@ mod tests {
         ]"#;
         let commands: Vec<Command> = serde_json::from_str(json).unwrap();
         assert_eq!(commands.len(), 2);
-        assert_eq!(commands[0].type_, "item_add");
-        assert_eq!(commands[1].type_, "item_complete");
+        assert_eq!(commands[0].command_type(), "item_add");
+        assert_eq!(commands[1].command_type(), "item_complete");
+    }
+
+    #[test]
+    fn test_command_wire_round_trip() {
+        let commands = CommandBuilder::new()
+            .item_move("123", "456", Some("789"))
+            .build();
+        let json = serde_json::to_value(&commands[0]).unwrap();
+        assert_eq!(json["type"], "item_move");
+        assert_eq!(
+            json["args"],
+            serde_json::json!({"id": "123", "project_id": "456", "section_id": "789"})
+        );
+        assert_eq!(json["uuid"], commands[0].uuid.as_str());
+
+        let parsed: Command = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.command_type(), "item_move");
+        assert_eq!(parsed.uuid, commands[0].uuid);
+    }
+
+    #[test]
+    fn test_parse_batch_rejects_unknown_commands() {
+        let err = parse_batch(
+            r#"[{"type": "reminder_add", "args": {"item_id": "1", "minute_offset": 30}}]"#,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(
+            err.contains("Invalid batch command [0]: type: unknown command type `reminder_add`"),
+            "{}",
+            err
+        );
+
+        let err = parse_batch(
+            r#"[{"type": "item_add", "args": {"content": "x", "duration": {"amount": 5, "unit": "minute", "anchor": 1}}}]"#,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(
+            err.contains("args.duration.anchor: unknown field `anchor`"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_parse_batch_allowing_unknown_passes_commands_through() {
+        let parsed = parse_batch_allowing_unknown(
+            r#"[
+                {"type": "reminder_add", "temp_id": "r1", "args": {"item_id": "1", "minute_offset": 30}},
+                {"type": "item_add", "args": {"content": "x", "auto_reminder": true, "duration": {"amount": 5, "unit": "minute", "anchor": 1}}},
+                {"type": "item_add", "args": {"content": "y"}}
+            ]"#,
+        )
+        .unwrap();
+        assert!(parsed[0].kind.is_unchecked());
+        assert!(parsed[0].kind.creates_resource());
+        assert!(parsed[1].kind.is_unchecked());
+        assert!(!parsed[2].kind.is_unchecked());
+
+        // Sent exactly as given
+        let json = serde_json::to_value(&parsed[1]).unwrap();
+        assert_eq!(json["type"], "item_add");
+        assert_eq!(json["args"]["auto_reminder"], true);
+        assert_eq!(json["args"]["duration"]["anchor"], 1);
+        let mut command = parsed[0].clone();
+        command.resolve_temp_ids(&HashMap::from([("1".to_string(), "99".to_string())]));
+        assert_eq!(
+            serde_json::to_value(&command).unwrap()["args"]["item_id"],
+            "99"
+        );
+
+        // Known fields are still checked
+        let err = parse_batch_allowing_unknown(
+            r#"[{"type": "item_add", "args": {"content": "x", "new_field": 1, "priority": 9}}]"#,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("args.priority: 9 is out of range"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_batch_reports_field_path() {
+        let err = parse_batch(
+            r#"[
+                {"type": "item_add", "args": {"content": "ok"}},
+                {"type": "item_add", "args": {"contnt": "typo"}}
+            ]"#,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("Invalid batch command [1]"), "{}", err);
+        assert!(
+            err.contains("args.contnt: unknown field `contnt`"),
+            "{}",
+            err
+        );
+
+        // The misspelt field is allowed through, but the required one is missing
+        let err =
+            parse_batch_allowing_unknown(r#"[{"type": "item_add", "args": {"contnt": "typo"}}]"#)
+                .unwrap_err()
+                .to_string();
+        assert!(
+            err.contains("args: missing field `content` (unknown: args.contnt)"),
+            "{}",
+            err
+        );
+
+        let err = parse_batch(r#"[{"type": "section_add", "args": {"name": "x"}}]"#)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("missing field `project_id`"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_batch_rejects_out_of_range_priority() {
+        let err = parse_batch(r#"[{"type": "item_add", "args": {"content": "x", "priority": 5}}]"#)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("args.priority: 5 is out of range"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_batch_rejects_unknown_view_style() {
+        let err = parse_batch(
+            r#"[{"type": "project_update", "args": {"id": "1", "view_style": "kanban"}}]"#,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("args.view_style: 'kanban'"), "{}", err);
+    }
+
+    #[test]
+    fn test_project_move_to_root_serializes_null_parent() {
+        let commands = CommandBuilder::new().project_move("123", None).build();
+        let json = serde_json::to_value(&commands[0]).unwrap();
+        assert_eq!(json["type"], "project_move");
+        assert!(json["args"]["parent_id"].is_null());
+        assert!(json["args"].as_object().unwrap().contains_key("parent_id"));
+    }
+
+    #[test]
+    fn test_item_update_unassign_serializes_null() {
+        let commands = CommandBuilder::new()
+            .item_update(ItemUpdateArgs::new("1".to_string()).responsible_uid(Some(None)))
+            .build();
+        let json = serde_json::to_value(&commands[0]).unwrap();
+        assert!(json["args"]["responsible_uid"].is_null());
+        assert!(json["args"]
+            .as_object()
+            .unwrap()
+            .contains_key("responsible_uid"));
+
+        let parsed = parse_batch(
+            r#"[{"type": "item_update", "args": {"id": "1", "responsible_uid": null}}]"#,
+        )
+        .unwrap();
+        match &parsed[0].kind {
+            CommandKind::ItemUpdate(args) => assert_eq!(args.responsible_uid, Some(None)),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_item_duration_is_validated() {
+        let parsed = parse_batch(
+            r#"[{"type": "item_add", "args": {"content": "a", "duration": {"amount": 45, "unit": "minute"}}}]"#,
+        )
+        .unwrap();
+        match &parsed[0].kind {
+            CommandKind::ItemAdd(args) => assert_eq!(
+                args.duration,
+                Some(DurationArgs {
+                    amount: 45,
+                    unit: DurationUnit::Minute
+                })
+            ),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let err = parse_batch(
+            r#"[{"type": "item_update", "args": {"id": "1", "duration": {"amount": 0, "unit": "day"}}}]"#,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("args.duration.amount"), "{}", err);
+        assert!(parse_batch(
+            r#"[{"type": "item_add", "args": {"content": "a", "duration": {"amount": 1, "unit": "week"}}}]"#,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_batch_invalid_json() {
+        let err = parse_batch("not json").unwrap_err().to_string();
+        assert!(err.contains("Invalid batch JSON"), "{}", err);
+    }
+
+    #[test]
+    fn test_batch_schema_lists_command_types() {
+        let schema = batch_schema().to_string();
+        for command_type in CommandKind::TYPES {
+            assert!(
+                schema.contains(&format!("\"{}\"", command_type)),
+                "{} missing from schema",
+                command_type
+            );
+        }
     }
 }
@@ -0,0 +1,842 @@
+//! # Undo Journal
+//!
+//! Every successful write is recorded together with the commands that revert
+//! it, computed from the cached state of the affected resources, or from a
+//! snapshot taken just before the write when the cache lacks them.
+//! `todorust undo` replays those inverse commands.
+//!
+//! Resources recreated by an undo (e.g. a deleted task and its subtasks, or a
+//! project with its sections and tasks) get new IDs. Their inverse commands
+//! use the old ID as `temp_id`, so commands referring to it can be remapped.
+//! Comments are not restored.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use super::cache::CacheData;
+use super::commands::{
+    Command, CommandKind, FilterAddArgs, FilterUpdateArgs, IdArgs, ItemAddArgs, ItemMoveArgs,
+    ItemUpdateArgs, LabelAddArgs, LabelUpdateArgs, ProjectAddArgs, ProjectMoveArgs,
+    ProjectOrderArgs, ProjectReorderArgs, ProjectUpdateArgs, SectionAddArgs, SectionMoveArgs,
+    SectionOrderArgs, SectionReorderArgs, SectionUpdateArgs,
+};
+use super::models::{SyncDue, SyncProject, SyncSection, SyncTask};
+use crate::error::TodoError;
+
+/// Number of operations kept in the journal
+pub const MAX_ENTRIES: usize = 100;
+
+/// One recorded write operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub id: u64,
+    /// Unix timestamp in seconds
+    pub recorded_at: i64,
+    /// Command types that were sent, e.g. `item_delete, item_add x2`
+    pub summary: String,
+    pub commands: Vec<Command>,
+    /// Commands reverting the operation, in execution order
+    pub inverse: Vec<Command>,
+    /// Parts of the operation that cannot be reverted
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    #[serde(default)]
+    pub undone: bool,
+}
+
+/// Stores the journal as JSON next to the configuration
+pub struct Journal {
+    path: PathBuf,
+}
+
+impl Default for Journal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Journal {
+    pub fn new() -> Self {
+        let config_dir = std::env::var("TODORUST_CONFIG_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| {
+                dirs::config_dir()
+                    .unwrap_or_else(|| PathBuf::from("."))
+                    .join("todorust")
+            });
+        Self {
+            path: config_dir.join("journal.json"),
+        }
+    }
+
+    /// Create a Journal with a custom path (for testing)
+    #[cfg(test)]
+    pub fn with_path(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn load(&self) -> Result<Vec<JournalEntry>, TodoError> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read_to_string(&self.path)?;
+        serde_json::from_str(&content)
+            .map_err(|e| TodoError::InvalidInput(format!("Failed to parse journal: {}", e)))
+    }
+
+    fn save(&self, entries: &[JournalEntry]) -> Result<(), TodoError> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(entries)
+            .map_err(|e| TodoError::Serialize(format!("Failed to serialize journal: {}", e)))?;
+        std::fs::write(&self.path, content)?;
+        Ok(())
+    }
+
+    /// Appends an operation, dropping the oldest entries beyond [`MAX_ENTRIES`]
+    pub fn record(
+        &self,
+        commands: &[Command],
+        inverse: Vec<Command>,
+        warnings: Vec<String>,
+    ) -> Result<JournalEntry, TodoError> {
+        let mut entries = self.load()?;
+        let entry = JournalEntry {
+            id: entries.last().map(|e| e.id + 1).unwrap_or(1),
+            recorded_at: chrono::Utc::now().timestamp(),
+            summary: summarize(commands),
+            commands: commands.to_vec(),
+            inverse,
+            warnings,
+            undone: false,
+        };
+        entries.push(entry.clone());
+        if entries.len() > MAX_ENTRIES {
+            entries.drain(..entries.len() - MAX_ENTRIES);
+        }
+        self.save(&entries)?;
+        Ok(entry)
+    }
+
+    /// The most recent operations that have not been undone, newest first,
+    /// up to `steps` with inverse commands. Operations without any are
+    /// included too, so they can be reported as not undoable.
+    pub fn undoable(&self, steps: usize) -> Result<Vec<JournalEntry>, TodoError> {
+        let mut remaining = steps;
+        Ok(self
+            .load()?
+            .into_iter()
+            .rev()
+            .filter(|e| !e.undone)
+            .take_while(|e| {
+                if remaining == 0 {
+                    return false;
+                }
+                if !e.inverse.is_empty() {
+                    remaining -= 1;
+                }
+                true
+            })
+            .collect())
+    }
+
+    pub fn mark_undone(&self, id: u64) -> Result<(), TodoError> {
+        let mut entries = self.load()?;
+        if let Some(entry) = entries.iter_mut().find(|e| e.id == id) {
+            entry.undone = true;
+        }
+        self.save(&entries)
+    }
+}
+
+fn summarize(commands: &[Command]) -> String {
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for command in commands {
+        match counts
+            .iter_mut()
+            .find(|(t, _)| *t == command.command_type())
+        {
+            Some((_, n)) => *n += 1,
+            None => counts.push((command.command_type(), 1)),
+        }
+    }
+    counts
+        .iter()
+        .map(|(t, n)| match n {
+            1 => t.to_string(),
+            _ => format!("{} x{}", t, n),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Resource types whose current state is needed to invert `commands`
+pub fn snapshot_resource_types(commands: &[Command]) -> Vec<&'static str> {
+    let mut types = Vec::new();
+    for command in commands {
+        if command.kind.creates_resource() {
+            continue;
+        }
+        let resources: &[&str] = match command.command_type().split('_').next() {
+            // Deleting a project deletes its sections and tasks too
+            _ if matches!(command.kind, CommandKind::ProjectDelete(_)) => {
+                &["projects", "sections", "items"]
+            }
+            Some("item") => &["items"],
+            Some("project") => &["projects"],
+            Some("section") => &["sections"],
+            Some("label") => &["labels"],
+            _ => &["filters"],
+        };
+        for resource in resources {
+            if !types.contains(resource) {
+                types.push(resource);
+            }
+        }
+    }
+    types
+}
+
+/// Whether `before` holds every resource `commands` change, so their inverse
+/// can be computed without fetching a snapshot
+pub fn snapshot_covers(commands: &[Command], before: &CacheData) -> bool {
+    // Created resources only need their new IDs, which are known after the write
+    let placeholders: HashMap<String, String> = commands
+        .iter()
+        .filter_map(|c| c.temp_id.clone())
+        .map(|t| (t.clone(), t))
+        .collect();
+    inverse_commands(commands, before, &placeholders)
+        .1
+        .is_empty()
+}
+
+/// Computes the commands reverting `commands`, given the state before they ran.
+///
+/// Returns the inverse commands in execution order and a warning for every
+/// command that cannot be reverted.
+pub fn inverse_commands(
+    commands: &[Command],
+    before: &CacheData,
+    temp_id_mapping: &HashMap<String, String>,
+) -> (Vec<Command>, Vec<String>) {
+    let mut inverse = Vec::new();
+    let mut warnings = Vec::new();
+    let deleted_tasks: HashSet<&str> = commands
+        .iter()
+        .filter_map(|c| match &c.kind {
+            CommandKind::ItemDelete(args) => Some(args.id.as_str()),
+            _ => None,
+        })
+        .collect();
+
+    for command in commands.iter().rev() {
+        // Subtasks are recreated together with their deleted parent
+        if let CommandKind::ItemDelete(args) = &command.kind {
+            if task_ancestors(before, &args.id).any(|id| deleted_tasks.contains(id)) {
+                continue;
+            }
+        }
+        let created_id = command
+            .temp_id
+            .as_ref()
+            .and_then(|t| temp_id_mapping.get(t))
+            .cloned();
+        match invert(&command.kind, before, created_id) {
+            Some(commands) => {
+                if let CommandKind::ProjectDelete(args) = &command.kind {
+                    warnings.push(format!(
+                        "project_delete: comments and completed tasks of project '{}' cannot be restored",
+                        args.id
+                    ));
+                }
+                inverse.extend(commands)
+            }
+            None => warnings.push(format!(
+                "{} cannot be undone: previous state unknown",
+                command.command_type()
+            )),
+        }
+    }
+    (inverse, warnings)
+}
+
+/// IDs of the parent, grandparent, ... of task `id` in `before`
+fn task_ancestors<'a>(before: &'a CacheData, id: &str) -> impl Iterator<Item = &'a str> {
+    let parent = |id: &str| {
+        before
+            .items
+            .iter()
+            .find(|t| t.id == id)
+            .and_then(|t| t.parent_id.as_deref())
+    };
+    let mut seen = HashSet::new();
+    std::iter::successors(parent(id), move |id| parent(id)).take_while(move |id| seen.insert(*id))
+}
+
+/// A command recreating a resource, using its old ID as `temp_id`
+fn recreate(kind: CommandKind, old_id: &str) -> Command {
+    let mut command = Command::new(kind);
+    command.temp_id = Some(old_id.to_string());
+    command
+}
+
+/// Commands recreating `roots` and all their subtasks in `before`, parents
+/// first, then completing the tasks that were completed
+fn recreate_tasks(before: &CacheData, roots: Vec<&SyncTask>) -> Vec<Command> {
+    let mut adds = Vec::new();
+    let mut completes = Vec::new();
+    let mut queue: std::collections::VecDeque<&SyncTask> = roots.into();
+    let mut seen = HashSet::new();
+    while let Some(t) = queue.pop_front() {
+        if !seen.insert(t.id.as_str()) {
+            continue;
+        }
+        let mut add = ItemAddArgs::new(t.content.clone())
+            .description(t.description.clone().filter(|d| !d.is_empty()))
+            .project_id(t.project_id.clone())
+            .section_id(t.section_id.clone())
+            .parent_id(t.parent_id.clone())
+            .due_string(t.due.as_ref().and_then(SyncDue::to_due_string))
+            .priority(Some(t.priority).filter(|p| (1..=4).contains(p)))
+            .labels(Some(t.labels.clone()).filter(|l| !l.is_empty()))
+            .responsible_uid(t.responsible_uid.clone())
+            .duration(t.duration.clone().map(Into::into));
+        add.child_order = Some(t.order);
+        adds.push(recreate(CommandKind::ItemAdd(add), &t.id));
+        if t.is_completed {
+            completes.push(Command::new(CommandKind::ItemComplete(IdArgs::new(&t.id))));
+        }
+
+        let mut children: Vec<&SyncTask> = before
+            .items
+            .iter()
+            .filter(|c| c.parent_id.as_deref() == Some(t.id.as_str()) && !c.is_deleted)
+            .collect();
+        children.sort_by_key(|c| c.order);
+        queue.extend(children);
+    }
+    // Subtasks before their parents
+    adds.extend(completes.into_iter().rev());
+    adds
+}
+
+/// Commands recreating project `root` with its subprojects, sections and
+/// tasks from `before`, parents first
+fn recreate_project(before: &CacheData, root: &SyncProject) -> Vec<Command> {
+    let mut commands = Vec::new();
+    let mut projects = vec![root];
+    let mut index = 0;
+    while let Some(p) = projects.get(index).copied() {
+        index += 1;
+        commands.push(recreate(
+            CommandKind::ProjectAdd(
+                ProjectAddArgs::new(p.name.clone())
+                    .parent_id(p.parent_id.clone())
+                    .color(Some(p.color.clone()).filter(|c| !c.is_empty()))
+                    .favorite(Some(p.favorite))
+                    .view_style(Some(p.view_style.clone()).filter(|v| !v.is_empty())),
+            ),
+            &p.id,
+        ));
+        let mut children: Vec<&SyncProject> = before
+            .projects
+            .iter()
+            .filter(|c| c.parent_id.as_deref() == Some(p.id.as_str()) && !c.is_deleted)
+            .filter(|c| !projects.iter().any(|known| known.id == c.id))
+            .collect();
+        children.sort_by_key(|c| c.sort_order);
+        projects.extend(children);
+    }
+
+    let in_projects = |id: Option<&str>| id.is_some_and(|id| projects.iter().any(|p| p.id == id));
+    let mut sections: Vec<&SyncSection> = before
+        .sections
+        .iter()
+        .filter(|s| in_projects(Some(&s.project_id)) && !s.is_deleted)
+        .collect();
+    sections.sort_by_key(|s| s.order);
+    commands.extend(sections.into_iter().map(|s| {
+        recreate(
+            CommandKind::SectionAdd(SectionAddArgs::new(s.name.clone(), s.project_id.clone())),
+            &s.id,
+        )
+    }));
+
+    let tasks: Vec<&SyncTask> = before
+        .items
+        .iter()
+        .filter(|t| in_projects(t.project_id.as_deref()) && !t.is_deleted)
+        .collect();
+    let roots = tasks
+        .iter()
+        .filter(|t| {
+            !t.parent_id
+                .as_ref()
+                .is_some_and(|p| tasks.iter().any(|o| &o.id == p))
+        })
+        .copied()
+        .collect();
+    commands.extend(recreate_tasks(before, roots));
+    commands
+}
+
+fn invert(
+    kind: &CommandKind,
+    before: &CacheData,
+    created_id: Option<String>,
+) -> Option<Vec<Command>> {
+    let task = |id: &str| before.items.iter().find(|t| t.id == id);
+    let project = |id: &str| before.projects.iter().find(|p| p.id == id);
+    let section = |id: &str| before.sections.iter().find(|s| s.id == id);
+    let label = |id: &str| before.labels.iter().find(|l| l.id == id);
+    let filter = |id: &str| before.filters.iter().find(|f| f.id == id);
+    let id_args = |id: &str| IdArgs::new(id);
+
+    let inverse = match kind {
+        CommandKind::ItemAdd(_) => CommandKind::ItemDelete(id_args(&created_id?)),
+        CommandKind::ProjectAdd(_) => CommandKind::ProjectDelete(id_args(&created_id?)),
+        CommandKind::SectionAdd(_) => CommandKind::SectionDelete(id_args(&created_id?)),
+        CommandKind::LabelAdd(_) => CommandKind::LabelDelete(id_args(&created_id?)),
+        CommandKind::FilterAdd(_) => CommandKind::FilterDelete(id_args(&created_id?)),
+
+        // Closing a recurring task moves it to its next occurrence instead
+        CommandKind::ItemClose(args)
+            if task(&args.id)
+                .and_then(|t| t.due.as_ref())
+                .is_some_and(|d| d.is_recurring) =>
+        {
+            return None;
+        }
+        CommandKind::ItemClose(args) | CommandKind::ItemComplete(args) => {
+            CommandKind::ItemUncomplete(args.clone())
+        }
+        CommandKind::ItemUncomplete(args) => CommandKind::ItemComplete(args.clone()),
+        CommandKind::ProjectArchive(args) => CommandKind::ProjectUnarchive(args.clone()),
+        CommandKind::ProjectUnarchive(args) => CommandKind::ProjectArchive(args.clone()),
+        CommandKind::SectionArchive(args) => CommandKind::SectionUnarchive(args.clone()),
+        CommandKind::SectionUnarchive(args) => CommandKind::SectionArchive(args.clone()),
+
+        CommandKind::ItemDelete(args) => {
+            return Some(recreate_tasks(before, vec![task(&args.id)?]))
+        }
+        CommandKind::ItemMove(args) => {
+            let t = task(&args.id)?;
+            CommandKind::ItemMove(ItemMoveArgs {
+                id: args.id.clone(),
+                project_id: t
+                    .section_id
+                    .is_none()
+                    .then(|| t.project_id.clone())
+                    .flatten(),
+                section_id: t.section_id.clone(),
+                parent_id: None,
+            })
+        }
+        CommandKind::ItemUpdate(args) => {
+            let t = task(&args.id)?;
+            let mut update = ItemUpdateArgs::new(args.id.clone());
+            if args.content.is_some() {
+                update.content = Some(t.content.clone());
+            }
+            if args.description.is_some() {
+                update.description = Some(t.description.clone().unwrap_or_default());
+            }
+            if args.priority.is_some() {
+                update.priority = Some(t.priority);
+            }
+            if args.labels.is_some() {
+                update.labels = Some(t.labels.clone());
+            }
+            if args.responsible_uid.is_some() {
+                update.responsible_uid = Some(t.responsible_uid.clone());
+            }
+            if args.duration.is_some() {
+                update.duration = Some(t.duration.clone().map(Into::into));
+            }
+            if args.due_string.is_some() || args.due_datetime.is_some() {
+                update.due_string = Some(
+                    t.due
+                        .as_ref()
+                        .and_then(SyncDue::to_due_string)
+                        .unwrap_or_else(|| "no date".to_string()),
+                );
+            }
+            CommandKind::ItemUpdate(update)
+        }
+
+        CommandKind::ProjectDelete(args) => {
+            return Some(recreate_project(before, project(&args.id)?))
+        }
+        CommandKind::ProjectUpdate(args) => {
+            let p = project(&args.id)?;
+            CommandKind::ProjectUpdate(ProjectUpdateArgs {
+                id: args.id.clone(),
+                name: args.name.as_ref().map(|_| p.name.clone()),
+                color: args.color.as_ref().map(|_| p.color.clone()),
+                favorite: args.favorite.map(|_| p.favorite),
+                view_style: args
+                    .view_style
+                    .as_ref()
+                    .map(|_| p.view_style.clone())
+                    .filter(|v| !v.is_empty()),
+            })
+        }
+        CommandKind::ProjectMove(args) => {
+            let p = project(&args.id)?;
+            CommandKind::ProjectMove(ProjectMoveArgs {
+                id: args.id.clone(),
+                parent_id: p.parent_id.clone(),
+            })
+        }
+        CommandKind::ProjectReorder(args) => {
+            let projects = args
+                .projects
+                .iter()
+                .map(|o| project(&o.id).map(|p| ProjectOrderArgs::new(p.id.clone(), p.sort_order)))
+                .collect::<Option<Vec<_>>>()?;
+            CommandKind::ProjectReorder(ProjectReorderArgs { projects })
+        }
+
+        CommandKind::SectionDelete(args) => {
+            let s = section(&args.id)?;
+            CommandKind::SectionAdd(SectionAddArgs::new(s.name.clone(), s.project_id.clone()))
+        }
+        CommandKind::SectionUpdate(args) => {
+            let s = section(&args.id)?;
+            CommandKind::SectionUpdate(SectionUpdateArgs {
+                id: args.id.clone(),
+                name: s.name.clone(),
+            })
+        }
+        CommandKind::SectionMove(args) => {
+            let s = section(&args.id)?;
+            CommandKind::SectionMove(SectionMoveArgs {
+                id: args.id.clone(),
+                project_id: s.project_id.clone(),
+            })
+        }
+        CommandKind::SectionReorder(args) => {
+            let sections = args
+                .sections
+                .iter()
+                .map(|o| section(&o.id).map(|s| SectionOrderArgs::new(s.id.clone(), s.order)))
+                .collect::<Option<Vec<_>>>()?;
+            CommandKind::SectionReorder(SectionReorderArgs { sections })
+        }
+
+        CommandKind::LabelDelete(args) => {
+            let l = label(&args.id)?;
+            CommandKind::LabelAdd(LabelAddArgs::new(l.name.clone()).color(Some(l.color.clone())))
+        }
+        CommandKind::LabelUpdate(args) => {
+            let l = label(&args.id)?;
+            CommandKind::LabelUpdate(LabelUpdateArgs {
+                id: args.id.clone(),
+                name: args.name.as_ref().map(|_| l.name.clone()),
+                color: args.color.as_ref().map(|_| l.color.clone()),
+            })
+        }
+
+        CommandKind::FilterDelete(args) => {
+            let f = filter(&args.id)?;
+            CommandKind::FilterAdd(FilterAddArgs::new(f.name.clone(), f.query.clone()))
+        }
+        CommandKind::FilterUpdate(args) => {
+            let f = filter(&args.id)?;
+            CommandKind::FilterUpdate(FilterUpdateArgs {
+                id: args.id.clone(),
+                name: args.name.as_ref().map(|_| f.name.clone()),
+                query: args.query.as_ref().map(|_| f.query.clone()),
+                color: None,
+            })
+        }
+        CommandKind::FilterUpdateOrders(_) | CommandKind::NoteAdd(_) | CommandKind::Other(_) => {
+            return None
+        }
+    };
+    Some(vec![Command::new(inverse)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::CommandBuilder;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn before() -> CacheData {
+        serde_json::from_value(json!({
+            "projects": [{"id": "p1", "name": "Work", "color": "blue"}],
+            "items": [{
+                "id": "t1",
+                "content": "Write report",
+                "description": "Q3",
+                "project_id": "p1",
+                "section_id": "s1",
+                "priority": 3,
+                "labels": ["office"],
+                "due": {"date": "2026-01-20"}
+            }],
+            "sections": [],
+            "labels": [],
+            "filters": []
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_inverse_of_delete_recreates_task() {
+        let commands = CommandBuilder::new().item_delete("t1").build();
+        let (inverse, warnings) = inverse_commands(&commands, &before(), &HashMap::new());
+        assert!(warnings.is_empty());
+        match &inverse[0].kind {
+            CommandKind::ItemAdd(args) => {
+                assert_eq!(args.content, "Write report");
+                assert_eq!(args.section_id.as_deref(), Some("s1"));
+                assert_eq!(args.priority, Some(3));
+                assert_eq!(args.due_string.as_deref(), Some("2026-01-20"));
+                assert_eq!(args.labels, Some(vec!["office".to_string()]));
+            }
+            other => panic!("unexpected inverse {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_inverse_of_delete_recreates_subtasks() {
+        let mut data = before();
+        data.items.extend(
+            serde_json::from_value::<Vec<SyncTask>>(json!([
+                {"id": "t2", "content": "Outline", "project_id": "p1", "parent_id": "t1", "child_order": 2},
+                {"id": "t3", "content": "Draft", "project_id": "p1", "parent_id": "t1", "child_order": 1, "checked": true},
+                {"id": "t4", "content": "Intro", "project_id": "p1", "parent_id": "t3"}
+            ]))
+            .unwrap(),
+        );
+
+        // Deleting a subtask too does not recreate it twice
+        let commands = CommandBuilder::new()
+            .item_delete("t1")
+            .item_delete("t3")
+            .build();
+        let (inverse, warnings) = inverse_commands(&commands, &data, &HashMap::new());
+        assert!(warnings.is_empty());
+        let summary: Vec<_> = inverse
+            .iter()
+            .map(|c| {
+                let args = serde_json::to_value(c).unwrap()["args"].clone();
+                (
+                    c.command_type().to_string(),
+                    c.temp_id.clone(),
+                    args["parent_id"].as_str().map(String::from),
+                    args["id"].as_str().map(String::from),
+                )
+            })
+            .collect();
+        let add = |id: &str, parent: Option<&str>| {
+            (
+                "item_add".to_string(),
+                Some(id.to_string()),
+                parent.map(String::from),
+                None,
+            )
+        };
+        assert_eq!(
+            summary,
+            vec![
+                add("t1", None),
+                add("t3", Some("t1")),
+                add("t2", Some("t1")),
+                add("t4", Some("t3")),
+                (
+                    "item_complete".to_string(),
+                    None,
+                    None,
+                    Some("t3".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_inverse_of_project_delete_recreates_contents() {
+        let mut data = before();
+        data.projects.push(
+            serde_json::from_value(json!({"id": "p2", "name": "Team", "parent_id": "p1"})).unwrap(),
+        );
+        data.sections = serde_json::from_value(json!([
+            {"id": "s1", "project_id": "p1", "name": "Doing", "order": 1, "created_at": ""},
+            {"id": "s2", "project_id": "p3", "name": "Elsewhere", "order": 1, "created_at": ""}
+        ]))
+        .unwrap();
+        data.items.push(
+            serde_json::from_value(json!({"id": "t2", "content": "Plan", "project_id": "p2"}))
+                .unwrap(),
+        );
+
+        let commands = CommandBuilder::new().project_delete("p1").build();
+        let (inverse, warnings) = inverse_commands(&commands, &data, &HashMap::new());
+        let order: Vec<_> = inverse
+            .iter()
+            .map(|c| {
+                format!(
+                    "{} {}",
+                    c.command_type(),
+                    c.temp_id.as_deref().unwrap_or("")
+                )
+            })
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                "project_add p1",
+                "project_add p2",
+                "section_add s1",
+                "item_add t1",
+                "item_add t2"
+            ]
+        );
+        // Recreated resources refer to each other by their old IDs
+        assert_eq!(
+            serde_json::to_value(&inverse[1]).unwrap()["args"]["parent_id"],
+            "p1"
+        );
+        assert_eq!(
+            serde_json::to_value(&inverse[3]).unwrap()["args"]["section_id"],
+            "s1"
+        );
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("comments"), "{:?}", warnings);
+    }
+
+    #[test]
+    fn test_journal_loads_entries_without_temp_ids() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("journal.json");
+        std::fs::write(
+            &path,
+            json!([{
+                "id": 1,
+                "recorded_at": 0,
+                "summary": "item_delete",
+                "commands": [{"type": "item_delete", "uuid": "u1", "args": {"id": "t1"}}],
+                "inverse": [{"type": "item_add", "args": {"content": "Write report"}}]
+            }])
+            .to_string(),
+        )
+        .unwrap();
+        let entries = Journal::with_path(path).load().unwrap();
+        assert_eq!(entries[0].inverse[0].command_type(), "item_add");
+    }
+
+    #[test]
+    fn test_inverse_runs_in_reverse_order() {
+        let commands = CommandBuilder::new()
+            .item_move("t1", "p2", None)
+            .item_close("t1")
+            .build();
+        let (inverse, _) = inverse_commands(&commands, &before(), &HashMap::new());
+        assert_eq!(inverse[0].command_type(), "item_uncomplete");
+        match &inverse[1].kind {
+            CommandKind::ItemMove(args) => {
+                assert_eq!(args.section_id.as_deref(), Some("s1"));
+                assert!(args.project_id.is_none());
+            }
+            other => panic!("unexpected inverse {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_inverse_of_add_uses_temp_id_mapping() {
+        let commands = CommandBuilder::new()
+            .item_add(ItemAddArgs::new("New".to_string()))
+            .build();
+        let mapping = HashMap::from([(commands[0].temp_id.clone().unwrap(), "t9".to_string())]);
+        let (inverse, _) = inverse_commands(&commands, &CacheData::default(), &mapping);
+        match &inverse[0].kind {
+            CommandKind::ItemDelete(args) => assert_eq!(args.id, "t9"),
+            other => panic!("unexpected inverse {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_inverse_of_update_restores_changed_fields_only() {
+        let commands = CommandBuilder::new()
+            .item_update(ItemUpdateArgs::new("t1".to_string()).priority(Some(4)))
+            .build();
+        let (inverse, _) = inverse_commands(&commands, &before(), &HashMap::new());
+        assert_eq!(
+            serde_json::to_value(&inverse[0]).unwrap()["args"],
+            json!({"id": "t1", "priority": 3})
+        );
+    }
+
+    #[test]
+    fn test_inverse_of_assign_unassigns() {
+        let commands = CommandBuilder::new()
+            .item_update(
+                ItemUpdateArgs::new("t1".to_string()).responsible_uid(Some(Some("u2".to_string()))),
+            )
+            .build();
+        let (inverse, _) = inverse_commands(&commands, &before(), &HashMap::new());
+        assert_eq!(
+            serde_json::to_value(&inverse[0]).unwrap()["args"],
+            json!({"id": "t1", "responsible_uid": null})
+        );
+    }
+
+    #[test]
+    fn test_recurring_tasks_keep_their_recurrence() {
+        let mut data = before();
+        data.items[0].due = serde_json::from_value(
+            json!({"date": "2026-01-20", "is_recurring": true, "string": "every tuesday"}),
+        )
+        .unwrap();
+
+        // Closing only advanced the due date, there is nothing to reopen
+        let commands = CommandBuilder::new().item_close("t1").build();
+        let (inverse, warnings) = inverse_commands(&commands, &data, &HashMap::new());
+        assert!(inverse.is_empty());
+        assert_eq!(warnings.len(), 1);
+
+        let commands = CommandBuilder::new().item_delete("t1").build();
+        let (inverse, _) = inverse_commands(&commands, &data, &HashMap::new());
+        match &inverse[0].kind {
+            CommandKind::ItemAdd(args) => {
+                assert_eq!(args.due_string.as_deref(), Some("every tuesday"))
+            }
+            other => panic!("unexpected inverse {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unknown_previous_state_is_a_warning() {
+        let commands = CommandBuilder::new().project_delete("missing").build();
+        let (inverse, warnings) = inverse_commands(&commands, &before(), &HashMap::new());
+        assert!(inverse.is_empty());
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_journal_record_and_undoable() {
+        let temp_dir = TempDir::new().unwrap();
+        let journal = Journal::with_path(temp_dir.path().join("journal.json"));
+
+        let first = CommandBuilder::new().item_close("t1").build();
+        let second = CommandBuilder::new()
+            .item_delete("a")
+            .item_delete("b")
+            .build();
+        journal.record(&first, vec![], vec![]).unwrap();
+        let entry = journal.record(&second, vec![], vec![]).unwrap();
+        assert_eq!(entry.id, 2);
+        assert_eq!(entry.summary, "item_delete x2");
+
+        journal.mark_undone(2).unwrap();
+        let undoable = journal.undoable(5).unwrap();
+        assert_eq!(undoable.len(), 1);
+        assert_eq!(undoable[0].id, 1);
+    }
+}
@@ -6,13 +6,22 @@
 mod cache;
 mod client;
 mod commands;
+mod journal;
 mod models;
+mod preview;
 
-pub use cache::{Cache, CacheManager};
+pub use cache::{Cache, CacheData, CacheManager};
 
-pub use client::{CacheStatus, TodoistSyncClient};
+pub use client::{CacheStatus, ChunkedOutcome, TodoistSyncClient, MAX_COMMANDS_PER_REQUEST};
 pub use commands::{
-    Command, CommandBuilder, FilterAddArgs, FilterOrderArgs, ItemAddArgs, ItemUpdateArgs,
-    LabelAddArgs, ProjectAddArgs, SectionAddArgs,
+    batch_schema, parse_batch, parse_batch_allowing_unknown, Command, CommandBuilder, CommandKind,
+    DurationArgs, DurationUnit, FilterAddArgs, FilterOrderArgs, IdArgs, ItemAddArgs,
+    ItemUpdateArgs, LabelAddArgs, NoteAddArgs, ProjectAddArgs, ProjectOrderArgs, ProjectUpdateArgs,
+    SectionAddArgs, VIEW_STYLES,
 };
-pub use models::{SyncDue, SyncFilter, SyncLabel, SyncProject, SyncSection, SyncTask};
+pub use journal::{Journal, JournalEntry};
+pub use models::{
+    SyncCollaborator, SyncCollaboratorState, SyncDue, SyncFilter, SyncLabel, SyncNote, SyncProject,
+    SyncSection, SyncTask, SyncUser,
+};
+pub use preview::{predict_changes, CacheChange};
@@ -6,7 +6,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-use crate::models::{Due, Project, Task};
+use crate::models::{Collaborator, Due, Duration, Project, Task};
 
 /// Sync API 读取响应
 #[derive(Debug, Deserialize, Serialize)]
@@ -24,6 +24,22 @@ pub struct SyncReadResponse {
     pub labels: Vec<SyncLabel>,
     #[serde(default)]
     pub filters: Vec<SyncFilter>,
+    #[serde(default)]
+    pub collaborators: Vec<SyncCollaborator>,
+    #[serde(default)]
+    pub collaborator_states: Vec<SyncCollaboratorState>,
+    #[serde(default)]
+    pub user: Option<SyncUser>,
+    #[serde(default)]
+    pub notes: Vec<SyncNote>,
+}
+
+/// 分页的资源列表 (如 `/projects/archived`)
+#[derive(Debug, Deserialize)]
+pub struct Page<T> {
+    pub results: Vec<T>,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
 }
 
 /// Sync API 写入响应
@@ -42,6 +58,8 @@ pub struct SyncProject {
     pub id: String,
     pub name: String,
     #[serde(default)]
+    pub parent_id: Option<String>,
+    #[serde(default)]
     pub color: String,
     #[serde(default, alias = "is_shared")]
     pub shared: bool,
@@ -49,6 +67,11 @@ pub struct SyncProject {
     pub favorite: bool,
     #[serde(default, alias = "child_order")]
     pub sort_order: i64,
+    /// "list", "board" or "calendar"
+    #[serde(default)]
+    pub view_style: String,
+    #[serde(default, alias = "is_inbox_project")]
+    pub inbox_project: bool,
     #[serde(default)]
     pub is_archived: bool,
     #[serde(default)]
@@ -67,6 +90,9 @@ pub struct SyncTask {
     pub project_id: Option<String>,
     #[serde(default)]
     pub section_id: Option<String>,
+    /// 父任务 ID (子任务)
+    #[serde(default)]
+    pub parent_id: Option<String>,
     pub content: String,
     #[serde(default)]
     pub description: Option<String>,
@@ -76,12 +102,21 @@ pub struct SyncTask {
     pub due: Option<SyncDue>,
     #[serde(default)]
     pub labels: Vec<String>,
+    /// 被指派的协作者 ID
+    #[serde(default)]
+    pub responsible_uid: Option<String>,
+    /// 预计时长，如 `{"amount": 45, "unit": "minute"}`
+    #[serde(default)]
+    pub duration: Option<Duration>,
     #[serde(default, alias = "child_order")]
     pub order: i64,
     #[serde(default)]
     pub indentation: i64,
     #[serde(default, alias = "checked")]
     pub is_completed: bool,
+    /// 完成时间
+    #[serde(default)]
+    pub completed_at: Option<String>,
     #[serde(default)]
     pub is_archived: bool,
     #[serde(default)]
@@ -110,6 +145,18 @@ pub struct SyncSection {
     pub is_collapsed: Option<bool>,
 }
 
+/// Sync 评论 (任务备注)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SyncNote {
+    pub id: String,
+    pub item_id: String,
+    pub content: String,
+    #[serde(default)]
+    pub posted_at: String,
+    #[serde(default)]
+    pub is_deleted: bool,
+}
+
 /// Sync 标签
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct SyncLabel {
@@ -118,6 +165,8 @@ pub struct SyncLabel {
     pub color: String,
     #[serde(default)]
     pub is_favorite: bool,
+    #[serde(default)]
+    pub is_deleted: bool,
 }
 
 /// Sync 过滤器
@@ -128,6 +177,40 @@ pub struct SyncFilter {
     pub query: String,
 }
 
+/// Sync 协作者 (共享项目中的其他用户)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SyncCollaborator {
+    pub id: String,
+    #[serde(default)]
+    pub email: String,
+    #[serde(default)]
+    pub full_name: String,
+    #[serde(default)]
+    pub timezone: Option<String>,
+}
+
+/// Sync 协作者在某个项目中的状态
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SyncCollaboratorState {
+    pub project_id: String,
+    pub user_id: String,
+    /// "active", "invited" or "deleted"
+    #[serde(default)]
+    pub state: String,
+    #[serde(default)]
+    pub is_deleted: bool,
+}
+
+/// Sync 当前用户
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SyncUser {
+    pub id: String,
+    #[serde(default)]
+    pub email: String,
+    #[serde(default)]
+    pub full_name: String,
+}
+
 /// Sync 截止日期
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct SyncDue {
@@ -137,6 +220,22 @@ pub struct SyncDue {
     pub datetime: Option<String>,
     #[serde(default)]
     pub timezone: Option<String>,
+    /// 用户输入的日期描述，如 "every monday"
+    #[serde(default)]
+    pub string: Option<String>,
+    #[serde(default)]
+    pub lang: Option<String>,
+}
+
+impl SyncDue {
+    /// 重建该日期的 due_string：循环任务用原描述，否则用日期
+    pub fn to_due_string(&self) -> Option<String> {
+        self.string
+            .clone()
+            .filter(|_| self.is_recurring)
+            .or_else(|| self.datetime.clone())
+            .or_else(|| self.date.clone())
+    }
 }
 
 // ==================== 类型转换 ====================
@@ -146,9 +245,23 @@ impl From<SyncProject> for Project {
         Self {
             id: sync.id,
             name: sync.name,
+            parent_id: sync.parent_id,
             color: sync.color,
             is_shared: sync.shared,
             is_favorite: sync.favorite,
+            view_style: sync.view_style,
+            is_inbox_project: sync.inbox_project,
+        }
+    }
+}
+
+impl From<SyncCollaborator> for Collaborator {
+    fn from(sync: SyncCollaborator) -> Self {
+        Self {
+            id: sync.id,
+            name: sync.full_name,
+            email: sync.email,
+            state: None,
         }
     }
 }
@@ -160,16 +273,21 @@ impl From<SyncTask> for Task {
             content: sync.content,
             description: sync.description,
             project_id: sync.project_id,
+            section_id: sync.section_id,
             due: sync.due.map(|d| Due {
                 date: d.date,
                 is_recurring: Some(d.is_recurring),
                 datetime: d.datetime,
+                string: d.string,
             }),
             is_completed: sync.is_completed,
+            completed_at: sync.completed_at,
             created_at: sync.created_at,
             order: sync.order as i32,
             priority: sync.priority,
             labels: sync.labels,
+            responsible_uid: sync.responsible_uid,
+            duration: sync.duration,
         }
     }
 }
@@ -189,6 +307,8 @@ mod tests {
         assert!(response.sections.is_empty());
         assert!(response.labels.is_empty());
         assert!(response.filters.is_empty());
+        assert!(response.collaborators.is_empty());
+        assert!(response.user.is_none());
     }
 
     #[test]
@@ -297,13 +417,17 @@ mod tests {
             "date": "2024-01-15",
             "is_recurring": true,
             "datetime": "2024-01-15T09:00:00",
-            "timezone": "UTC"
+            "timezone": "UTC",
+            "string": "every monday 9am",
+            "lang": "en"
         }"#;
 
         let due: SyncDue = serde_json::from_str(json).unwrap();
         assert_eq!(due.date, Some("2024-01-15".to_string()));
         assert!(due.is_recurring);
         assert_eq!(due.datetime, Some("2024-01-15T09:00:00".to_string()));
+        assert_eq!(due.string.as_deref(), Some("every monday 9am"));
+        assert_eq!(due.lang.as_deref(), Some("en"));
     }
 
     #[test]
@@ -323,10 +447,13 @@ mod tests {
         let sync_project = SyncProject {
             id: "p1".to_string(),
             name: "My Project".to_string(),
+            parent_id: Some("p0".to_string()),
             color: "green".to_string(),
             shared: true,
             favorite: false,
             sort_order: 10,
+            view_style: "board".to_string(),
+            inbox_project: false,
             is_archived: false,
             is_deleted: false,
             created_at: "2024-01-01T00:00:00Z".to_string(),
@@ -339,6 +466,8 @@ mod tests {
         assert_eq!(project.color, "green");
         assert!(project.is_shared);
         assert!(!project.is_favorite);
+        assert_eq!(project.parent_id.as_deref(), Some("p0"));
+        assert_eq!(project.view_style, "board");
     }
 
     #[test]
@@ -347,6 +476,7 @@ mod tests {
             id: "t1".to_string(),
             project_id: Some("p1".to_string()),
             section_id: None,
+            parent_id: None,
             content: "My Task".to_string(),
             description: Some("Task description".to_string()),
             priority: 4,
@@ -355,11 +485,16 @@ mod tests {
                 is_recurring: false,
                 datetime: None,
                 timezone: None,
+                string: None,
+                lang: None,
             }),
             labels: vec!["label1".to_string(), "label2".to_string()],
+            responsible_uid: Some("u2".to_string()),
+            duration: Some(Duration::minutes(45)),
             order: 5,
             indentation: 0,
             is_completed: false,
+            completed_at: None,
             is_archived: false,
             is_deleted: false,
             created_at: "2024-01-01T00:00:00Z".to_string(),
@@ -372,6 +507,8 @@ mod tests {
         assert_eq!(task.priority, 4);
         assert!(!task.is_completed);
         assert_eq!(task.labels.len(), 2);
+        assert_eq!(task.responsible_uid.as_deref(), Some("u2"));
+        assert_eq!(task.duration, Some(Duration::minutes(45)));
     }
 
     #[test]
@@ -0,0 +1,265 @@
+//! # Dry-run Preview
+//!
+//! Predicts how a list of commands would change the synced resources,
+//! without sending anything to Todoist.
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use super::cache::CacheData;
+use super::commands::{Command, CommandKind};
+
+/// Predicted change to a single resource
+#[derive(Debug, Serialize)]
+pub struct CacheChange {
+    /// `add`, `update` or `delete`
+    pub action: &'static str,
+    /// `task`, `project`, `section`, `label`, `filter` or `comment`
+    pub resource: &'static str,
+    /// Real ID, or the temp ID for new resources
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Changed fields as `{"from": ..., "to": ...}`
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    pub changes: Map<String, Value>,
+}
+
+/// Predicts the changes `commands` would make to `data`
+pub fn predict_changes(commands: &[Command], data: &CacheData) -> Vec<CacheChange> {
+    let mut changes = Vec::new();
+    for command in commands {
+        let args = serde_json::to_value(&command.kind)
+            .ok()
+            .and_then(|mut v| v.get_mut("args").map(Value::take))
+            .unwrap_or(Value::Null);
+
+        match &command.kind {
+            CommandKind::SectionReorder(args) => {
+                for s in &args.sections {
+                    changes.push(update(data, "section", &s.id, json!({ "order": s.order })));
+                }
+            }
+            CommandKind::FilterUpdateOrders(args) => {
+                for f in &args.filters {
+                    changes.push(update(
+                        data,
+                        "filter",
+                        &f.id,
+                        json!({ "item_order": f.order }),
+                    ));
+                }
+            }
+            kind if kind.creates_resource() => {
+                let id = command
+                    .temp_id
+                    .clone()
+                    .unwrap_or_else(|| command.uuid.clone());
+                let name = args
+                    .get("content")
+                    .or_else(|| args.get("name"))
+                    .and_then(Value::as_str)
+                    .map(str::to_string);
+                let changes_map = object(args)
+                    .into_iter()
+                    .map(|(k, v)| (k, json!({ "from": null, "to": v })))
+                    .collect();
+                changes.push(CacheChange {
+                    action: "add",
+                    resource: resource_of(kind),
+                    id,
+                    name,
+                    changes: changes_map,
+                });
+            }
+            kind => {
+                let id = args
+                    .get("id")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string();
+                let resource = resource_of(kind);
+                let change = match kind {
+                    CommandKind::ItemDelete(_)
+                    | CommandKind::ProjectDelete(_)
+                    | CommandKind::SectionDelete(_)
+                    | CommandKind::LabelDelete(_)
+                    | CommandKind::FilterDelete(_) => CacheChange {
+                        action: "delete",
+                        resource,
+                        name: current(data, resource, &id).and_then(|c| display_name(&c)),
+                        id,
+                        changes: Map::new(),
+                    },
+                    CommandKind::ItemClose(_) | CommandKind::ItemComplete(_) => {
+                        update(data, resource, &id, json!({ "is_completed": true }))
+                    }
+                    CommandKind::ItemUncomplete(_) => {
+                        update(data, resource, &id, json!({ "is_completed": false }))
+                    }
+                    CommandKind::ProjectArchive(_) | CommandKind::SectionArchive(_) => {
+                        update(data, resource, &id, json!({ "is_archived": true }))
+                    }
+                    CommandKind::ProjectUnarchive(_) | CommandKind::SectionUnarchive(_) => {
+                        update(data, resource, &id, json!({ "is_archived": false }))
+                    }
+                    _ => update(data, resource, &id, args),
+                };
+                changes.push(change);
+            }
+        }
+    }
+    changes
+}
+
+fn update(data: &CacheData, resource: &'static str, id: &str, to: Value) -> CacheChange {
+    let before = current(data, resource, id);
+    let changes = object(to)
+        .into_iter()
+        .filter(|(field, _)| field != "id")
+        .map(|(field, to)| {
+            let from = before
+                .as_ref()
+                .map(|b| field_value(b, &field))
+                .unwrap_or(Value::Null);
+            (field, json!({ "from": from, "to": to }))
+        })
+        .filter(|(_, change)| change["from"] != change["to"])
+        .collect();
+    CacheChange {
+        action: "update",
+        resource,
+        id: id.to_string(),
+        name: before.as_ref().and_then(display_name),
+        changes,
+    }
+}
+
+fn resource_of(kind: &CommandKind) -> &'static str {
+    let command_type = kind.command_type();
+    match command_type.split('_').next() {
+        Some("item") => "task",
+        Some("project") => "project",
+        Some("section") => "section",
+        Some("label") => "label",
+        Some("note") => "comment",
+        _ => "filter",
+    }
+}
+
+fn current(data: &CacheData, resource: &str, id: &str) -> Option<Value> {
+    let found = match resource {
+        "task" => data
+            .items
+            .iter()
+            .find(|t| t.id == id)
+            .and_then(|t| serde_json::to_value(t).ok()),
+        "project" => data
+            .projects
+            .iter()
+            .find(|p| p.id == id)
+            .and_then(|p| serde_json::to_value(p).ok()),
+        "section" => data
+            .sections
+            .iter()
+            .find(|s| s.id == id)
+            .and_then(|s| serde_json::to_value(s).ok()),
+        "label" => data
+            .labels
+            .iter()
+            .find(|l| l.id == id)
+            .and_then(|l| serde_json::to_value(l).ok()),
+        _ => data
+            .filters
+            .iter()
+            .find(|f| f.id == id)
+            .and_then(|f| serde_json::to_value(f).ok()),
+    };
+    found
+}
+
+/// Reads the cached value matching a command argument
+fn field_value(resource: &Value, field: &str) -> Value {
+    match field {
+        "due_string" => resource["due"]["date"].clone(),
+        _ => resource.get(field).cloned().unwrap_or(Value::Null),
+    }
+}
+
+fn display_name(resource: &Value) -> Option<String> {
+    resource
+        .get("content")
+        .or_else(|| resource.get("name"))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+fn object(value: Value) -> Map<String, Value> {
+    match value {
+        Value::Object(map) => map,
+        _ => Map::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{CommandBuilder, ItemAddArgs, ItemUpdateArgs};
+
+    fn data() -> CacheData {
+        serde_json::from_value(json!({
+            "projects": [{"id": "p1", "name": "Work"}],
+            "items": [{
+                "id": "t1",
+                "content": "Write report",
+                "priority": 1,
+                "project_id": "p1",
+                "labels": []
+            }],
+            "sections": [],
+            "labels": [],
+            "filters": []
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_predict_update_shows_before_and_after() {
+        let commands = CommandBuilder::new()
+            .item_update(
+                ItemUpdateArgs::new("t1".to_string())
+                    .content(Some("Write report".to_string()))
+                    .priority(Some(4)),
+            )
+            .build();
+        let changes = predict_changes(&commands, &data());
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].action, "update");
+        assert_eq!(changes[0].name.as_deref(), Some("Write report"));
+        // Unchanged content is not reported
+        assert_eq!(
+            Value::Object(changes[0].changes.clone()),
+            json!({"priority": {"from": 1, "to": 4}})
+        );
+    }
+
+    #[test]
+    fn test_predict_add_and_delete() {
+        let commands = CommandBuilder::new()
+            .item_add(ItemAddArgs::new("New task".to_string()))
+            .project_delete("p1")
+            .item_complete("t1")
+            .build();
+        let changes = predict_changes(&commands, &data());
+
+        assert_eq!(changes[0].action, "add");
+        assert_eq!(changes[0].id, commands[0].temp_id.clone().unwrap());
+        assert_eq!(changes[0].changes["content"]["to"], "New task");
+
+        assert_eq!(changes[1].action, "delete");
+        assert_eq!(changes[1].resource, "project");
+        assert_eq!(changes[1].name.as_deref(), Some("Work"));
+
+        assert_eq!(changes[2].changes["is_completed"]["to"], true);
+    }
+}
@@ -0,0 +1,250 @@
+//! # Table
+//!
+//! Renders rows as aligned columns for reading at a terminal. Columns are
+//! shrunk to fit the terminal width, cutting long values with `…`, and cells
+//! are colored only when stdout is a terminal and `NO_COLOR` is not set.
+
+use crate::models::Duration;
+use serde_json::Value;
+use std::io::IsTerminal;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+const GAP: &str = "  ";
+/// Columns are never shrunk below this width
+const MIN_WIDTH: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Color {
+    Red,
+    Yellow,
+    Blue,
+    Dim,
+}
+
+impl Color {
+    fn code(self) -> &'static str {
+        match self {
+            Color::Red => "31",
+            Color::Yellow => "33",
+            Color::Blue => "34",
+            Color::Dim => "2",
+        }
+    }
+}
+
+/// How a table is laid out on the current output
+#[derive(Debug, Clone, Copy)]
+pub struct RenderOptions {
+    /// Maximum line width, `None` for no limit
+    pub width: Option<usize>,
+    pub color: bool,
+}
+
+impl RenderOptions {
+    /// Terminal width and colors for stdout; no limit and no colors when piped
+    pub fn detect() -> Self {
+        let tty = std::io::stdout().is_terminal();
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        Self {
+            width: terminal_size::terminal_size().map(|(w, _)| w.0 as usize),
+            color: tty && !no_color,
+        }
+    }
+}
+
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<(String, Option<Color>)>>,
+}
+
+impl Table {
+    /// Builds a table from serialized rows, one column per key.
+    /// `color` picks the color of a cell from its row and column key.
+    pub fn from_rows(
+        rows: &[Value],
+        columns: &[&str],
+        color: impl Fn(&Value, &str) -> Option<Color>,
+    ) -> Self {
+        Self {
+            headers: columns.iter().map(|c| c.to_uppercase()).collect(),
+            rows: rows
+                .iter()
+                .map(|row| {
+                    columns
+                        .iter()
+                        .map(|&key| {
+                            let value = row.get(key).unwrap_or(&Value::Null);
+                            (cell_text(value), color(row, key))
+                        })
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    pub fn render(&self, options: RenderOptions) -> String {
+        let widths = self.column_widths(options.width);
+
+        let mut lines = Vec::with_capacity(self.rows.len() + 1);
+        let headers: Vec<_> = self.headers.iter().map(|h| (h.clone(), None)).collect();
+        lines.push(render_line(&headers, &widths, options.color, true));
+        for row in &self.rows {
+            lines.push(render_line(row, &widths, options.color, false));
+        }
+        lines.join("\n")
+    }
+
+    /// Natural column widths, narrowing the widest column until the line fits
+    fn column_widths(&self, max: Option<usize>) -> Vec<usize> {
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| h.width()).collect();
+        for row in &self.rows {
+            for (width, (text, _)) in widths.iter_mut().zip(row) {
+                *width = (*width).max(text.width());
+            }
+        }
+
+        if let Some(max) = max {
+            let gaps = GAP.len() * widths.len().saturating_sub(1);
+            while widths.iter().sum::<usize>() + gaps > max {
+                let widest = widths.iter_mut().max().filter(|w| **w > MIN_WIDTH);
+                match widest {
+                    Some(w) => *w -= 1,
+                    None => break,
+                }
+            }
+        }
+        widths
+    }
+}
+
+fn render_line(
+    cells: &[(String, Option<Color>)],
+    widths: &[usize],
+    color: bool,
+    header: bool,
+) -> String {
+    let last = cells.len().saturating_sub(1);
+    let line = cells
+        .iter()
+        .zip(widths)
+        .enumerate()
+        .map(|(i, ((text, cell_color), &width))| {
+            let text = truncate(text, width);
+            // No trailing spaces after the last column
+            let padding = if i == last {
+                String::new()
+            } else {
+                " ".repeat(width - text.width())
+            };
+            let code = if header {
+                Some("1")
+            } else {
+                cell_color.map(Color::code)
+            };
+            match code {
+                Some(code) if color => format!("\x1b[{}m{}\x1b[0m{}", code, text, padding),
+                _ => format!("{}{}", text, padding),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(GAP);
+    line.trim_end().to_string()
+}
+
+/// Cuts `text` to `width` display columns, ending with `…` when shortened
+fn truncate(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+    let mut out = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w + 1 > width {
+            break;
+        }
+        out.push(c);
+        used += w;
+    }
+    out.push('…');
+    out
+}
+
+/// Text of a cell: strings as is, lists comma separated, durations as `1h30m`
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.replace('\n', " "),
+        Value::Bool(true) => "yes".to_string(),
+        Value::Bool(false) => String::new(),
+        Value::Array(items) => items.iter().map(cell_text).collect::<Vec<_>>().join(", "),
+        Value::Object(_) => match serde_json::from_value::<Duration>(value.clone()) {
+            Ok(duration) => duration.to_string(),
+            Err(_) => value.to_string(),
+        },
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn plain(width: Option<usize>) -> RenderOptions {
+        RenderOptions {
+            width,
+            color: false,
+        }
+    }
+
+    fn rows() -> Vec<Value> {
+        vec![
+            json!({"id": "1", "content": "Buy milk", "labels": ["home", "errand"]}),
+            json!({"id": "22", "content": "Write the quarterly report", "labels": []}),
+        ]
+    }
+
+    #[test]
+    fn test_render_aligns_columns() {
+        let table = Table::from_rows(&rows(), &["id", "content", "labels"], |_, _| None);
+        assert_eq!(
+            table.render(plain(None)),
+            "ID  CONTENT                     LABELS\n\
+             1   Buy milk                    home, errand\n\
+             22  Write the quarterly report"
+        );
+    }
+
+    #[test]
+    fn test_render_truncates_to_width() {
+        let table = Table::from_rows(&rows(), &["id", "content"], |_, _| None);
+        let output = table.render(plain(Some(16)));
+        for line in output.lines() {
+            assert!(line.width() <= 16, "{:?}", line);
+        }
+        assert!(output.contains("Write the q…"));
+    }
+
+    #[test]
+    fn test_render_colors_cells() {
+        let table = Table::from_rows(&rows(), &["id", "content"], |row, key| {
+            (row["id"] == "1" && key == "content").then_some(Color::Red)
+        });
+        let colored = table.render(RenderOptions {
+            width: None,
+            color: true,
+        });
+        assert!(colored.contains("\x1b[1mID\x1b[0m"));
+        assert!(colored.contains("\x1b[31mBuy milk\x1b[0m"));
+        assert!(!table.render(plain(None)).contains('\x1b'));
+    }
+
+    #[test]
+    fn test_cell_text() {
+        assert_eq!(cell_text(&json!({"amount": 90, "unit": "minute"})), "1h30m");
+        assert_eq!(cell_text(&json!(true)), "yes");
+        assert_eq!(cell_text(&json!(null)), "");
+        assert_eq!(cell_text(&json!(3)), "3");
+    }
+}
@@ -0,0 +1,239 @@
+//! # Templates
+//!
+//! Renders output through [MiniJinja](https://docs.rs/minijinja) templates: loops,
+//! conditionals and filters over the same data as the JSON output. The checklist
+//! and structured formats are built-in templates; user templates are given with
+//! `--template`, `--template-string` or stored as `templates/<name>.j2` in the
+//! config directory and selected with `--format template:<name>`.
+//!
+//! Besides the MiniJinja built-ins, templates can use:
+//!
+//! - `date(format)`: formats a date or datetime, e.g. `{{ task.due_date | date("%b %d") }}`
+//! - `duration`: a duration as `1h30m`
+//! - `minutes`: a duration in minutes
+//! - `md_escape`: escapes words that Markdown sync reads as tags or markers,
+//!   e.g. `\#bug`
+
+use crate::error::{Result, TodoError};
+use crate::models::Duration;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime};
+use minijinja::{AutoEscape, Environment, ErrorKind, UndefinedBehavior};
+use serde::Deserialize;
+use serde_json::Value;
+use std::fmt::Write;
+use std::path::Path;
+
+/// Templates behind the checklist, structured and obsidian formats
+pub mod builtin {
+    pub const TASKS_CHECKLIST: &str = include_str!("templates/tasks_checklist.j2");
+    pub const TASKS_STRUCTURED: &str = include_str!("templates/tasks_structured.j2");
+    pub const TASKS_OBSIDIAN: &str = include_str!("templates/tasks_obsidian.j2");
+    pub const PROJECTS_CHECKLIST: &str = include_str!("templates/projects_checklist.j2");
+    pub const PROJECTS_STRUCTURED: &str = include_str!("templates/projects_structured.j2");
+    pub const SECTIONS_CHECKLIST: &str = include_str!("templates/sections_checklist.j2");
+    pub const SECTIONS_STRUCTURED: &str = include_str!("templates/sections_structured.j2");
+    pub const FILTERS_CHECKLIST: &str = include_str!("templates/filters_checklist.j2");
+    pub const FILTERS_STRUCTURED: &str = include_str!("templates/filters_structured.j2");
+    pub const LABELS_CHECKLIST: &str = include_str!("templates/labels_checklist.j2");
+    pub const LABELS_STRUCTURED: &str = include_str!("templates/labels_structured.j2");
+    pub const COLLABORATORS_CHECKLIST: &str = include_str!("templates/collaborators_checklist.j2");
+    pub const COLLABORATORS_STRUCTURED: &str =
+        include_str!("templates/collaborators_structured.j2");
+    pub const AGENDA_CHECKLIST: &str = include_str!("templates/agenda_checklist.j2");
+    pub const AGENDA_STRUCTURED: &str = include_str!("templates/agenda_structured.j2");
+}
+
+/// Builds the context of a template: the data under `name` (and `items` for lists)
+/// plus `today`. Null fields are left out, so they test false and render empty.
+pub fn context(name: &str, data: Value) -> Value {
+    let data = strip_nulls(data);
+    let mut context = serde_json::Map::new();
+    if data.is_array() {
+        context.insert("items".to_string(), data.clone());
+    }
+    context.insert(name.to_string(), data);
+    context.insert(
+        "today".to_string(),
+        Value::String(Local::now().format("%Y-%m-%d").to_string()),
+    );
+    Value::Object(context)
+}
+
+/// Leaves out null fields, recursively
+pub fn strip_nulls(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| (k, strip_nulls(v)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(strip_nulls).collect()),
+        other => other,
+    }
+}
+
+/// Renders `source` against `context`, without trailing newlines
+pub fn render(source: &str, context: Value) -> Result<String> {
+    let mut env = Environment::new();
+    env.set_undefined_behavior(UndefinedBehavior::Chainable);
+    env.set_auto_escape_callback(|_| AutoEscape::None);
+    env.add_filter("date", date);
+    env.add_filter("duration", duration);
+    env.add_filter("minutes", minutes);
+    env.add_filter("md_escape", |content: String| {
+        crate::markdown::escape_content(&content)
+    });
+    env.add_template("template", source)?;
+
+    let output = env.get_template("template")?.render(context)?;
+    Ok(output.trim_end_matches('\n').to_string())
+}
+
+/// Reads a template file given with `--template`
+pub fn load_file(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path)
+        .map_err(|e| TodoError::Template(format!("Cannot read template {}: {}", path.display(), e)))
+}
+
+/// Reads the named template `templates/<name>.j2` from the config directory
+pub fn load_named(name: &str) -> Result<String> {
+    if name.is_empty() || name.contains(['/', '\\']) {
+        return Err(TodoError::Template(format!(
+            "Invalid template name '{}'",
+            name
+        )));
+    }
+    let path = crate::config::config_dir()?
+        .join("templates")
+        .join(format!("{}.j2", name));
+    if !path.exists() {
+        return Err(TodoError::Template(format!(
+            "Template '{}' not found (expected {})",
+            name,
+            path.display()
+        )));
+    }
+    load_file(&path)
+}
+
+fn parse_datetime(value: &str) -> Option<NaiveDateTime> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return date.and_hms_opt(0, 0, 0);
+    }
+    if let Ok(utc) = DateTime::parse_from_rfc3339(value) {
+        return Some(utc.with_timezone(&Local).naive_local());
+    }
+    value.parse().ok()
+}
+
+/// `{{ value | date("%b %d") }}`; the format defaults to `%Y-%m-%d`
+fn date(value: String, format: Option<String>) -> std::result::Result<String, minijinja::Error> {
+    let datetime = parse_datetime(&value).ok_or_else(|| {
+        minijinja::Error::new(
+            ErrorKind::InvalidOperation,
+            format!("'{}' is not a date", value),
+        )
+    })?;
+    let mut out = String::new();
+    write!(
+        out,
+        "{}",
+        datetime.format(format.as_deref().unwrap_or("%Y-%m-%d"))
+    )
+    .map_err(|_| minijinja::Error::new(ErrorKind::InvalidOperation, "invalid date format"))?;
+    Ok(out)
+}
+
+fn to_duration(value: minijinja::Value) -> std::result::Result<Duration, minijinja::Error> {
+    Duration::deserialize(value).map_err(|e| {
+        minijinja::Error::new(ErrorKind::InvalidOperation, "not a duration").with_source(e)
+    })
+}
+
+/// `{{ task.duration | duration }}` -> `1h30m`
+fn duration(value: minijinja::Value) -> std::result::Result<String, minijinja::Error> {
+    Ok(to_duration(value)?.to_string())
+}
+
+/// `{{ task.duration | minutes }}` -> `90`
+fn minutes(value: minijinja::Value) -> std::result::Result<i64, minijinja::Error> {
+    Ok(to_duration(value)?.as_minutes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use serial_test::serial;
+
+    #[test]
+    fn test_render_loops_and_conditionals() {
+        let context = context(
+            "tasks",
+            json!([
+                {"content": "Buy milk", "due_date": "2026-01-20", "labels": ["home"]},
+                {"content": "Call Bea", "due_date": null, "labels": []}
+            ]),
+        );
+        let output = render(
+            "{% for task in tasks %}* {{ task.content }}\
+             {% if task.due_date %} ({{ task.due_date | date(\"%b %d\") }}){% endif %}\
+             {% if task.labels %} {{ task.labels | join(\",\") }}{% endif %}\n{% endfor %}",
+            context,
+        )
+        .unwrap();
+        assert_eq!(output, "* Buy milk (Jan 20) home\n* Call Bea");
+    }
+
+    #[test]
+    fn test_render_duration_filters() {
+        let context = context(
+            "task",
+            json!({"duration": {"amount": 90, "unit": "minute"}}),
+        );
+        assert_eq!(
+            render(
+                "{{ task.duration | duration }} / {{ task.duration | minutes }}",
+                context
+            )
+            .unwrap(),
+            "1h30m / 90"
+        );
+    }
+
+    #[test]
+    fn test_render_errors() {
+        let err = render("{% for task in tasks %}", context("tasks", json!([]))).unwrap_err();
+        assert!(matches!(err, TodoError::Template(_)));
+        let err = render("{{ 'soon' | date }}", context("tasks", json!([]))).unwrap_err();
+        assert!(err.to_string().contains("not a date"));
+    }
+
+    #[test]
+    fn test_date_filter() {
+        assert_eq!(date("2026-01-20".into(), None).unwrap(), "2026-01-20");
+        assert_eq!(
+            date("2026-01-20T09:30:00".into(), Some("%H:%M".into())).unwrap(),
+            "09:30"
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_load_named() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("templates")).unwrap();
+        std::fs::write(dir.path().join("templates/weekly.j2"), "{{ today }}").unwrap();
+        std::env::set_var("TODORUST_CONFIG_DIR", dir.path());
+
+        assert_eq!(load_named("weekly").unwrap(), "{{ today }}");
+        assert!(load_named("missing")
+            .unwrap_err()
+            .to_string()
+            .contains("not found"));
+        assert!(load_named("../config").is_err());
+
+        std::env::remove_var("TODORUST_CONFIG_DIR");
+    }
+}
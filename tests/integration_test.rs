@@ -51,6 +51,8 @@ async fn test_end_to_end_workflow() {
             None,
             Some(4),
             None,
+            None,
+            None,
         )
         .await
         .expect("Failed to create task");